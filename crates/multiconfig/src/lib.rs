@@ -39,6 +39,9 @@
 //!   - `Playlist` — `item_duration`, `crossfade`, optional `fps`/`antialias`, `items`.
 //!   - `PlaylistItem` — `handle`, optional `duration`/`fps`/`antialias`, `refresh_once`, `mode`.
 //!   - `PlaylistItemMode` — per-item `animate` or `still` with optional `still_time`.
+//!   - `StillTime` — a `still_time` duration, or `"auto"` to search for a keyframe.
+//!   - `LiveAudioRoute` — per-item `live_audio` table routing stdin/FIFO PCM onto an
+//!     `iChannel`; `PcmFormat` names its sample encoding.
 //!   - `AntialiasSetting` — AA policy mapped to `renderer` MSAA.
 //!   - `ResolvedItem` — merged view used by the runtime to schedule playback.
 //!   - `ConfigError` — parse/validation errors with actionable text.
@@ -50,9 +53,11 @@
 //! - Deserialization helpers
 //!   - `deserialize_duration[_opt]` — numerics or humantime strings.
 //!   - `deserialize_antialias_opt` — named levels or counts; see `parse_antialias`.
+//!   - `deserialize_still_time_opt` — durations like `deserialize_duration_opt`, or `"auto"`.
 //!
 use std::collections::BTreeMap;
 use std::fmt;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use serde::de::{self, Deserializer};
@@ -132,8 +137,42 @@ pub struct PlaylistItem {
     pub refresh_once: bool,
     #[serde(default)]
     pub mode: Option<PlaylistItemMode>,
-    #[serde(default, deserialize_with = "deserialize_duration_opt")]
-    pub still_time: Option<Duration>,
+    #[serde(default, deserialize_with = "deserialize_still_time_opt")]
+    pub still_time: Option<StillTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_audio: Option<LiveAudioRoute>,
+}
+
+/// Routes a live PCM stream (stdin or a FIFO) onto one `iChannel` of a playlist item,
+/// overriding whatever the shader pack binds there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveAudioRoute {
+    pub channel: u8,
+    /// Named pipe to read; stdin when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fifo: Option<PathBuf>,
+    #[serde(default = "default_live_sample_rate")]
+    pub sample_rate: u32,
+    #[serde(default = "default_live_channels")]
+    pub channels: u16,
+    #[serde(default)]
+    pub format: PcmFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PcmFormat {
+    #[default]
+    S16le,
+    F32le,
+}
+
+fn default_live_sample_rate() -> u32 {
+    44_100
+}
+
+fn default_live_channels() -> u16 {
+    2
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -143,6 +182,16 @@ pub enum PlaylistItemMode {
     Still,
 }
 
+/// Timestamp a `still` item is rendered at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StillTime {
+    /// Fixed offset into the shader's timeline.
+    At(Duration),
+    /// Let the renderer pick the most interesting frame.
+    Auto,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AntialiasSetting {
@@ -292,6 +341,38 @@ where
     Ok(result)
 }
 
+fn deserialize_still_time_opt<'de, D>(deserializer: D) -> Result<Option<StillTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Helper {
+        Str(String),
+        Int(i64),
+        Float(f64),
+    }
+
+    let helper: Option<Helper> = Option::deserialize(deserializer)?;
+    let seconds = match helper {
+        None => return Ok(None),
+        Some(Helper::Str(raw)) if raw.trim().eq_ignore_ascii_case("auto") => {
+            return Ok(Some(StillTime::Auto));
+        }
+        Some(Helper::Str(raw)) => {
+            return humantime::parse_duration(raw.trim())
+                .map(|duration| Some(StillTime::At(duration)))
+                .map_err(|err| de::Error::custom(format!("invalid still_time '{raw}': {err}")));
+        }
+        Some(Helper::Int(value)) => value as f64,
+        Some(Helper::Float(value)) => value,
+    };
+    if seconds.is_nan() || seconds.is_sign_negative() {
+        return Err(de::Error::custom("still_time must be non-negative"));
+    }
+    Ok(Some(StillTime::At(Duration::from_secs_f64(seconds))))
+}
+
 fn parse_antialias(raw: &str) -> Result<AntialiasSetting, String> {
     let normalized = raw.trim().to_ascii_lowercase();
     match normalized.as_str() {
@@ -390,6 +471,21 @@ impl MultiConfig {
                         )));
                     }
                 }
+
+                if let Some(live) = &item.live_audio {
+                    if live.channel > 3 {
+                        return Err(ConfigError::Invalid(format!(
+                            "playlist '{name}' item '{}' live_audio channel {} exceeds iChannel3",
+                            item.handle, live.channel
+                        )));
+                    }
+                    if live.sample_rate == 0 || live.channels == 0 {
+                        return Err(ConfigError::Invalid(format!(
+                            "playlist '{name}' item '{}' live_audio needs a non-zero sample_rate and channels",
+                            item.handle
+                        )));
+                    }
+                }
             }
         }
 
@@ -596,4 +692,95 @@ handle = "local/demo"
         assert_eq!(resolved.crossfade, Duration::from_secs_f32(1.0));
         assert!(!resolved.refresh_once);
     }
+
+    #[test]
+    fn parses_and_validates_live_audio_routes() {
+        let config = MultiConfig::from_toml_str(
+            r#"
+version = 1
+
+[playlists.main]
+mode = "continuous"
+
+[[playlists.main.items]]
+handle = "local/visualizer"
+live_audio = { channel = 2, fifo = "/tmp/wax11.fifo", sample_rate = 48000 }
+"#,
+        )
+        .unwrap();
+        let live = config.playlist("main").unwrap().items[0]
+            .live_audio
+            .as_ref()
+            .expect("live audio route");
+        assert_eq!(live.channel, 2);
+        assert_eq!(
+            live.fifo.as_deref(),
+            Some(std::path::Path::new("/tmp/wax11.fifo"))
+        );
+        assert_eq!(live.sample_rate, 48_000);
+        assert_eq!(live.channels, 2);
+        assert_eq!(live.format, PcmFormat::S16le);
+
+        let err = MultiConfig::from_toml_str(
+            r#"
+version = 1
+
+[playlists.main]
+mode = "continuous"
+
+[[playlists.main.items]]
+handle = "local/visualizer"
+live_audio = { channel = 4 }
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("live_audio channel 4"));
+    }
+
+    #[test]
+    fn still_time_accepts_durations_and_auto() {
+        let config = r#"
+version = 1
+
+[playlists.main]
+mode = "continuous"
+
+[[playlists.main.items]]
+handle = "shader://a"
+mode = "still"
+still_time = "auto"
+
+[[playlists.main.items]]
+handle = "shader://b"
+mode = "still"
+still_time = "2.5s"
+
+[[playlists.main.items]]
+handle = "shader://c"
+still_time = 4
+"#;
+        let config = MultiConfig::from_toml_str(config).expect("parse config");
+        let times: Vec<_> = config.playlists["main"]
+            .items
+            .iter()
+            .map(|item| item.still_time)
+            .collect();
+        assert_eq!(
+            times,
+            [
+                Some(StillTime::Auto),
+                Some(StillTime::At(Duration::from_millis(2500))),
+                Some(StillTime::At(Duration::from_secs(4))),
+            ]
+        );
+
+        let negative = config_with_still_time("-1");
+        assert!(MultiConfig::from_toml_str(&negative).is_err());
+    }
+
+    fn config_with_still_time(value: &str) -> String {
+        format!(
+            "version = 1\n[playlists.main]\nmode = \"continuous\"\n[[playlists.main.items]]\nhandle = \"shader://a\"\nstill_time = {value}\n"
+        )
+    }
 }
@@ -0,0 +1,326 @@
+//! Decoding and playback for animated texture channels.
+//!
+//! Animated GIF/APNG files and numbered image-sequence directories stand in
+//! for ShaderToy's video inputs without a video decoder. Every frame is
+//! decoded up front into RGBA8; the GPU channel code asks for the frame at the
+//! current shader time and uploads it only when it changes.
+//!
+//! Flow
+//!
+//! ```text
+//!   clip.gif / clip.png (APNG)        frames/0001.png, 0002.png, …
+//!        │  AnimationDecoder                 │  sorted by trailing number
+//!        └──────────────┬────────────────────┘
+//!                       ▼
+//!   AnimatedImage { frames, frame_ends } ── frame_at(seconds, playback)
+//!                                              │  repeat / once / ping-pong
+//!                                              ▼
+//!                                 (frame index, iChannelTime)
+//! ```
+//!
+//! Frame timing comes from the file's per-frame delays unless the channel sets
+//! an explicit `fps`; sequences default to [`DEFAULT_SEQUENCE_FPS`].
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::imageops::flip_vertical_in_place;
+use image::{AnimationDecoder, Frame, RgbaImage};
+
+use crate::types::{AnimationLoop, ChannelPlayback};
+
+/// Frame rate used for image sequences without an explicit `fps`.
+pub(crate) const DEFAULT_SEQUENCE_FPS: f32 = 30.0;
+
+/// Delay substituted for zero-length GIF/APNG frames, matching browser behaviour.
+const MIN_FRAME_DELAY: f32 = 0.02;
+
+const SEQUENCE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "bmp"];
+
+/// Fully decoded animation; every frame shares the same dimensions.
+pub(crate) struct AnimatedImage {
+    pub width: u32,
+    pub height: u32,
+    frames: Vec<Vec<u8>>,
+    /// Cumulative end time of each frame in seconds at the native rate.
+    frame_ends: Vec<f32>,
+}
+
+impl AnimatedImage {
+    /// Decodes a GIF/APNG file or a numbered image-sequence directory.
+    pub(crate) fn load(path: &Path, vflip: bool) -> Result<Self> {
+        let frames = if path.is_dir() {
+            decode_sequence(path)?
+        } else {
+            decode_animated_file(path)?
+        };
+        Self::from_frames(frames, vflip)
+            .with_context(|| format!("invalid animation at {}", path.display()))
+    }
+
+    fn from_frames(frames: Vec<(RgbaImage, f32)>, vflip: bool) -> Result<Self> {
+        let Some((first, _)) = frames.first() else {
+            bail!("animation has no frames");
+        };
+        let (width, height) = first.dimensions();
+        let mut pixels = Vec::with_capacity(frames.len());
+        let mut frame_ends = Vec::with_capacity(frames.len());
+        let mut elapsed = 0.0;
+        for (index, (mut frame, delay)) in frames.into_iter().enumerate() {
+            if frame.dimensions() != (width, height) {
+                bail!(
+                    "frame {index} is {}x{}, expected {width}x{height}",
+                    frame.width(),
+                    frame.height()
+                );
+            }
+            if vflip {
+                flip_vertical_in_place(&mut frame);
+            }
+            elapsed += delay;
+            frame_ends.push(elapsed);
+            pixels.push(frame.into_raw());
+        }
+        Ok(Self {
+            width,
+            height,
+            frames: pixels,
+            frame_ends,
+        })
+    }
+
+    pub(crate) fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub(crate) fn frame(&self, index: usize) -> &[u8] {
+        &self.frames[index]
+    }
+
+    /// Frame to show at shader time `seconds` and the playback position to report
+    /// through `iChannelTime`.
+    pub(crate) fn frame_at(&self, seconds: f32, playback: ChannelPlayback) -> (usize, f32) {
+        let count = self.frames.len();
+        let native = *self.frame_ends.last().unwrap_or(&0.0);
+        let duration = match playback.fps {
+            Some(fps) if fps > 0.0 => count as f32 / fps,
+            _ => native,
+        };
+        if count <= 1 || duration <= 0.0 {
+            return (0, 0.0);
+        }
+
+        let seconds = seconds.max(0.0);
+        let position = match playback.looping {
+            AnimationLoop::Repeat => seconds % duration,
+            AnimationLoop::Once => seconds.min(duration),
+            AnimationLoop::PingPong => {
+                let phase = seconds % (2.0 * duration);
+                if phase > duration {
+                    2.0 * duration - phase
+                } else {
+                    phase
+                }
+            }
+        };
+
+        let index = match playback.fps {
+            Some(fps) if fps > 0.0 => (position * fps) as usize,
+            _ => self.frame_ends.partition_point(|end| *end <= position),
+        };
+        (index.min(count - 1), position)
+    }
+}
+
+fn decode_animated_file(path: &Path) -> Result<Vec<(RgbaImage, f32)>> {
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("failed to open animation {}", path.display()))
+    };
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let frames = match extension.as_deref() {
+        Some("gif") => GifDecoder::new(open()?)?.into_frames().collect_frames(),
+        Some("png" | "apng") => {
+            let decoder = PngDecoder::new(open()?)?;
+            if !decoder.is_apng()? {
+                // A still PNG is a one-frame animation.
+                let image = image::open(path)
+                    .with_context(|| format!("failed to decode {}", path.display()))?;
+                return Ok(vec![(image.to_rgba8(), 0.0)]);
+            }
+            decoder.apng()?.into_frames().collect_frames()
+        }
+        _ => bail!(
+            "unsupported animation format for {} (expected .gif or .png)",
+            path.display()
+        ),
+    }
+    .with_context(|| format!("failed to decode animation frames from {}", path.display()))?;
+
+    Ok(frames.into_iter().map(frame_with_delay).collect())
+}
+
+fn frame_with_delay(frame: Frame) -> (RgbaImage, f32) {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    let delay = if denom == 0 {
+        0.0
+    } else {
+        numer as f32 / denom as f32 / 1000.0
+    };
+    (frame.into_buffer(), delay.max(MIN_FRAME_DELAY))
+}
+
+fn decode_sequence(directory: &Path) -> Result<Vec<(RgbaImage, f32)>> {
+    let files = sequence_files(directory)?;
+    if files.is_empty() {
+        bail!("no numbered images found in {}", directory.display());
+    }
+    files
+        .iter()
+        .map(|path| {
+            let image = image::open(path)
+                .with_context(|| format!("failed to decode sequence frame {}", path.display()))?;
+            Ok((image.to_rgba8(), 1.0 / DEFAULT_SEQUENCE_FPS))
+        })
+        .collect()
+}
+
+/// Image files in `directory` ordered by the number at the end of their stem.
+fn sequence_files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(directory)
+        .with_context(|| format!("failed to read image sequence {}", directory.display()))?;
+    let mut numbered = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let supported = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SEQUENCE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        if !supported || !path.is_file() {
+            continue;
+        }
+        if let Some(number) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(trailing_number)
+        {
+            numbered.push((number, path));
+        }
+    }
+    numbered.sort();
+    Ok(numbered.into_iter().map(|(_, path)| path).collect())
+}
+
+fn trailing_number(stem: &str) -> Option<u64> {
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    stem[stem.len() - digits..].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_frames(delays: &[f32]) -> AnimatedImage {
+        let frames = delays
+            .iter()
+            .enumerate()
+            .map(|(index, delay)| {
+                (
+                    RgbaImage::from_pixel(2, 2, image::Rgba([index as u8, 0, 0, 255])),
+                    *delay,
+                )
+            })
+            .collect();
+        AnimatedImage::from_frames(frames, true).expect("valid frames")
+    }
+
+    fn playback(fps: Option<f32>, looping: AnimationLoop) -> ChannelPlayback {
+        ChannelPlayback { fps, looping }
+    }
+
+    #[test]
+    fn follows_native_delays_and_loop_modes() {
+        let animation = solid_frames(&[0.1, 0.2, 0.1]);
+        let repeat = playback(None, AnimationLoop::Repeat);
+        assert_eq!(animation.frame_at(0.05, repeat).0, 0);
+        assert_eq!(animation.frame_at(0.25, repeat).0, 1);
+        assert_eq!(animation.frame_at(0.35, repeat).0, 2);
+        let (index, position) = animation.frame_at(0.45, repeat);
+        assert_eq!(index, 0);
+        assert!((position - 0.05).abs() < 1e-4);
+
+        let once = playback(None, AnimationLoop::Once);
+        let (index, position) = animation.frame_at(10.0, once);
+        assert_eq!(index, 2);
+        assert!((position - 0.4).abs() < 1e-4);
+
+        let ping_pong = playback(None, AnimationLoop::PingPong);
+        assert_eq!(animation.frame_at(0.75, ping_pong).0, 0);
+        assert_eq!(animation.frame_at(0.55, ping_pong).0, 1);
+    }
+
+    #[test]
+    fn explicit_fps_overrides_frame_delays() {
+        let animation = solid_frames(&[1.0, 1.0, 1.0, 1.0]);
+        let fast = playback(Some(10.0), AnimationLoop::Repeat);
+        assert_eq!(animation.frame_at(0.25, fast).0, 2);
+        assert_eq!(animation.frame_at(0.45, fast).0, 0);
+    }
+
+    #[test]
+    fn decodes_gif_frames_with_their_delays() {
+        use image::codecs::gif::GifEncoder;
+        use image::Delay;
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("anim.gif");
+        {
+            let file = File::create(&path).unwrap();
+            let mut encoder = GifEncoder::new(file);
+            for (shade, delay_ms) in [(0u8, 100), (255, 300)] {
+                let buffer = RgbaImage::from_pixel(4, 2, image::Rgba([shade, shade, shade, 255]));
+                let delay = Delay::from_numer_denom_ms(delay_ms, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(buffer, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+
+        let animation = AnimatedImage::load(&path, false).expect("decode gif");
+        assert_eq!((animation.width, animation.height), (4, 2));
+        assert_eq!(animation.frame_count(), 2);
+        assert_eq!(animation.frame(1)[0], 255);
+        let repeat = playback(None, AnimationLoop::Repeat);
+        assert_eq!(animation.frame_at(0.05, repeat).0, 0);
+        assert_eq!(animation.frame_at(0.2, repeat).0, 1);
+        assert_eq!(animation.frame_at(0.45, repeat).0, 0);
+    }
+
+    #[test]
+    fn orders_sequences_numerically_and_rejects_mixed_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let temp = dir.path();
+        for (name, size) in [("frame10.png", 2), ("frame2.png", 2), ("frame1.png", 2)] {
+            RgbaImage::new(size, size).save(temp.join(name)).unwrap();
+        }
+        std::fs::write(temp.join("notes.txt"), "ignored").unwrap();
+        let files = sequence_files(temp).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["frame1.png", "frame2.png", "frame10.png"]);
+        assert_eq!(AnimatedImage::load(temp, true).unwrap().frame_count(), 3);
+
+        RgbaImage::new(4, 4).save(temp.join("frame11.png")).unwrap();
+        assert!(AnimatedImage::load(temp, true).is_err());
+    }
+}
@@ -0,0 +1,764 @@
+//! Audio decoding and ShaderToy-style spectrum analysis for audio channels.
+//!
+//! ShaderToy exposes sound inputs as a 512×2 single-channel texture: row 0 holds
+//! the FFT magnitude spectrum and row 1 the most recent waveform, both as bytes.
+//! This module reproduces the Web Audio `AnalyserNode` behaviour that the site
+//! relies on so visualisers react the same way.
+//!
+//! Flow
+//!
+//! ```text
+//!   audio file (wav/ogg/mp3)            stdin / FIFO (interleaved PCM)
+//!        │  decode_audio_file                 │  reader thread, downmix
+//!        ▼  (symphonia, mono)                 ▼
+//!   AudioClip::window_at(seconds)       LiveCapture::latest
+//!        │  (looping)                         │
+//!        └──────────────┬─────────────────────┘
+//!                       ▼  last FFT_SIZE samples
+//!   SpectrumAnalyzer::analyze ──▶ [row 0: FFT bytes | row 1: waveform bytes]
+//! ```
+//!
+//! Key types
+//!
+//! - `AudioClip` — decoded mono samples plus sample rate; maps shader time onto a
+//!   looping playback position.
+//! - `PendingClip` — a clip still being rendered elsewhere (the sound pass);
+//!   reads as silence until it lands.
+//! - `LiveCapture` — process-wide reader per stdin/FIFO input feeding a ring of
+//!   recent samples; stale streams read as silence.
+//! - `SpectrumAnalyzer` — Blackman-windowed FFT with Web Audio smoothing and dB
+//!   scaling; stateful so the spectrum decays between frames.
+//! - `AudioStream` — per-channel state pairing a source with its analyzer; the
+//!   GPU channel code asks it for fresh texture bytes every frame.
+//! - `write_wav_pcm16` — the inverse direction, used when exporting sound passes.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::types::{LiveAudio, LiveAudioInput, PcmFormat};
+
+/// Width of the ShaderToy audio texture (number of FFT bins and waveform samples).
+pub(crate) const AUDIO_TEXTURE_WIDTH: u32 = 512;
+/// Height of the ShaderToy audio texture: spectrum row plus waveform row.
+pub(crate) const AUDIO_TEXTURE_HEIGHT: u32 = 2;
+
+/// Analysis window length; yields `AUDIO_TEXTURE_WIDTH` frequency bins.
+const FFT_SIZE: usize = AUDIO_TEXTURE_WIDTH as usize * 2;
+/// Web Audio `AnalyserNode` defaults used by ShaderToy.
+const SMOOTHING_TIME_CONSTANT: f32 = 0.8;
+const MIN_DECIBELS: f32 = -100.0;
+const MAX_DECIBELS: f32 = -30.0;
+
+/// Decoded audio, downmixed to mono `f32` samples.
+#[derive(Clone)]
+pub(crate) struct AudioClip {
+    samples: Arc<[f32]>,
+    sample_rate: u32,
+}
+
+impl AudioClip {
+    pub(crate) fn new(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            samples: samples.into(),
+            sample_rate: sample_rate.max(1),
+        }
+    }
+
+    pub(crate) fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub(crate) fn duration_seconds(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate as f32
+    }
+
+    /// Playback position for shader time `seconds`, looping at the end of the clip.
+    pub(crate) fn position(&self, seconds: f32) -> f32 {
+        let duration = self.duration_seconds();
+        if duration <= 0.0 {
+            return 0.0;
+        }
+        seconds.max(0.0).rem_euclid(duration)
+    }
+
+    /// Copies the `FFT_SIZE` samples that end at `seconds` into `out`, wrapping
+    /// around the clip so loops stay seamless.
+    fn window_at(&self, seconds: f32, out: &mut [f32]) {
+        let len = self.samples.len();
+        if len == 0 {
+            out.fill(0.0);
+            return;
+        }
+        let end = (self.position(seconds) * self.sample_rate as f32) as usize % len;
+        let start = (end + len * out.len().div_ceil(len) - out.len()) % len;
+        for (offset, sample) in out.iter_mut().enumerate() {
+            *sample = self.samples[(start + offset) % len];
+        }
+    }
+}
+
+/// Clip produced in the background and shared by every channel that plays it.
+#[derive(Clone, Default)]
+pub(crate) struct PendingClip {
+    shared: Arc<(Mutex<PendingState>, Condvar)>,
+}
+
+#[derive(Default)]
+enum PendingState {
+    #[default]
+    Rendering,
+    Ready(AudioClip),
+    Failed,
+}
+
+impl PendingClip {
+    /// Publishes the result; `None` leaves the clip silent for good.
+    pub(crate) fn finish(&self, clip: Option<AudioClip>) {
+        let (state, ready) = &*self.shared;
+        *state.lock().expect("pending clip poisoned") = match clip {
+            Some(clip) => PendingState::Ready(clip),
+            None => PendingState::Failed,
+        };
+        ready.notify_all();
+    }
+
+    /// The clip, once rendered.
+    pub(crate) fn get(&self) -> Option<AudioClip> {
+        let (state, _) = &*self.shared;
+        match &*state.lock().expect("pending clip poisoned") {
+            PendingState::Ready(clip) => Some(clip.clone()),
+            PendingState::Rendering | PendingState::Failed => None,
+        }
+    }
+
+    /// Blocks until the clip is rendered or has failed.
+    pub(crate) fn wait(&self) {
+        let (state, ready) = &*self.shared;
+        let guard = state.lock().expect("pending clip poisoned");
+        let _guard = ready
+            .wait_while(guard, |state| matches!(state, PendingState::Rendering))
+            .expect("pending clip poisoned");
+    }
+
+    /// True when nothing but `self` refers to the clip.
+    pub(crate) fn is_unshared(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+}
+
+/// Decodes a local WAV, OGG/Vorbis or MP3 file into a mono clip.
+pub(crate) fn decode_audio_file(path: &Path) -> Result<AudioClip> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open audio file {}", path.display()))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .with_context(|| format!("unsupported audio format at {}", path.display()))?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow!("no decodable audio track in {}", path.display()))?;
+    let track_id = track.id;
+    let sample_rate = track.codec_params.sample_rate.unwrap_or(44_100);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .with_context(|| format!("unsupported audio codec in {}", path.display()))?;
+
+    let mut samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(err))
+                if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            }
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(err)) => {
+                tracing::debug!(path = %path.display(), error = %err, "skipping corrupt audio packet");
+                continue;
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to decode {}", path.display()))
+            }
+        };
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let buffer = match buffer.as_mut() {
+            Some(existing) if existing.capacity() >= decoded.capacity() * channels => existing,
+            _ => buffer.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+
+    if samples.is_empty() {
+        anyhow::bail!("audio file {} contains no samples", path.display());
+    }
+    Ok(AudioClip::new(samples, sample_rate))
+}
+
+/// Writes interleaved `[-1, 1]` samples as a 16-bit PCM WAV file.
+pub(crate) fn write_wav_pcm16(
+    path: &Path,
+    interleaved: &[f32],
+    channels: u16,
+    sample_rate: u32,
+) -> Result<()> {
+    let block_align = u32::from(channels) * 2;
+    let data_len = u32::try_from(interleaved.len() * 2)
+        .map_err(|_| anyhow!("audio is too long for a WAV file"))?;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend(b"RIFF");
+    bytes.extend((36 + data_len).to_le_bytes());
+    bytes.extend(b"WAVEfmt ");
+    bytes.extend(16u32.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes()); // PCM
+    bytes.extend(channels.to_le_bytes());
+    bytes.extend(sample_rate.to_le_bytes());
+    bytes.extend((sample_rate * block_align).to_le_bytes());
+    bytes.extend((block_align as u16).to_le_bytes());
+    bytes.extend(16u16.to_le_bytes());
+    bytes.extend(b"data");
+    bytes.extend(data_len.to_le_bytes());
+    for sample in interleaved {
+        let quantized = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16;
+        bytes.extend(quantized.to_le_bytes());
+    }
+    std::fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Reproduces Web Audio's `getByteFrequencyData`/`getByteTimeDomainData`.
+pub(crate) struct SpectrumAnalyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    smoothed: Vec<f32>,
+    buffer: Vec<Complex<f32>>,
+}
+
+impl SpectrumAnalyzer {
+    pub(crate) fn new() -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(FFT_SIZE);
+        // Blackman window, as specified for AnalyserNode.
+        let window = (0..FFT_SIZE)
+            .map(|n| {
+                let phase = 2.0 * std::f32::consts::PI * n as f32 / FFT_SIZE as f32;
+                0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+            })
+            .collect();
+        Self {
+            fft,
+            window,
+            smoothed: vec![0.0; AUDIO_TEXTURE_WIDTH as usize],
+            buffer: vec![Complex::default(); FFT_SIZE],
+        }
+    }
+
+    /// Fills `out` (`AUDIO_TEXTURE_WIDTH * AUDIO_TEXTURE_HEIGHT` bytes) from the
+    /// latest `FFT_SIZE` samples: spectrum in row 0, waveform in row 1.
+    pub(crate) fn analyze(&mut self, samples: &[f32], out: &mut [u8]) {
+        let width = AUDIO_TEXTURE_WIDTH as usize;
+        debug_assert_eq!(samples.len(), FFT_SIZE);
+        debug_assert_eq!(out.len(), width * AUDIO_TEXTURE_HEIGHT as usize);
+
+        for ((slot, sample), weight) in self.buffer.iter_mut().zip(samples).zip(&self.window) {
+            *slot = Complex::new(sample * weight, 0.0);
+        }
+        self.fft.process(&mut self.buffer);
+
+        let (spectrum, waveform) = out.split_at_mut(width);
+        for (bin, byte) in spectrum.iter_mut().enumerate() {
+            let magnitude = self.buffer[bin].norm() / FFT_SIZE as f32;
+            let smoothed = SMOOTHING_TIME_CONSTANT * self.smoothed[bin]
+                + (1.0 - SMOOTHING_TIME_CONSTANT) * magnitude;
+            self.smoothed[bin] = smoothed;
+            let decibels = 20.0 * smoothed.max(f32::MIN_POSITIVE).log10();
+            let scaled = (decibels - MIN_DECIBELS) / (MAX_DECIBELS - MIN_DECIBELS);
+            *byte = (scaled.clamp(0.0, 1.0) * 255.0) as u8;
+        }
+
+        for (byte, sample) in waveform.iter_mut().zip(&samples[FFT_SIZE - width..]) {
+            *byte = (128.0 * (1.0 + sample)).clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Shared state of one live PCM reader, keyed by its input in `LIVE_CAPTURES`.
+struct LiveShared {
+    config: LiveAudio,
+    ring: Mutex<LiveRing>,
+}
+
+/// Most recent mono samples of a live stream.
+struct LiveRing {
+    samples: VecDeque<f32>,
+    updated: Option<Instant>,
+}
+
+impl LiveRing {
+    fn push(&mut self, frames: impl IntoIterator<Item = f32>) {
+        self.samples.extend(frames);
+        let excess = self.samples.len().saturating_sub(LIVE_RING_CAPACITY);
+        self.samples.drain(..excess);
+        self.updated = Some(Instant::now());
+    }
+
+    /// Copies the newest samples into `out`, left-padding with silence. A stream
+    /// that stopped delivering data reads as silence rather than a frozen spectrum.
+    fn latest(&self, out: &mut [f32]) {
+        let fresh = self
+            .updated
+            .is_some_and(|updated| updated.elapsed() < LIVE_STALE_AFTER);
+        out.fill(0.0);
+        if !fresh {
+            return;
+        }
+        let count = self.samples.len().min(out.len());
+        let offset = out.len() - count;
+        for (slot, sample) in out[offset..]
+            .iter_mut()
+            .zip(self.samples.range(self.samples.len() - count..))
+        {
+            *slot = *sample;
+        }
+    }
+}
+
+/// Keep a few analysis windows so a slow frame never starves the FFT.
+const LIVE_RING_CAPACITY: usize = FFT_SIZE * 4;
+const LIVE_STALE_AFTER: Duration = Duration::from_millis(500);
+const LIVE_READ_CHUNK: usize = 4096;
+const FIFO_REOPEN_DELAY: Duration = Duration::from_millis(250);
+
+/// One reader per input for the whole process: stdin can only be consumed once,
+/// and shader swaps should not reopen pipes mid-stream.
+static LIVE_CAPTURES: OnceLock<Mutex<HashMap<LiveAudioInput, Arc<LiveShared>>>> = OnceLock::new();
+
+/// Handle to a background thread that reads interleaved PCM from stdin or a FIFO.
+#[derive(Clone)]
+pub(crate) struct LiveCapture {
+    shared: Arc<LiveShared>,
+}
+
+impl LiveCapture {
+    /// Returns the capture for `config.input`, spawning its reader on first use.
+    pub(crate) fn open(config: &LiveAudio) -> Result<Self> {
+        let mut captures = LIVE_CAPTURES
+            .get_or_init(Default::default)
+            .lock()
+            .expect("live capture registry poisoned");
+        if let Some(shared) = captures.get(&config.input) {
+            if shared.config != *config {
+                tracing::warn!(
+                    input = ?config.input,
+                    active = ?shared.config,
+                    requested = ?config,
+                    "live audio input already open with different settings; reusing it"
+                );
+            }
+            return Ok(Self {
+                shared: Arc::clone(shared),
+            });
+        }
+
+        let shared = Arc::new(LiveShared {
+            config: config.clone(),
+            ring: Mutex::new(LiveRing {
+                samples: VecDeque::with_capacity(LIVE_RING_CAPACITY),
+                updated: None,
+            }),
+        });
+        let reader = Arc::clone(&shared);
+        thread::Builder::new()
+            .name("wax11-live-audio".into())
+            .spawn(move || run_live_reader(&reader))
+            .context("failed to spawn live audio reader")?;
+        captures.insert(config.input.clone(), Arc::clone(&shared));
+        Ok(Self { shared })
+    }
+
+    pub(crate) fn sample_rate(&self) -> u32 {
+        self.shared.config.sample_rate
+    }
+
+    fn latest(&self, out: &mut [f32]) {
+        self.shared
+            .ring
+            .lock()
+            .expect("live audio ring poisoned")
+            .latest(out);
+    }
+}
+
+fn run_live_reader(shared: &LiveShared) {
+    let config = &shared.config;
+    loop {
+        let result = match &config.input {
+            LiveAudioInput::Stdin => pump_pcm(std::io::stdin().lock(), shared),
+            LiveAudioInput::Fifo(path) => File::open(path)
+                .with_context(|| format!("failed to open audio FIFO {}", path.display()))
+                .and_then(|file| pump_pcm(file, shared)),
+        };
+        match (&config.input, result) {
+            (LiveAudioInput::Stdin, Ok(())) => {
+                tracing::info!("live audio stdin closed; channel falls back to silence");
+                return;
+            }
+            (LiveAudioInput::Stdin, Err(error)) => {
+                tracing::warn!(error = %error, "live audio stdin reader stopped");
+                return;
+            }
+            // A FIFO hits EOF whenever its writer exits; wait for the next one.
+            (LiveAudioInput::Fifo(path), Ok(())) => {
+                tracing::debug!(path = %path.display(), "live audio FIFO writer closed; reopening");
+            }
+            (LiveAudioInput::Fifo(path), Err(error)) => {
+                tracing::warn!(path = %path.display(), error = %error, "live audio FIFO read failed; retrying");
+            }
+        }
+        thread::sleep(FIFO_REOPEN_DELAY);
+    }
+}
+
+/// Reads PCM until EOF, pushing downmixed mono samples into the shared ring.
+fn pump_pcm(mut reader: impl Read, shared: &LiveShared) -> Result<()> {
+    let config = &shared.config;
+    let frame_bytes = config.format.bytes_per_sample() * config.channels as usize;
+    let mut buffer = vec![0u8; LIVE_READ_CHUNK * frame_bytes];
+    let mut filled = 0;
+    loop {
+        let read = match reader.read(&mut buffer[filled..]) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        filled += read;
+        let whole = filled - filled % frame_bytes;
+        let frames = decode_pcm_frames(&buffer[..whole], config);
+        shared
+            .ring
+            .lock()
+            .expect("live audio ring poisoned")
+            .push(frames);
+        // Keep any partial frame for the next read.
+        buffer.copy_within(whole..filled, 0);
+        filled -= whole;
+    }
+}
+
+/// Converts whole interleaved frames into mono samples in `[-1, 1]`.
+fn decode_pcm_frames<'a>(bytes: &'a [u8], config: &LiveAudio) -> impl Iterator<Item = f32> + 'a {
+    let format = config.format;
+    let channels = config.channels as usize;
+    let sample_bytes = format.bytes_per_sample();
+    bytes
+        .chunks_exact(sample_bytes * channels)
+        .map(move |frame| {
+            let sum: f32 = frame
+                .chunks_exact(sample_bytes)
+                .map(|sample| match format {
+                    PcmFormat::S16Le => i16::from_le_bytes([sample[0], sample[1]]) as f32 / 32768.0,
+                    PcmFormat::F32Le => {
+                        f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]])
+                    }
+                })
+                .sum();
+            sum / channels as f32
+        })
+}
+
+/// Where an audio channel gets its samples from.
+enum AudioSource {
+    Clip(AudioClip),
+    Pending(PendingClip),
+    Live(LiveCapture),
+}
+
+/// Audio channel state: a source plus the analyzer that turns it into texture bytes.
+pub(crate) struct AudioStream {
+    source: AudioSource,
+    analyzer: SpectrumAnalyzer,
+    window: Vec<f32>,
+    texels: Vec<u8>,
+}
+
+impl AudioStream {
+    pub(crate) fn from_clip(clip: AudioClip) -> Self {
+        Self::new(AudioSource::Clip(clip))
+    }
+
+    pub(crate) fn from_pending(clip: PendingClip) -> Self {
+        Self::new(AudioSource::Pending(clip))
+    }
+
+    pub(crate) fn from_live(capture: LiveCapture) -> Self {
+        Self::new(AudioSource::Live(capture))
+    }
+
+    fn new(source: AudioSource) -> Self {
+        Self {
+            source,
+            analyzer: SpectrumAnalyzer::new(),
+            window: vec![0.0; FFT_SIZE],
+            texels: vec![0; (AUDIO_TEXTURE_WIDTH * AUDIO_TEXTURE_HEIGHT) as usize],
+        }
+    }
+
+    /// Analyses the audio at shader time `seconds`.
+    ///
+    /// Returns the playback position to report through `iChannelTime` together
+    /// with the texture bytes to upload. Live streams always analyse their newest
+    /// samples and report the shader clock.
+    pub(crate) fn update(&mut self, seconds: f32) -> (f32, &[u8]) {
+        let position = match &self.source {
+            AudioSource::Clip(clip) => {
+                clip.window_at(seconds, &mut self.window);
+                clip.position(seconds)
+            }
+            AudioSource::Pending(pending) => match pending.get() {
+                Some(clip) => {
+                    clip.window_at(seconds, &mut self.window);
+                    clip.position(seconds)
+                }
+                None => {
+                    self.window.fill(0.0);
+                    0.0
+                }
+            },
+            AudioSource::Live(capture) => {
+                capture.latest(&mut self.window);
+                seconds
+            }
+        };
+        self.analyzer.analyze(&self.window, &mut self.texels);
+        (position, &self.texels)
+    }
+
+    /// Sample rate to expose through `iSampleRate`; only live inputs define one.
+    pub(crate) fn live_sample_rate(&self) -> Option<u32> {
+        match &self.source {
+            AudioSource::Clip(_) | AudioSource::Pending(_) => None,
+            AudioSource::Live(capture) => Some(capture.sample_rate()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        let count = (sample_rate as f32 * seconds) as usize;
+        (0..count)
+            .map(|n| (2.0 * std::f32::consts::PI * frequency * n as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn spectrum_peaks_at_tone_bin() {
+        let sample_rate = 44_100;
+        // Bin 64 of a 1024-point FFT.
+        let frequency = 64.0 * sample_rate as f32 / FFT_SIZE as f32;
+        // Quiet enough that the peak stays below the -30 dB ceiling instead of clipping.
+        let samples = sine(frequency, sample_rate, 1.0)
+            .into_iter()
+            .map(|sample| sample * 0.001)
+            .collect();
+        let clip = AudioClip::new(samples, sample_rate);
+        let mut stream = AudioStream::from_clip(clip);
+        for _ in 0..30 {
+            stream.update(0.5);
+        }
+        let (_, texels) = stream.update(0.5);
+        let spectrum = &texels[..AUDIO_TEXTURE_WIDTH as usize];
+        let peak = spectrum
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, value)| **value)
+            .map(|(bin, _)| bin)
+            .unwrap();
+        assert_eq!(peak, 64);
+        assert!(spectrum[400] < spectrum[64]);
+    }
+
+    #[test]
+    fn downmixes_interleaved_pcm_frames() {
+        let config = LiveAudio {
+            input: LiveAudioInput::Stdin,
+            sample_rate: 48_000,
+            channels: 2,
+            format: PcmFormat::S16Le,
+        };
+        let mut bytes = Vec::new();
+        for sample in [i16::MAX, i16::MAX, -16384, 0, 0] {
+            bytes.extend(sample.to_le_bytes());
+        }
+        // The trailing half frame is ignored.
+        let mono: Vec<f32> = decode_pcm_frames(&bytes[..bytes.len() - 2], &config).collect();
+        assert_eq!(mono.len(), 2);
+        assert!(mono[0] > 0.99);
+        assert!((mono[1] + 0.25).abs() < 1e-4);
+
+        let float = LiveAudio {
+            channels: 1,
+            format: PcmFormat::F32Le,
+            ..config
+        };
+        let bytes: Vec<u8> = [0.5f32, -1.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let mono: Vec<f32> = decode_pcm_frames(&bytes, &float).collect();
+        assert_eq!(mono, vec![0.5, -1.0]);
+    }
+
+    #[test]
+    fn live_ring_keeps_newest_samples_and_goes_silent_when_stale() {
+        let mut ring = LiveRing {
+            samples: VecDeque::new(),
+            updated: None,
+        };
+        let mut out = [1.0; 4];
+        ring.latest(&mut out);
+        assert_eq!(out, [0.0; 4]);
+
+        ring.push((0..LIVE_RING_CAPACITY + 2).map(|n| n as f32));
+        assert_eq!(ring.samples.len(), LIVE_RING_CAPACITY);
+        ring.latest(&mut out);
+        let last = (LIVE_RING_CAPACITY + 1) as f32;
+        assert_eq!(out, [last - 3.0, last - 2.0, last - 1.0, last]);
+
+        ring.updated = Instant::now().checked_sub(LIVE_STALE_AFTER * 2);
+        ring.latest(&mut out);
+        assert_eq!(out, [0.0; 4]);
+    }
+
+    #[test]
+    fn silence_centres_waveform_row() {
+        let clip = AudioClip::new(vec![0.0; 4096], 48_000);
+        let mut stream = AudioStream::from_clip(clip);
+        let (_, texels) = stream.update(0.02);
+        let width = AUDIO_TEXTURE_WIDTH as usize;
+        assert!(texels[..width].iter().all(|value| *value == 0));
+        assert!(texels[width..].iter().all(|value| *value == 128));
+    }
+
+    #[test]
+    fn position_loops_past_clip_end() {
+        let clip = AudioClip::new(vec![0.0; 48_000], 48_000);
+        assert!((clip.position(2.25) - 0.25).abs() < 1e-4);
+        assert_eq!(clip.position(-1.0), 0.0);
+    }
+
+    #[test]
+    fn decodes_pcm_wav_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("clip.wav");
+        let samples: Vec<i16> = vec![0, i16::MAX, 0, i16::MIN];
+        let data_len = (samples.len() * 2) as u32;
+        let mut bytes = Vec::new();
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + data_len).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes()); // PCM
+        bytes.extend(1u16.to_le_bytes()); // mono
+        bytes.extend(8_000u32.to_le_bytes());
+        bytes.extend(16_000u32.to_le_bytes());
+        bytes.extend(2u16.to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(data_len.to_le_bytes());
+        for sample in &samples {
+            bytes.extend(sample.to_le_bytes());
+        }
+        std::fs::write(&path, bytes).unwrap();
+
+        let clip = decode_audio_file(&path).expect("decode wav");
+        assert_eq!(clip.sample_rate(), 8_000);
+        assert_eq!(clip.samples.len(), samples.len());
+        assert!(clip.samples[1] > 0.99);
+        assert!(clip.samples[3] < -0.99);
+    }
+
+    #[test]
+    fn written_wav_decodes_back_to_a_mono_mix() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("out.wav");
+        // Stereo frames: (1, 0) then (-1, -1).
+        write_wav_pcm16(&path, &[1.0, 0.0, -1.0, -1.0], 2, 22_050).expect("write wav");
+
+        let clip = decode_audio_file(&path).expect("decode wav");
+        assert_eq!(clip.sample_rate(), 22_050);
+        assert_eq!(clip.samples.len(), 2);
+        assert!((clip.samples[0] - 0.5).abs() < 1e-3);
+        assert!((clip.samples[1] + 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn pending_clip_is_silent_until_it_lands() {
+        let pending = PendingClip::default();
+        let mut stream = AudioStream::from_pending(pending.clone());
+        let (position, texels) = stream.update(0.25);
+        assert_eq!(position, 0.0);
+        assert!(texels[..AUDIO_TEXTURE_WIDTH as usize]
+            .iter()
+            .all(|&bin| bin == 0));
+
+        let worker = {
+            let pending = pending.clone();
+            thread::spawn(move || pending.finish(Some(AudioClip::new(vec![0.5; 8_000], 8_000))))
+        };
+        pending.wait();
+        worker.join().unwrap();
+        let (position, texels) = stream.update(0.25);
+        assert_eq!(position, 0.25);
+        assert!(texels[AUDIO_TEXTURE_WIDTH as usize..]
+            .iter()
+            .any(|&sample| sample != 128));
+    }
+}
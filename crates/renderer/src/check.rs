@@ -0,0 +1,184 @@
+//! Headless compile checks behind `wax11 check`.
+//!
+//! Every pass of a shader (image, buffers, cubemap and sound) is wrapped and
+//! compiled with each requested backend, without creating a window or even a
+//! GPU device, so a shader repository can be gated in CI:
+//!
+//! ```text
+//!   ShaderCheckConfig
+//!     │  image + buffer/cubemap/sound passes
+//!     ▼
+//!   preprocess::load_pass_source ──▶ compile::check_fragment_shader (per backend)
+//!     │                                   │ Ok(usage) → unbound channel warnings
+//!     │                                   │ Err       → channel kind suggestions
+//!     ▼                                   ▼
+//!   Vec<PassCheck>
+//! ```
+
+use std::path::Path;
+
+use crate::compile::{channel_kind_mismatches, check_fragment_shader, PassTarget};
+use crate::preprocess::load_pass_source;
+use crate::types::{
+    ChannelBindings, ShaderCheckConfig, ShaderCompiler, ShaderLanguage, CHANNEL_COUNT,
+};
+
+/// Outcome of compiling one pass with one backend.
+#[derive(Debug)]
+pub struct PassCheck {
+    /// Pass name: `image` for the entry pass, otherwise the manifest name.
+    pub pass: String,
+    /// Frontend that compiled the pass (`shaderc`, `naga glsl` or `naga wgsl`).
+    pub frontend: &'static str,
+    /// Compile failure; a [`crate::ShaderCompileError`] when diagnostics were located.
+    pub error: Option<anyhow::Error>,
+    /// Channel problems that do not stop the pass from compiling.
+    pub warnings: Vec<String>,
+}
+
+impl PassCheck {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Compiles every pass described by `config` with each requested backend.
+pub fn check_shader(config: &ShaderCheckConfig) -> Vec<PassCheck> {
+    let bindings = &config.channel_bindings;
+    let mut passes = vec![(
+        "image",
+        config.shader_source.as_path(),
+        bindings,
+        PassTarget::Image,
+    )];
+    for pass in bindings.buffer_passes() {
+        passes.push((&pass.name, &pass.source, &pass.channels, PassTarget::Buffer));
+    }
+    if let Some(pass) = bindings.cubemap_pass() {
+        passes.push((
+            &pass.name,
+            &pass.source,
+            &pass.channels,
+            PassTarget::Cubemap,
+        ));
+    }
+    if let Some(pass) = bindings.sound_pass() {
+        passes.push((&pass.name, &pass.source, &pass.channels, PassTarget::Sound));
+    }
+
+    let mut checks = Vec::new();
+    for (name, path, channels, target) in passes {
+        check_pass(config, name, path, channels, target, &mut checks);
+    }
+    checks
+}
+
+fn check_pass(
+    config: &ShaderCheckConfig,
+    name: &str,
+    path: &Path,
+    channels: &ChannelBindings,
+    target: PassTarget,
+    checks: &mut Vec<PassCheck>,
+) {
+    let language = channels.language();
+    let source = match load_pass_source(path, language, &config.shader_include_dirs) {
+        Ok(source) => source,
+        Err(err) => {
+            checks.push(PassCheck {
+                pass: name.to_owned(),
+                frontend: "preprocess",
+                error: Some(err),
+                warnings: Vec::new(),
+            });
+            return;
+        }
+    };
+
+    let compilers: &[ShaderCompiler] = match language {
+        // The backend choice only applies to GLSL, so WGSL is compiled once.
+        ShaderLanguage::Wgsl => &[ShaderCompiler::NagaGlsl],
+        ShaderLanguage::Glsl => &config.compilers,
+    };
+    let kinds = channels.layout_signature();
+    for &compiler in compilers {
+        let frontend = match (language, compiler) {
+            (ShaderLanguage::Wgsl, _) => "naga wgsl",
+            (ShaderLanguage::Glsl, ShaderCompiler::Shaderc) => "shaderc",
+            (ShaderLanguage::Glsl, _) => "naga glsl",
+        };
+        let mut warnings = Vec::new();
+        let error = match check_fragment_shader(&source, target, &kinds, compiler) {
+            Ok(usage) => {
+                let sampled = usage.map_or([false; CHANNEL_COUNT], |usage| usage.channels);
+                for (channel, &sampled) in sampled.iter().enumerate() {
+                    if sampled && channels.channel(channel).is_none() {
+                        warnings.push(format!(
+                            "shader samples iChannel{channel} but nothing is bound to it"
+                        ));
+                    }
+                }
+                None
+            }
+            Err(err) => {
+                for (channel, sampled_as) in channel_kind_mismatches(&source, target, &kinds) {
+                    warnings.push(format!(
+                        "iChannel{channel} is bound as a {} but the shader samples it as a {}",
+                        kinds[channel].describe(),
+                        sampled_as.describe(),
+                    ));
+                }
+                Some(err)
+            }
+        };
+        checks.push(PassCheck {
+            pass: name.to_owned(),
+            frontend,
+            error,
+            warnings,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_each_pass_and_backend() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let image = dir.join("image.glsl");
+        let buffer = dir.join("buffer.glsl");
+        std::fs::write(
+            &image,
+            "void mainImage(out vec4 c, in vec2 p) { c = texture(iChannel1, p / iResolution.xy); }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &buffer,
+            "void mainImage(out vec4 c, in vec2 p) { c = oops; }\n",
+        )
+        .unwrap();
+
+        let mut bindings = ChannelBindings::default();
+        bindings.add_buffer_pass("Buffer A", buffer, ChannelBindings::default());
+        let checks = check_shader(&ShaderCheckConfig {
+            shader_source: image,
+            channel_bindings: bindings,
+            shader_include_dirs: Vec::new(),
+            compilers: vec![ShaderCompiler::NagaGlsl],
+        });
+
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].passed());
+        assert_eq!(checks[0].frontend, "naga glsl");
+        assert_eq!(
+            checks[0].warnings,
+            ["shader samples iChannel1 but nothing is bound to it"]
+        );
+        assert_eq!(checks[1].pass, "Buffer A");
+        let error = checks[1].error.as_ref().expect("buffer fails to compile");
+        assert!(error.downcast_ref::<crate::ShaderCompileError>().is_some());
+    }
+}
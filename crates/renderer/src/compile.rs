@@ -22,27 +22,49 @@
 //!          wgpu::ShaderModule
 //! ```
 //!
+//! Passes declared with `language = "wgsl"` skip the GLSL path: the source is
+//! appended to [`wgsl_prelude`] plus a WGSL epilogue for the pass target and handed
+//! straight to naga's WGSL frontend.
+//!
 //! Integration
 //!
+//! - `preprocess::load_pass_source` reads the pass and expands `#include`s first.
 //! - `gpu::GpuState` calls `compile_vertex_shader` (static) and
 //!   `compile_fragment_shader` (wrapped user shader) while building pipelines.
-//! - Wrapped source is dumped to `/tmp/wax11_wrapped.frag` when compiling the
-//!   fragment shader to aid diagnostics and naga/shaderc comparisons.
+//! - Failures come back as [`ShaderCompileError`]: naga is run up front (and the
+//!   shaderc log parsed) so diagnostics can be mapped through the `#line` markers
+//!   to pack files instead of panicking inside `wgpu`.
+//! - `gpu::pipeline::compile_pass_shader` calls `channel_kind_mismatches` after a
+//!   failed compile to name channels bound with the wrong texture kind.
 //!
 //! Key functions
 //!
 //! - `compile_vertex_shader` — minimal full-screen triangle vertex module.
 //! - `compile_fragment_shader` — wraps + compiles the runtime shader.
 //! - `wrap_shadertoy_fragment` — removes ShaderToy uniforms and injects prelude/epilogue.
-//! - `compile_glsl` — backend switch between Shaderc and Naga GLSL.
+//! - `wrap_wgsl_fragment` — prepends the WGSL prelude and appends the `@fragment` entry.
+//! - `compile_glsl` — backend switch between Shaderc and Naga GLSL; `Auto` tries
+//!   both (cached winner first) and reports every backend's errors if all fail.
 //!
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 use wgpu::naga::ShaderStage;
 
-use crate::types::ShaderCompiler;
+use crate::diagnostic::{
+    naga_glsl_module, naga_wgsl_module, LineMap, ShaderCompileError, ShaderFallbackError,
+};
+use crate::gpu::SurfaceColorSpace;
+use crate::preprocess::PassSource;
+use crate::reflect::ShaderUsage;
+use crate::shader_cache::{key_hasher, ShaderCache};
+use crate::types::{ChannelTextureKind, ShaderCompiler, ShaderLanguage, CHANNEL_COUNT};
 
+#[cfg(feature = "shaderc")]
+use anyhow::anyhow;
 #[cfg(feature = "shaderc")]
 use tracing::warn;
 
@@ -50,6 +72,7 @@ use tracing::warn;
 pub(crate) fn compile_vertex_shader(
     device: &wgpu::Device,
     compiler: ShaderCompiler,
+    cache: Option<&ShaderCache>,
 ) -> Result<wgpu::ShaderModule> {
     compile_glsl(
         device,
@@ -57,51 +80,248 @@ pub(crate) fn compile_vertex_shader(
         ShaderStage::Vertex,
         "fullscreen triangle vertex",
         compiler,
+        &LineMap::from_glsl(VERTEX_SHADER_GLSL, &[]),
+        cache.map(|cache| CacheScope { cache, layout: 0 }),
     )
+    .map(|compiled| compiled.module)
+}
+
+/// A compiled module and the frame-varying inputs its code reads.
+pub(crate) struct CompiledShader {
+    pub module: wgpu::ShaderModule,
+    /// `None` when naga could not reflect the compiled module.
+    pub usage: Option<ShaderUsage>,
+}
+
+impl CompiledShader {
+    /// Channels the pass reads; every channel when reflection was unavailable.
+    pub(crate) fn sampled_channels(&self) -> [bool; CHANNEL_COUNT] {
+        self.usage
+            .map_or([true; CHANNEL_COUNT], |usage| usage.channels)
+    }
+}
+
+/// Hash of every prelude, footer and the crate version; names the shader cache
+/// revision so any change to the generated code invalidates old entries.
+pub(crate) fn prelude_fingerprint() -> u64 {
+    let mut hasher = key_hasher();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    for part in [
+        HEADER,
+        HEADER_TAIL,
+        FOOTER,
+        BUFFER_FOOTER,
+        CUBEMAP_FOOTER,
+        SOUND_FOOTER,
+        LEGACY_SOUND_FOOTER,
+        WGSL_HEADER,
+        WGSL_FOOTER,
+        WGSL_BUFFER_FOOTER,
+        WGSL_CUBEMAP_FOOTER,
+        WGSL_SOUND_FOOTER,
+        VERTEX_SHADER_GLSL,
+    ] {
+        part.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Disk cache plus the layout part of the entry key for one compile.
+#[derive(Clone, Copy)]
+struct CacheScope<'a> {
+    cache: &'a ShaderCache,
+    /// Hash of the pass target, channel layout and surface color space.
+    layout: u64,
+}
+
+impl CacheScope<'_> {
+    fn key(&self, source: &str, stage: ShaderStage, compiler: ShaderCompiler) -> u64 {
+        let mut hasher = key_hasher();
+        self.layout.hash(&mut hasher);
+        compiler.hash(&mut hasher);
+        source_hash(source, stage).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Where a wrapped fragment shader writes its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PassTarget {
+    /// The presented image: coordinates follow the fill method and output is
+    /// premultiplied by the crossfade mix.
+    Image,
+    /// An offscreen buffer pass: raw `fragCoord` and unmodified RGBA output so
+    /// feedback passes can store state in every channel.
+    Buffer,
+    /// One face of a "Cube A" pass: calls `mainCubemap` with the face's ray
+    /// direction, selected by `wax11_CubeFace`.
+    Cubemap,
+    /// A block of a "Sound" pass: every texel evaluates `mainSound` for one
+    /// sample, with `iFrame` selecting the block.
+    Sound,
 }
 
-/// Wraps the user shader with our ShaderToy prelude and compiles it as GLSL.
+/// Wraps the user shader with the prelude for `language` and compiles it.
 ///
-/// The wrapped source is dumped to `/tmp/wax11_wrapped.frag` to aid
-/// debugging when compilation fails in `wgpu`.
+/// GLSL goes through the selected `compiler`; WGSL always uses naga's WGSL
+/// frontend. Errors carry a [`ShaderCompileError`] whose diagnostics point into
+/// `source.files`. With a `cache`, shaderc output is looked up and stored on disk.
 pub(crate) fn compile_fragment_shader(
     device: &wgpu::Device,
-    source: &str,
+    source: &PassSource,
+    target: PassTarget,
+    channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+    color_space: SurfaceColorSpace,
     compiler: ShaderCompiler,
-) -> Result<wgpu::ShaderModule> {
-    let wrapped = wrap_shadertoy_fragment(source);
-
-    if let Err(err) = std::fs::write("/tmp/wax11_wrapped.frag", &wrapped) {
-        tracing::debug!(error = %err, "failed to dump wrapped shader");
+    cache: Option<&ShaderCache>,
+) -> Result<CompiledShader> {
+    if source.language == ShaderLanguage::Wgsl {
+        let wrapped = wrap_wgsl_fragment(&source.code, target, channel_kinds);
+        let module = match naga_wgsl_module(&wrapped) {
+            Ok(module) => module,
+            Err(errors) => {
+                let body_line = wgsl_prelude(channel_kinds).lines().count() + 1;
+                let lines = LineMap::from_offset(&wrapped, body_line, &source.files);
+                return Err(ShaderCompileError::new("naga wgsl", errors, &lines))
+                    .context("failed to compile fragment shader");
+            }
+        };
+        return Ok(CompiledShader {
+            usage: ShaderUsage::from_module(&module),
+            module: device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("wax11 fragment"),
+                source: wgpu::ShaderSource::Wgsl(Cow::Owned(wrapped)),
+            }),
+        });
     }
 
+    let wrapped = wrap_shadertoy_fragment(&source.code, target, channel_kinds);
     compile_glsl(
         device,
         &wrapped,
         ShaderStage::Fragment,
         "wax11 fragment",
         compiler,
+        &LineMap::from_glsl(&wrapped, &source.files),
+        cache.map(|cache| {
+            let mut hasher = key_hasher();
+            (target, channel_kinds, color_space).hash(&mut hasher);
+            CacheScope {
+                cache,
+                layout: hasher.finish(),
+            }
+        }),
     )
-    .with_context(|| "failed to compile fragment shader")
+    .context("failed to compile fragment shader")
+}
+
+/// Compiles a pass without a device, as `wax11 check` does, returning the
+/// reflected usage. `Auto` checks with the preferred backend only.
+pub(crate) fn check_fragment_shader(
+    source: &PassSource,
+    target: PassTarget,
+    channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+    compiler: ShaderCompiler,
+) -> Result<Option<ShaderUsage>> {
+    if source.language == ShaderLanguage::Wgsl {
+        let wrapped = wrap_wgsl_fragment(&source.code, target, channel_kinds);
+        let module = naga_wgsl_module(&wrapped).map_err(|errors| {
+            let body_line = wgsl_prelude(channel_kinds).lines().count() + 1;
+            let lines = LineMap::from_offset(&wrapped, body_line, &source.files);
+            ShaderCompileError::new("naga wgsl", errors, &lines)
+        })?;
+        return Ok(ShaderUsage::from_module(&module));
+    }
+
+    let wrapped = wrap_shadertoy_fragment(&source.code, target, channel_kinds);
+    let lines = LineMap::from_glsl(&wrapped, &source.files);
+    match compiler {
+        ShaderCompiler::NagaGlsl => {
+            let module = naga_glsl_module(&wrapped, ShaderStage::Fragment)
+                .map_err(|errors| ShaderCompileError::new("naga glsl", errors, &lines))?;
+            Ok(ShaderUsage::from_module(&module))
+        }
+        ShaderCompiler::Shaderc => {
+            let spirv = shaderc_spirv(&wrapped, ShaderStage::Fragment, "wax11 fragment", &lines)?;
+            Ok(ShaderUsage::from_spirv(&spirv))
+        }
+        ShaderCompiler::Auto => {
+            check_fragment_shader(source, target, channel_kinds, ShaderCompiler::preferred())
+        }
+    }
+}
+
+/// Channels whose declared kind keeps a failing pass from compiling, paired
+/// with the kind under which naga accepts it.
+///
+/// Each channel the source mentions is re-declared as the other kinds one at a
+/// time, so this only runs after a compile has already failed.
+pub(crate) fn channel_kind_mismatches(
+    source: &PassSource,
+    target: PassTarget,
+    channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+) -> Vec<(usize, ChannelTextureKind)> {
+    const KINDS: [ChannelTextureKind; 3] = [
+        ChannelTextureKind::Texture2d,
+        ChannelTextureKind::Cubemap,
+        ChannelTextureKind::Texture3d,
+    ];
+    let compiles = |kinds: &[ChannelTextureKind; CHANNEL_COUNT]| match source.language {
+        ShaderLanguage::Glsl => {
+            let wrapped = wrap_shadertoy_fragment(&source.code, target, kinds);
+            naga_glsl_module(&wrapped, ShaderStage::Fragment).is_ok()
+        }
+        ShaderLanguage::Wgsl => {
+            naga_wgsl_module(&wrap_wgsl_fragment(&source.code, target, kinds)).is_ok()
+        }
+    };
+
+    let mut mismatches = Vec::new();
+    for channel in 0..CHANNEL_COUNT {
+        let name = match source.language {
+            ShaderLanguage::Glsl => format!("iChannel{channel}"),
+            ShaderLanguage::Wgsl => format!("channel{channel}"),
+        };
+        if !source.code.contains(&name) {
+            continue;
+        }
+        let candidate = KINDS.into_iter().find(|&kind| {
+            let mut kinds = *channel_kinds;
+            kinds[channel] = kind;
+            kind != channel_kinds[channel] && compiles(&kinds)
+        });
+        if let Some(kind) = candidate {
+            mismatches.push((channel, kind));
+        }
+    }
+    mismatches
 }
 
 /// Produces a self-contained GLSL fragment shader from raw ShaderToy code.
 ///
 /// Steps performed:
 ///
-/// 1. Strip `#version` directives and ShaderToy uniform declarations so we can
-///    inject our own definitions.
-/// 2. Prepend [`HEADER`] which declares the uniform block, sampler bindings, and
-///    macro aliases.
-/// 3. Append [`FOOTER`] which remaps `gl_FragCoord`, calls `mainImage`, and
-///    writes to `outColor`.
-fn wrap_shadertoy_fragment(source: &str) -> String {
+/// 1. Blank out `#version` directives and ShaderToy uniform declarations so we can
+///    inject our own definitions without shifting line numbers.
+/// 2. Prepend [`HEADER`] which declares the uniform block and macro aliases,
+///    followed by channel bindings typed after `channel_kinds` and [`HEADER_TAIL`].
+/// 3. Append the footer for `target`: [`FOOTER`] remaps `gl_FragCoord` for the
+///    image pass, [`BUFFER_FOOTER`] passes coordinates through for buffers, and
+///    [`CUBEMAP_FOOTER`] derives a face ray for `mainCubemap`, and [`SOUND_FOOTER`]
+///    turns texels into sample numbers for `mainSound`. All write to `outColor`.
+fn wrap_shadertoy_fragment(
+    source: &str,
+    target: PassTarget,
+    channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+) -> String {
     let mut sanitized = String::new();
     let mut skipped_version = false;
     let mut sanitized_lines = Vec::new();
     for line in source.lines() {
         if !skipped_version && line.trim_start().starts_with("#version") {
             skipped_version = true;
+            // Blank rather than drop stripped lines so `#line` numbering stays exact.
+            sanitized_lines.push("");
             continue;
         }
         let trimmed = line.trim_start();
@@ -120,6 +340,7 @@ fn wrap_shadertoy_fragment(source: &str) -> String {
                 || trimmed.contains("iChannel2")
                 || trimmed.contains("iChannel3"));
         if should_skip_uniform {
+            sanitized_lines.push("");
             continue;
         }
         sanitized_lines.push(line);
@@ -130,27 +351,133 @@ fn wrap_shadertoy_fragment(source: &str) -> String {
         sanitized.push('\n');
     }
 
+    let footer = match target {
+        PassTarget::Image => FOOTER,
+        PassTarget::Buffer => BUFFER_FOOTER,
+        PassTarget::Cubemap => CUBEMAP_FOOTER,
+        PassTarget::Sound if sound_takes_sample_index(&sanitized) => SOUND_FOOTER,
+        PassTarget::Sound => LEGACY_SOUND_FOOTER,
+    };
+    let channels = channel_declarations(channel_kinds);
+
     format!(
-        "{HEADER}\n#line 1\n{sanitized}{FOOTER}",
+        "{HEADER}{channels}{HEADER_TAIL}\n#line 1\n{sanitized}{footer}",
         sanitized = sanitized
     )
 }
 
+/// Returns true for `mainSound(int samp, float time)`; older ShaderToy sound
+/// shaders declare `mainSound(float time)`.
+fn sound_takes_sample_index(source: &str) -> bool {
+    let Some(start) = source.find("mainSound") else {
+        return true;
+    };
+    let params = source[start + "mainSound".len()..]
+        .trim_start()
+        .trim_start_matches('(')
+        .trim_start();
+    let params = params.strip_prefix("in ").unwrap_or(params).trim_start();
+    !params.starts_with("float")
+}
+
+/// Declares the texture/sampler pair and `iChannelN` macro for every channel.
+///
+/// Cubemap and volume channels must be declared as `textureCube`/`texture3D` so they
+/// match the `Cube`/`D3` views bound by `gpu::pipeline::build_channel_layout_entries`.
+fn channel_declarations(kinds: &[ChannelTextureKind; CHANNEL_COUNT]) -> String {
+    let mut declarations = String::new();
+    for (index, kind) in kinds.iter().enumerate() {
+        let (texture, _) = channel_glsl_types(*kind);
+        declarations.push_str(&format!(
+            "layout(set = 1, binding = {texture_binding}) uniform {texture} wax11_channel{index}_texture;\n\
+             layout(set = 1, binding = {sampler_binding}) uniform sampler wax11_channel{index}_sampler;\n",
+            texture_binding = index * 2,
+            sampler_binding = index * 2 + 1,
+        ));
+    }
+    declarations.push('\n');
+    for (index, kind) in kinds.iter().enumerate() {
+        let (_, sampler) = channel_glsl_types(*kind);
+        declarations.push_str(&format!(
+            "#define iChannel{index} {sampler}(wax11_channel{index}_texture, wax11_channel{index}_sampler)\n"
+        ));
+    }
+    declarations
+}
+
+/// Produces a WGSL module from a native WGSL pass.
+///
+/// The pass defines the entry function for its target — `main_image`,
+/// `main_cubemap` or `main_sound` — and the appended epilogue supplies the
+/// `@fragment fn main` that maps coordinates and encodes output exactly like
+/// the GLSL footers do.
+fn wrap_wgsl_fragment(
+    source: &str,
+    target: PassTarget,
+    channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+) -> String {
+    let footer = match target {
+        PassTarget::Image => WGSL_FOOTER,
+        PassTarget::Buffer => WGSL_BUFFER_FOOTER,
+        PassTarget::Cubemap => WGSL_CUBEMAP_FOOTER,
+        PassTarget::Sound => WGSL_SOUND_FOOTER,
+    };
+    format!(
+        "{prelude}\n{source}\n{footer}",
+        prelude = wgsl_prelude(channel_kinds)
+    )
+}
+
+/// WGSL declarations shared by every native WGSL pass.
+///
+/// `ShaderParams` mirrors the GLSL uniform block (and `ShadertoyUniforms`) at
+/// `@group(0) @binding(0)`. Channel `N` is bound at `@group(1)` as `channelN`
+/// (binding `2N`) and `channelN_sampler` (binding `2N + 1`), typed after the
+/// channel kind.
+fn wgsl_prelude(channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT]) -> String {
+    let mut prelude = String::from(WGSL_HEADER);
+    for (index, kind) in channel_kinds.iter().enumerate() {
+        let texture = match kind {
+            ChannelTextureKind::Texture2d => "texture_2d<f32>",
+            ChannelTextureKind::Cubemap => "texture_cube<f32>",
+            ChannelTextureKind::Texture3d => "texture_3d<f32>",
+        };
+        prelude.push_str(&format!(
+            "@group(1) @binding({texture_binding}) var channel{index}: {texture};\n\
+             @group(1) @binding({sampler_binding}) var channel{index}_sampler: sampler;\n",
+            texture_binding = index * 2,
+            sampler_binding = index * 2 + 1,
+        ));
+    }
+    prelude
+}
+
+/// GLSL texture and combined sampler type names for a channel kind.
+fn channel_glsl_types(kind: ChannelTextureKind) -> (&'static str, &'static str) {
+    match kind {
+        ChannelTextureKind::Texture2d => ("texture2D", "sampler2D"),
+        ChannelTextureKind::Cubemap => ("textureCube", "samplerCube"),
+        ChannelTextureKind::Texture3d => ("texture3D", "sampler3D"),
+    }
+}
+
 /// GLSL prologue injected ahead of every ShaderToy fragment shader.
 ///
-/// The uniform block layout must match [`ShadertoyUniforms`] in `gpu.rs`. Note that we keep
-/// `_iResolution` as a vec3 but reserve the fourth float for the mirrored
-/// `iTime`, ensuring the shader can animate even if vec3 padding is collapsed.
+/// The uniform block layout must match `ShadertoyUniforms` in `gpu/uniforms.rs`. std140
+/// packs a trailing float into the fourth slot of a vec3, so `_iTimeMirror` claims it
+/// explicitly (the host mirrors `iTime` there) and the fields after it line up with
+/// the host struct.
 const HEADER: &str = r"#version 450
 layout(location = 0) in vec2 v_uv;
 layout(location = 0) out vec4 outColor;
 
 layout(std140, set = 0, binding = 0) uniform ShaderParams {
     vec3 _iResolution;
+    float _iTimeMirror;
     float _iTime;
     float _iTimeDelta;
     int _iFrame;
-    float _padding0;
+    float _iCubeFace;
     vec4 _iMouse;
     vec4 _iDate;
     float _iSampleRate;
@@ -175,19 +502,10 @@ layout(std140, set = 0, binding = 0) uniform ShaderParams {
 #define iChannelTime ubo._iChannelTime
 #define iChannelResolution ubo._iChannelResolution
 
-layout(set = 1, binding = 0) uniform texture2D wax11_channel0_texture;
-layout(set = 1, binding = 1) uniform sampler wax11_channel0_sampler;
-layout(set = 1, binding = 2) uniform texture2D wax11_channel1_texture;
-layout(set = 1, binding = 3) uniform sampler wax11_channel1_sampler;
-layout(set = 1, binding = 4) uniform texture2D wax11_channel2_texture;
-layout(set = 1, binding = 5) uniform sampler wax11_channel2_sampler;
-layout(set = 1, binding = 6) uniform texture2D wax11_channel3_texture;
-layout(set = 1, binding = 7) uniform sampler wax11_channel3_sampler;
-
-#define iChannel0 sampler2D(wax11_channel0_texture, wax11_channel0_sampler)
-#define iChannel1 sampler2D(wax11_channel1_texture, wax11_channel1_sampler)
-#define iChannel2 sampler2D(wax11_channel2_texture, wax11_channel2_sampler)
-#define iChannel3 sampler2D(wax11_channel3_texture, wax11_channel3_sampler)
+";
+
+/// Remainder of the prologue, emitted after the generated channel declarations.
+const HEADER_TAIL: &str = r"#define wax11_CubeFace int(ubo._iCubeFace)
 #define wax11_Surface ubo._iSurface
 #define wax11_Fill ubo._iFill
 #define wax11_FillWrap ubo._iFillWrap
@@ -241,6 +559,181 @@ const FOOTER: &str = r"void main() {
 }
 ";
 
+/// GLSL epilogue for offscreen buffer passes.
+///
+/// Buffer targets are stored with row 0 holding `fragCoord.y == 0.5`, so the
+/// hardware coordinate is already in ShaderToy's bottom-left convention when
+/// sampled back through `iChannelN`.
+const BUFFER_FOOTER: &str = r"void main() {
+    #undef gl_FragCoord
+    vec2 fragCoord = gl_FragCoord.xy;
+    #define gl_FragCoord wax11_gl_FragCoord
+    wax11_gl_FragCoord = vec4(fragCoord, 0.0, 1.0);
+
+    vec4 color = vec4(0.0);
+    mainImage(color, fragCoord);
+    outColor = color;
+}
+";
+
+/// GLSL epilogue for one face of a cubemap pass.
+///
+/// Face order and orientation follow the Vulkan cubemap convention (+X, -X,
+/// +Y, -Y, +Z, -Z) with texel rows growing downwards, so the rendered layers
+/// sample back correctly through a `samplerCube`.
+const CUBEMAP_FOOTER: &str = r"void main() {
+    #undef gl_FragCoord
+    vec2 fragCoord = gl_FragCoord.xy;
+    #define gl_FragCoord wax11_gl_FragCoord
+    wax11_gl_FragCoord = vec4(fragCoord, 0.0, 1.0);
+
+    vec2 uv = fragCoord / iResolution.xy * 2.0 - 1.0;
+    vec3 rayDir;
+    switch (wax11_CubeFace) {
+        case 0: rayDir = vec3(1.0, -uv.y, -uv.x); break;
+        case 1: rayDir = vec3(-1.0, -uv.y, uv.x); break;
+        case 2: rayDir = vec3(uv.x, 1.0, uv.y); break;
+        case 3: rayDir = vec3(uv.x, -1.0, -uv.y); break;
+        case 4: rayDir = vec3(uv.x, -uv.y, 1.0); break;
+        default: rayDir = vec3(-uv.x, -uv.y, -1.0); break;
+    }
+
+    vec4 color = vec4(0.0);
+    mainCubemap(color, fragCoord, vec3(0.0), normalize(rayDir));
+    outColor = color;
+}
+";
+
+/// GLSL epilogue for one block of a sound pass.
+///
+/// Texels map to consecutive samples in row-major order; `iFrame` is the block
+/// index so sample numbers stay exact integers however long the export runs.
+/// Like ShaderToy, each channel is quantised to 16 bits and packed into two
+/// bytes (`rg` = left, `ba` = right) so any RGBA8 target can hold it.
+const SOUND_FOOTER: &str = r"void main() {
+    #undef gl_FragCoord
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    #define gl_FragCoord wax11_gl_FragCoord
+    int width = int(iResolution.x);
+    int samp = iFrame * width * int(iResolution.y) + texel.y * width + texel.x;
+    vec2 sound = mainSound(samp, float(samp) / iSampleRate);
+    vec2 level = min(floor((0.5 + 0.5 * clamp(sound, -1.0, 1.0)) * 65536.0), 65535.0);
+    vec2 low = mod(level, 256.0) / 255.0;
+    vec2 high = floor(level / 256.0) / 255.0;
+    outColor = vec4(low.x, high.x, low.y, high.y);
+}
+";
+
+/// [`SOUND_FOOTER`] for the legacy `vec2 mainSound(float time)` entry point.
+const LEGACY_SOUND_FOOTER: &str = r"void main() {
+    #undef gl_FragCoord
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    #define gl_FragCoord wax11_gl_FragCoord
+    int width = int(iResolution.x);
+    int samp = iFrame * width * int(iResolution.y) + texel.y * width + texel.x;
+    vec2 sound = mainSound(float(samp) / iSampleRate);
+    vec2 level = min(floor((0.5 + 0.5 * clamp(sound, -1.0, 1.0)) * 65536.0), 65535.0);
+    vec2 low = mod(level, 256.0) / 255.0;
+    vec2 high = floor(level / 256.0) / 255.0;
+    outColor = vec4(low.x, high.x, low.y, high.y);
+}
+";
+
+/// WGSL counterpart of [`HEADER`].
+///
+/// Uniform-address-space arrays need a 16-byte stride, so `channel_time` is an
+/// array of `vec4` with the time in `.x`, which is also how std140 lays out the
+/// GLSL `float[4]`.
+const WGSL_HEADER: &str = r"struct ShaderParams {
+    resolution: vec3<f32>,
+    time_mirror: f32,
+    time: f32,
+    time_delta: f32,
+    frame: i32,
+    cube_face: f32,
+    mouse: vec4<f32>,
+    date: vec4<f32>,
+    sample_rate: f32,
+    fade: f32,
+    padding: vec2<f32>,
+    channel_time: array<vec4<f32>, 4>,
+    channel_resolution: array<vec4<f32>, 4>,
+    surface: vec4<f32>,
+    fill: vec4<f32>,
+    fill_wrap: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> params: ShaderParams;
+
+";
+
+/// WGSL counterpart of [`FOOTER`]; calls `main_image(frag_coord) -> vec4<f32>`.
+const WGSL_FOOTER: &str = r"@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    var mapped = vec2<f32>(
+        position.x * params.fill.x + params.fill.z,
+        (params.surface.y - position.y) * params.fill.y + params.fill.w,
+    );
+    var outside = mapped.x < 0.0 || mapped.y < 0.0 || mapped.x >= params.resolution.x
+        || mapped.y >= params.resolution.y;
+    if params.fill_wrap.x > 0.0 {
+        mapped.x = mapped.x - floor(mapped.x / params.fill_wrap.x) * params.fill_wrap.x;
+        outside = false;
+    }
+    if params.fill_wrap.y > 0.0 {
+        mapped.y = mapped.y - floor(mapped.y / params.fill_wrap.y) * params.fill_wrap.y;
+        outside = false;
+    }
+    if outside {
+        return vec4<f32>(0.0);
+    }
+    let color = main_image(mapped);
+    return vec4<f32>(color.rgb * params.fade, params.fade);
+}
+";
+
+/// WGSL counterpart of [`BUFFER_FOOTER`].
+const WGSL_BUFFER_FOOTER: &str = r"@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return main_image(position.xy);
+}
+";
+
+/// WGSL counterpart of [`CUBEMAP_FOOTER`]; calls
+/// `main_cubemap(frag_coord, ray_origin, ray_dir) -> vec4<f32>`.
+const WGSL_CUBEMAP_FOOTER: &str = r"@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = position.xy / params.resolution.xy * 2.0 - 1.0;
+    var ray_dir: vec3<f32>;
+    switch i32(params.cube_face) {
+        case 0: { ray_dir = vec3<f32>(1.0, -uv.y, -uv.x); }
+        case 1: { ray_dir = vec3<f32>(-1.0, -uv.y, uv.x); }
+        case 2: { ray_dir = vec3<f32>(uv.x, 1.0, uv.y); }
+        case 3: { ray_dir = vec3<f32>(uv.x, -1.0, -uv.y); }
+        case 4: { ray_dir = vec3<f32>(uv.x, -uv.y, 1.0); }
+        default: { ray_dir = vec3<f32>(-uv.x, -uv.y, -1.0); }
+    }
+    return main_cubemap(position.xy, vec3<f32>(0.0), normalize(ray_dir));
+}
+";
+
+/// WGSL counterpart of [`SOUND_FOOTER`]; calls `main_sound(samp, time) -> vec2<f32>`.
+const WGSL_SOUND_FOOTER: &str = r"@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let texel = vec2<i32>(position.xy);
+    let width = i32(params.resolution.x);
+    let samp = params.frame * width * i32(params.resolution.y) + texel.y * width + texel.x;
+    let sound = main_sound(samp, f32(samp) / params.sample_rate);
+    let level = min(
+        floor((0.5 + 0.5 * clamp(sound, vec2<f32>(-1.0), vec2<f32>(1.0))) * 65536.0),
+        vec2<f32>(65535.0),
+    );
+    let low = (level % 256.0) / 255.0;
+    let high = floor(level / 256.0) / 255.0;
+    return vec4<f32>(low.x, high.x, low.y, high.y);
+}
+";
+
 /// Minimal full-screen triangle vertex shader.
 const VERTEX_SHADER_GLSL: &str = r"#version 450
 layout(location = 0) out vec2 v_uv;
@@ -265,18 +758,98 @@ fn compile_glsl(
     stage: ShaderStage,
     label: &'static str,
     compiler: ShaderCompiler,
-) -> Result<wgpu::ShaderModule> {
+    lines: &LineMap,
+    cache: Option<CacheScope>,
+) -> Result<CompiledShader> {
     match compiler {
-        ShaderCompiler::Shaderc => compile_with_shaderc(device, source, stage, label),
-        ShaderCompiler::NagaGlsl => Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some(label),
-            source: wgpu::ShaderSource::Glsl {
-                shader: Cow::Owned(source.to_owned()),
-                stage,
-                defines: &[],
-            },
-        })),
+        ShaderCompiler::Shaderc => compile_with_shaderc(device, source, stage, label, lines, cache),
+        ShaderCompiler::NagaGlsl => {
+            // wgpu panics on invalid GLSL, so surface naga's errors ourselves first.
+            let module = naga_glsl_module(source, stage)
+                .map_err(|errors| ShaderCompileError::new("naga glsl", errors, lines))?;
+            Ok(CompiledShader {
+                usage: ShaderUsage::from_module(&module),
+                module: device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some(label),
+                    source: wgpu::ShaderSource::Glsl {
+                        shader: Cow::Owned(source.to_owned()),
+                        stage,
+                        defines: &[],
+                    },
+                }),
+            })
+        }
+        ShaderCompiler::Auto => compile_with_fallback(device, source, stage, label, lines, cache),
+    }
+}
+
+/// Backend that last compiled each wrapped source under `ShaderCompiler::Auto`,
+/// keyed by [`source_hash`].
+static AUTO_BACKENDS: OnceLock<Mutex<HashMap<u64, ShaderCompiler>>> = OnceLock::new();
+
+/// Tries each concrete backend in [`auto_order`] until one accepts `source`.
+fn compile_with_fallback(
+    device: &wgpu::Device,
+    source: &str,
+    stage: ShaderStage,
+    label: &'static str,
+    lines: &LineMap,
+    cache: Option<CacheScope>,
+) -> Result<CompiledShader> {
+    let key = source_hash(source, stage);
+    let backends = AUTO_BACKENDS.get_or_init(Default::default);
+    let cached = backends
+        .lock()
+        .ok()
+        .and_then(|backends| backends.get(&key).copied());
+
+    let mut attempts = Vec::new();
+    for backend in auto_order(cached) {
+        match compile_glsl(device, source, stage, label, backend, lines, cache) {
+            Ok(module) => {
+                if !attempts.is_empty() {
+                    tracing::info!(label = label, compiler = %backend, "fell back to alternate shader compiler");
+                }
+                if let Ok(mut backends) = backends.lock() {
+                    backends.insert(key, backend);
+                }
+                return Ok(module);
+            }
+            Err(err) => {
+                let frontend = match backend {
+                    ShaderCompiler::Shaderc => "shaderc",
+                    _ => "naga glsl",
+                };
+                attempts.push(
+                    err.downcast::<ShaderCompileError>()
+                        .unwrap_or_else(|err| ShaderCompileError::unlocated(frontend, &err)),
+                );
+            }
+        }
     }
+    Err(ShaderFallbackError { attempts }.into())
+}
+
+/// Backends `Auto` tries: the cached winner first, otherwise the preferred one.
+/// Shaderc is skipped when it is not compiled in.
+fn auto_order(cached: Option<ShaderCompiler>) -> Vec<ShaderCompiler> {
+    let first = cached.unwrap_or_else(ShaderCompiler::preferred);
+    let order = if first == ShaderCompiler::NagaGlsl {
+        [ShaderCompiler::NagaGlsl, ShaderCompiler::Shaderc]
+    } else {
+        [ShaderCompiler::Shaderc, ShaderCompiler::NagaGlsl]
+    };
+    order
+        .into_iter()
+        .filter(|backend| cfg!(feature = "shaderc") || *backend != ShaderCompiler::Shaderc)
+        .collect()
+}
+
+fn source_hash(source: &str, stage: ShaderStage) -> u64 {
+    let mut hasher = key_hasher();
+    source.hash(&mut hasher);
+    stage.hash(&mut hasher);
+    hasher.finish()
 }
 
 #[cfg(feature = "shaderc")]
@@ -285,7 +858,34 @@ fn compile_with_shaderc(
     source: &str,
     stage: ShaderStage,
     label: &'static str,
-) -> Result<wgpu::ShaderModule> {
+    lines: &LineMap,
+    cache: Option<CacheScope>,
+) -> Result<CompiledShader> {
+    let cache_key = cache.map(|scope| scope.key(source, stage, ShaderCompiler::Shaderc));
+    if let Some(spirv) = cache
+        .zip(cache_key)
+        .and_then(|(scope, key)| scope.cache.load_spirv(key))
+    {
+        tracing::debug!(label = label, "using cached SPIR-V");
+        return Ok(spirv_shader(device, label, spirv));
+    }
+
+    let spirv = shaderc_spirv(source, stage, label, lines)?;
+    if let Some((scope, key)) = cache.zip(cache_key) {
+        scope.cache.store_spirv(key, &spirv);
+    }
+    Ok(spirv_shader(device, label, spirv))
+}
+
+/// Runs shaderc over wrapped GLSL, mapping its log through `lines` on failure.
+#[cfg(feature = "shaderc")]
+fn shaderc_spirv(
+    source: &str,
+    stage: ShaderStage,
+    label: &'static str,
+    lines: &LineMap,
+) -> Result<Vec<u32>> {
+    use crate::diagnostic::parse_glslang_log;
     use shaderc::{
         CompileOptions, Compiler, EnvVersion, OptimizationLevel, ShaderKind, SourceLanguage,
         TargetEnv,
@@ -308,20 +908,49 @@ fn compile_with_shaderc(
         other => return Err(anyhow!("unsupported shader stage: {other:?}")),
     };
 
-    let artifact = compiler
-        .compile_into_spirv(source, shader_kind, label, "main", Some(&options))
-        .with_context(|| format!("shaderc failed to compile {label}"))?;
+    let artifact =
+        match compiler.compile_into_spirv(source, shader_kind, label, "main", Some(&options)) {
+            Ok(artifact) => artifact,
+            Err(shaderc::Error::CompilationError(_, log)) => {
+                return Err(
+                    ShaderCompileError::new("shaderc", parse_glslang_log(&log), lines).into(),
+                );
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("shaderc failed to compile {label}"))
+            }
+        };
 
     let warnings = artifact.get_warning_messages();
     if !warnings.is_empty() {
         warn!(label = label, warnings = %warnings, "shaderc emitted warnings");
     }
 
-    let spirv = artifact.as_binary().to_vec();
-    Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some(label),
-        source: wgpu::ShaderSource::SpirV(Cow::Owned(spirv)),
-    }))
+    Ok(artifact.as_binary().to_vec())
+}
+
+#[cfg(feature = "shaderc")]
+fn spirv_shader(device: &wgpu::Device, label: &'static str, spirv: Vec<u32>) -> CompiledShader {
+    CompiledShader {
+        usage: ShaderUsage::from_spirv(&spirv),
+        module: device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::SpirV(Cow::Owned(spirv)),
+        }),
+    }
+}
+
+#[cfg(not(feature = "shaderc"))]
+fn shaderc_spirv(
+    _source: &str,
+    _stage: ShaderStage,
+    label: &'static str,
+    _lines: &LineMap,
+) -> Result<Vec<u32>> {
+    anyhow::bail!(
+        "shaderc support was not enabled at build time; cannot compile {}",
+        label
+    );
 }
 
 #[cfg(not(feature = "shaderc"))]
@@ -330,7 +959,9 @@ fn compile_with_shaderc(
     _source: &str,
     _stage: ShaderStage,
     label: &'static str,
-) -> Result<wgpu::ShaderModule> {
+    _lines: &LineMap,
+    _cache: Option<CacheScope>,
+) -> Result<CompiledShader> {
     anyhow::bail!(
         "shaderc support was not enabled at build time; cannot compile {}",
         label
@@ -340,6 +971,7 @@ fn compile_with_shaderc(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::preprocess::load_pass_source;
 
     #[test]
     fn wrap_strips_shadertoy_uniforms() {
@@ -352,10 +984,219 @@ mod tests {
             }
         "#;
 
-        let wrapped = wrap_shadertoy_fragment(source);
+        let wrapped = wrap_shadertoy_fragment(
+            source,
+            PassTarget::Image,
+            &[ChannelTextureKind::Texture2d; CHANNEL_COUNT],
+        );
         assert!(!wrapped.contains("uniform float iTime"));
         assert!(!wrapped.contains("uniform vec3 iResolution"));
         assert!(wrapped.contains("mainImage"));
         assert!(wrapped.contains("wax11_mix"));
     }
+
+    #[test]
+    fn buffer_footer_writes_raw_color() {
+        let source = "void mainImage(out vec4 c, in vec2 p) { c = vec4(p, 0.0, 0.25); }";
+        let wrapped = wrap_shadertoy_fragment(
+            source,
+            PassTarget::Buffer,
+            &[ChannelTextureKind::Texture2d; CHANNEL_COUNT],
+        );
+        assert!(wrapped.contains("outColor = color;"));
+        assert!(!wrapped.contains("color.rgb * wax11_mix"));
+    }
+
+    #[test]
+    fn cubemap_channels_declare_cube_samplers() {
+        let source =
+            "void mainCubemap(out vec4 c, in vec2 p, in vec3 o, in vec3 d) { c = vec4(d, 1.0); }";
+        let mut kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+        kinds[2] = ChannelTextureKind::Cubemap;
+        let wrapped = wrap_shadertoy_fragment(source, PassTarget::Cubemap, &kinds);
+        assert!(wrapped.contains("uniform textureCube wax11_channel2_texture;"));
+        assert!(wrapped.contains("#define iChannel2 samplerCube("));
+        assert!(wrapped.contains("#define iChannel0 sampler2D("));
+        assert!(wrapped.contains("mainCubemap(color, fragCoord"));
+    }
+
+    #[test]
+    fn volume_channels_declare_3d_samplers() {
+        let source =
+            "void mainImage(out vec4 c, in vec2 p) { c = texture(iChannel1, vec3(p, 0.5)); }";
+        let mut kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+        kinds[1] = ChannelTextureKind::Texture3d;
+        let wrapped = wrap_shadertoy_fragment(source, PassTarget::Image, &kinds);
+        assert!(wrapped.contains("uniform texture3D wax11_channel1_texture;"));
+        assert!(wrapped.contains("#define iChannel1 sampler3D("));
+    }
+
+    #[test]
+    fn sound_footer_matches_entry_signature() {
+        let kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+        let modern = "vec2 mainSound(int samp, float time) { return vec2(sin(time)); }";
+        let wrapped = wrap_shadertoy_fragment(modern, PassTarget::Sound, &kinds);
+        assert!(wrapped.contains("mainSound(samp, float(samp) / iSampleRate)"));
+
+        let legacy = "vec2 mainSound( in float time ) { return vec2(sin(time)); }";
+        let wrapped = wrap_shadertoy_fragment(legacy, PassTarget::Sound, &kinds);
+        assert!(wrapped.contains("mainSound(float(samp) / iSampleRate)"));
+    }
+
+    #[test]
+    fn include_line_markers_survive_wrapping() {
+        use wgpu::naga::front::glsl::{Frontend, Options};
+
+        let source = "#version 300 es\n#line 1 1\nfloat helper() { return 0.5; }\n#line 2 0\n\
+                      void mainImage(out vec4 c, in vec2 p) { c = vec4(helper()); }\n";
+        let wrapped = wrap_shadertoy_fragment(
+            source,
+            PassTarget::Image,
+            &[ChannelTextureKind::Texture2d; CHANNEL_COUNT],
+        );
+        assert!(wrapped.contains("#line 1\n\n#line 1 1\n"));
+        Frontend::default()
+            .parse(&Options::from(ShaderStage::Fragment), &wrapped)
+            .expect("naga accepts #line markers");
+    }
+
+    #[test]
+    fn naga_errors_map_to_pack_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let image = dir.join("image.glsl");
+        let helper = dir.join("helper.glsl");
+        std::fs::write(&helper, "float helper() {\n    return missing;\n}\n").unwrap();
+        std::fs::write(
+            &image,
+            "#include \"helper.glsl\"\nvoid mainImage(out vec4 c, in vec2 p) { c = vec4(helper()); }\n",
+        )
+        .unwrap();
+        let wgsl = dir.join("image.wgsl");
+        std::fs::write(
+            &wgsl,
+            "fn main_image(frag_coord: vec2<f32>) -> vec4<f32> {\n    return vec4<f32>(nope);\n}\n",
+        )
+        .unwrap();
+        let kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+
+        let source = load_pass_source(&image, ShaderLanguage::Glsl, &[]).unwrap();
+        let wrapped = wrap_shadertoy_fragment(&source.code, PassTarget::Image, &kinds);
+        let glsl = ShaderCompileError::new(
+            "naga glsl",
+            naga_glsl_module(&wrapped, ShaderStage::Fragment).unwrap_err(),
+            &LineMap::from_glsl(&wrapped, &source.files),
+        );
+
+        let source = load_pass_source(&wgsl, ShaderLanguage::Wgsl, &[]).unwrap();
+        let wrapped = wrap_wgsl_fragment(&source.code, PassTarget::Image, &kinds);
+        let body_line = wgsl_prelude(&kinds).lines().count() + 1;
+        let wgsl_error = ShaderCompileError::new(
+            "naga wgsl",
+            naga_wgsl_module(&wrapped).unwrap_err(),
+            &LineMap::from_offset(&wrapped, body_line, &source.files),
+        );
+
+        for (error, path) in [(&glsl, &helper), (&wgsl_error, &wgsl)] {
+            let diagnostic = &error.diagnostics[0];
+            let location = diagnostic.location.as_ref().expect("mapped to a pack file");
+            assert_eq!(&location.path, path, "{error}");
+            assert_eq!(location.line, 2, "{error}");
+            assert!(diagnostic.excerpt.as_deref().unwrap().contains('^'));
+        }
+    }
+
+    #[test]
+    fn suggests_the_kind_a_failing_channel_is_sampled_as() {
+        let source = PassSource {
+            code: "void mainImage(out vec4 c, in vec2 p) {\n\
+                   c = texture(iChannel0, p) + texture(iChannel1, vec3(p, 1.0));\n}\n"
+                .to_owned(),
+            language: ShaderLanguage::Glsl,
+            files: Vec::new(),
+        };
+        let kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+        assert_eq!(
+            channel_kind_mismatches(&source, PassTarget::Image, &kinds),
+            vec![(1, ChannelTextureKind::Cubemap)]
+        );
+    }
+
+    #[test]
+    fn wrapped_glsl_reflects_only_user_reads() {
+        let kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+        let usage = |body: &str| {
+            let wrapped = wrap_shadertoy_fragment(
+                &format!("void mainImage(out vec4 c, in vec2 p) {{ {body} }}"),
+                PassTarget::Image,
+                &kinds,
+            );
+            ShaderUsage::from_module(&naga_glsl_module(&wrapped, ShaderStage::Fragment).unwrap())
+                .unwrap()
+        };
+
+        assert!(usage("c = vec4(p / iResolution.xy, 0.0, 1.0);").is_time_independent());
+        assert!(usage("c = vec4(sin(iTime));").time);
+        assert!(usage("c = texture(iChannel2, p);").channels[2]);
+    }
+
+    #[test]
+    fn auto_tries_the_cached_backend_first() {
+        let expected = if cfg!(feature = "shaderc") {
+            vec![ShaderCompiler::NagaGlsl, ShaderCompiler::Shaderc]
+        } else {
+            vec![ShaderCompiler::NagaGlsl]
+        };
+        assert_eq!(auto_order(Some(ShaderCompiler::NagaGlsl)), expected);
+        assert_eq!(auto_order(None)[0], ShaderCompiler::preferred());
+        assert_ne!(
+            source_hash("void main() {}", ShaderStage::Fragment),
+            source_hash("void main() {}", ShaderStage::Vertex)
+        );
+    }
+
+    fn validate_wgsl(source: &str) {
+        use wgpu::naga::valid::{Capabilities, ValidationFlags, Validator};
+
+        let module = wgpu::naga::front::wgsl::parse_str(source)
+            .unwrap_or_else(|err| panic!("{}", err.emit_to_string(source)));
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .expect("wrapped WGSL validates");
+    }
+
+    #[test]
+    fn wgsl_passes_validate_against_prelude() {
+        let mut kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
+        kinds[1] = ChannelTextureKind::Cubemap;
+        kinds[2] = ChannelTextureKind::Texture3d;
+        let image = r"
+            fn main_image(frag_coord: vec2<f32>) -> vec4<f32> {
+                let uv = frag_coord / params.resolution.xy;
+                let flat = textureSample(channel0, channel0_sampler, uv);
+                let cube = textureSample(channel1, channel1_sampler, vec3<f32>(uv, 1.0));
+                let volume = textureSample(channel2, channel2_sampler, vec3<f32>(uv, params.time));
+                return flat + cube + volume + vec4<f32>(params.channel_time[0].x);
+            }
+        ";
+        let wrapped = wrap_wgsl_fragment(image, PassTarget::Image, &kinds);
+        assert!(wrapped.contains("var channel1: texture_cube<f32>;"));
+        assert!(wrapped.contains("@group(1) @binding(5) var channel2_sampler: sampler;"));
+        validate_wgsl(&wrapped);
+        validate_wgsl(&wrap_wgsl_fragment(image, PassTarget::Buffer, &kinds));
+
+        let cubemap = r"
+            fn main_cubemap(frag_coord: vec2<f32>, ray_origin: vec3<f32>, ray_dir: vec3<f32>) -> vec4<f32> {
+                return vec4<f32>(ray_dir * 0.5 + 0.5, 1.0);
+            }
+        ";
+        validate_wgsl(&wrap_wgsl_fragment(cubemap, PassTarget::Cubemap, &kinds));
+
+        let sound = r"
+            fn main_sound(samp: i32, time: f32) -> vec2<f32> {
+                return vec2<f32>(sin(6.2831 * 440.0 * time));
+            }
+        ";
+        validate_wgsl(&wrap_wgsl_fragment(sound, PassTarget::Sound, &kinds));
+    }
 }
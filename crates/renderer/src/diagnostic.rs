@@ -0,0 +1,519 @@
+//! Shader compile diagnostics mapped back to pack source files.
+//!
+//! Compilers only ever see the wrapped shader (prelude + expanded pass +
+//! footer), so their positions need translating before they are useful:
+//!
+//! ```text
+//!   shaderc: "1:14: error: 'fbm' : no matching overloaded function"
+//!        │  logical (#line source, line)
+//!        │                      naga: Span in the wrapped text
+//!        │                            │  physical line → LineMap (#line markers)
+//!        ▼                            ▼
+//!   SourceLocation { lib/noise.glsl, line 14, column } + excerpt with caret
+//!        ▼
+//!   ShaderCompileError { frontend, diagnostics }
+//! ```
+//!
+//! Positions that land in wax11's own prelude or footer stay unlocated; the
+//! message is still reported, just without an excerpt. `ShaderCompiler::Auto`
+//! reports one [`ShaderCompileError`] per backend in a [`ShaderFallbackError`].
+
+use std::fmt;
+use std::path::PathBuf;
+
+use wgpu::naga::ShaderStage;
+
+/// Shader compilation failure with every diagnostic mapped to the pack sources.
+///
+/// Returned (inside `anyhow::Error`) by pipeline creation; callers can
+/// `downcast_ref::<ShaderCompileError>()` to inspect individual diagnostics.
+#[derive(Debug, Clone)]
+pub struct ShaderCompileError {
+    /// Compiler that rejected the shader (`shaderc`, `naga glsl`, `naga wgsl`).
+    pub frontend: &'static str,
+    pub diagnostics: Vec<ShaderDiagnostic>,
+}
+
+/// One compiler message.
+#[derive(Debug, Clone)]
+pub struct ShaderDiagnostic {
+    pub message: String,
+    /// Where the message points, when that is inside a pack file.
+    pub location: Option<SourceLocation>,
+    /// The offending source line followed by a caret line marking the column.
+    pub excerpt: Option<String>,
+}
+
+/// Position inside a pack source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, when the compiler reported one.
+    pub column: Option<usize>,
+}
+
+/// A compiler message positioned in the wrapped source, before mapping.
+#[derive(Debug, Clone)]
+pub(crate) struct RawDiagnostic {
+    pub message: String,
+    pub position: RawPosition,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum RawPosition {
+    /// 1-based line/column in the wrapped text; naga spans ignore `#line`.
+    Wrapped {
+        line: usize,
+        column: usize,
+        length: usize,
+    },
+    /// `source:line` after `#line` remapping, as glslang reports it.
+    Logical {
+        source: usize,
+        line: usize,
+    },
+    Unknown,
+}
+
+/// Maps lines of a wrapped shader back to the pack files they came from.
+pub(crate) struct LineMap {
+    /// `(file index, 1-based line)` for every physical line of the wrapped text.
+    lines: Vec<Option<(usize, usize)>>,
+    files: Vec<PathBuf>,
+    sources: Vec<Option<String>>,
+}
+
+impl LineMap {
+    /// Follows the `#line` markers emitted by `compile` and `preprocess`.
+    ///
+    /// Lines before the first marker belong to the prelude; `#line N` without a
+    /// source number enters the pass file (source 0).
+    pub(crate) fn from_glsl(wrapped: &str, files: &[PathBuf]) -> Self {
+        let mut lines = Vec::new();
+        let mut current: Option<(usize, usize)> = None;
+        for text in wrapped.lines() {
+            if let Some((line, source)) = parse_line_directive(text) {
+                let source = source.or(current.map(|(file, _)| file)).unwrap_or(0);
+                current = Some((source, line));
+                lines.push(None);
+                continue;
+            }
+            lines.push(current);
+            if let Some((_, line)) = current.as_mut() {
+                *line += 1;
+            }
+        }
+        Self::new(lines, files)
+    }
+
+    /// Maps a WGSL module whose pass body starts at `body_line` (0-based physical line).
+    pub(crate) fn from_offset(wrapped: &str, body_line: usize, files: &[PathBuf]) -> Self {
+        let lines = (0..wrapped.lines().count())
+            .map(|physical| {
+                physical
+                    .checked_sub(body_line)
+                    .map(|offset| (0, offset + 1))
+            })
+            .collect();
+        Self::new(lines, files)
+    }
+
+    fn new(lines: Vec<Option<(usize, usize)>>, files: &[PathBuf]) -> Self {
+        let sources = files
+            .iter()
+            .map(|path| std::fs::read_to_string(path).ok())
+            .collect();
+        Self {
+            lines,
+            files: files.to_vec(),
+            sources,
+        }
+    }
+
+    fn resolve(&self, diagnostic: RawDiagnostic) -> ShaderDiagnostic {
+        let (file, line, column, length) = match diagnostic.position {
+            RawPosition::Wrapped {
+                line,
+                column,
+                length,
+            } => match self.lines.get(line.wrapping_sub(1)).copied().flatten() {
+                Some((file, line)) => (file, line, Some(column), length),
+                None => return unlocated(diagnostic.message),
+            },
+            RawPosition::Logical { source, line } => (source, line, None, 0),
+            RawPosition::Unknown => return unlocated(diagnostic.message),
+        };
+        // Lines past the end of the file are the generated footer.
+        let Some(text) = self
+            .sources
+            .get(file)
+            .and_then(Option::as_deref)
+            .and_then(|source| source.lines().nth(line.wrapping_sub(1)))
+        else {
+            return unlocated(diagnostic.message);
+        };
+
+        let (column, length) = match column {
+            Some(column) => (column, length.max(1)),
+            None => quoted_token_column(&diagnostic.message, text).unwrap_or_else(|| {
+                let indent = text.len() - text.trim_start().len();
+                (indent + 1, 1)
+            }),
+        };
+        let length = length.min(
+            text.chars()
+                .count()
+                .saturating_sub(column.saturating_sub(1))
+                .max(1),
+        );
+        let caret_indent: String = text
+            .chars()
+            .take(column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let excerpt = format!("{text}\n{caret_indent}{}", "^".repeat(length));
+        ShaderDiagnostic {
+            message: diagnostic.message,
+            location: Some(SourceLocation {
+                path: self.files[file].clone(),
+                line,
+                column: Some(column),
+            }),
+            excerpt: Some(excerpt),
+        }
+    }
+}
+
+impl ShaderCompileError {
+    pub(crate) fn new(
+        frontend: &'static str,
+        diagnostics: Vec<RawDiagnostic>,
+        lines: &LineMap,
+    ) -> Self {
+        Self {
+            frontend,
+            diagnostics: diagnostics
+                .into_iter()
+                .map(|diagnostic| lines.resolve(diagnostic))
+                .collect(),
+        }
+    }
+}
+
+impl ShaderCompileError {
+    /// Wraps a failure that produced no compiler log (e.g. shaderc missing).
+    pub(crate) fn unlocated(frontend: &'static str, error: &anyhow::Error) -> Self {
+        Self {
+            frontend,
+            diagnostics: vec![unlocated(format!("{error:#}"))],
+        }
+    }
+}
+
+/// Every backend tried by `ShaderCompiler::Auto` rejected the shader.
+#[derive(Debug, Clone)]
+pub struct ShaderFallbackError {
+    /// One error per backend, in the order they were tried.
+    pub attempts: Vec<ShaderCompileError>,
+}
+
+impl fmt::Display for ShaderFallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no shader compiler accepted the shader")?;
+        for attempt in &self.attempts {
+            write!(f, "\n\n{attempt}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShaderFallbackError {}
+
+impl fmt::Display for ShaderCompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.diagnostics.len();
+        write!(
+            f,
+            "{} reported {count} error{}",
+            self.frontend,
+            if count == 1 { "" } else { "s" }
+        )?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShaderCompileError {}
+
+impl fmt::Display for ShaderDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error: {}", self.message)?;
+        let Some(location) = &self.location else {
+            return write!(f, " (in the generated wrapper)");
+        };
+        write!(f, "\n  --> {}:{}", location.path.display(), location.line)?;
+        if let Some(column) = location.column {
+            write!(f, ":{column}")?;
+        }
+        if let Some(excerpt) = &self.excerpt {
+            let gutter = location.line.to_string().len();
+            let mut lines = excerpt.lines();
+            let code = lines.next().unwrap_or_default();
+            let caret = lines.next().unwrap_or_default();
+            write!(
+                f,
+                "\n{:gutter$} |\n{} | {code}\n{:gutter$} | {caret}",
+                "", location.line, ""
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a shaderc/glslang log into diagnostics.
+///
+/// Lines look like `label:12: error: 'x' : undeclared identifier` or, straight
+/// from glslang, `ERROR: 1:12: 'x' : undeclared identifier`. A numeric label is
+/// a `#line` source number; the compile label itself stands for source 0.
+pub(crate) fn parse_glslang_log(log: &str) -> Vec<RawDiagnostic> {
+    let mut diagnostics = Vec::new();
+    for entry in log.lines().map(str::trim).filter(|entry| !entry.is_empty()) {
+        let entry = entry.strip_prefix("ERROR: ").unwrap_or(entry);
+        if entry.starts_with("WARNING: ") || entry.ends_with("compilation terminated") {
+            continue;
+        }
+        let Some((source, line, rest)) = split_glslang_location(entry) else {
+            if !entry.contains("error generated") && !entry.contains("errors generated") {
+                diagnostics.push(RawDiagnostic {
+                    message: entry.to_owned(),
+                    position: RawPosition::Unknown,
+                });
+            }
+            continue;
+        };
+        if rest.starts_with("warning:") {
+            continue;
+        }
+        let message = rest.strip_prefix("error:").unwrap_or(rest).trim();
+        if message.ends_with("compilation terminated") {
+            continue;
+        }
+        diagnostics.push(RawDiagnostic {
+            message: message.to_owned(),
+            position: RawPosition::Logical {
+                source: source.parse().unwrap_or(0),
+                line,
+            },
+        });
+    }
+    diagnostics
+}
+
+/// Splits `name:line: rest` at the first `:<digits>: `.
+fn split_glslang_location(entry: &str) -> Option<(&str, usize, &str)> {
+    for (index, _) in entry.match_indices(':') {
+        let after = &entry[index + 1..];
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            continue;
+        }
+        if let Some(rest) = after[digits..].strip_prefix(": ") {
+            let line = after[..digits].parse().ok()?;
+            return Some((&entry[..index], line, rest));
+        }
+    }
+    None
+}
+
+/// Converts a naga span location in the wrapped text into a raw diagnostic.
+pub(crate) fn wrapped_diagnostic(
+    message: String,
+    location: Option<wgpu::naga::SourceLocation>,
+) -> RawDiagnostic {
+    let position = match location {
+        Some(location) => RawPosition::Wrapped {
+            line: location.line_number as usize,
+            column: location.line_position as usize,
+            length: location.length as usize,
+        },
+        None => RawPosition::Unknown,
+    };
+    RawDiagnostic { message, position }
+}
+
+/// Parses and validates wrapped GLSL with naga, collecting every error.
+pub(crate) fn naga_glsl_module(
+    wrapped: &str,
+    stage: ShaderStage,
+) -> Result<wgpu::naga::Module, Vec<RawDiagnostic>> {
+    use wgpu::naga::front::glsl::{Frontend, Options};
+
+    let module = Frontend::default()
+        .parse(&Options::from(stage), wrapped)
+        .map_err(|errors| {
+            errors
+                .errors
+                .iter()
+                .map(|error| wrapped_diagnostic(error.kind.to_string(), error.location(wrapped)))
+                .collect::<Vec<_>>()
+        })?;
+    validate(module, wrapped)
+}
+
+/// Parses and validates a wrapped WGSL module with naga.
+pub(crate) fn naga_wgsl_module(wrapped: &str) -> Result<wgpu::naga::Module, Vec<RawDiagnostic>> {
+    let module = wgpu::naga::front::wgsl::parse_str(wrapped).map_err(|error| {
+        let mut message = error.message().to_owned();
+        for (_, label) in error.labels().filter(|(_, label)| !label.is_empty()) {
+            message = format!("{message} ({label})");
+        }
+        vec![wrapped_diagnostic(message, error.location(wrapped))]
+    })?;
+    validate(module, wrapped)
+}
+
+fn validate(
+    module: wgpu::naga::Module,
+    wrapped: &str,
+) -> Result<wgpu::naga::Module, Vec<RawDiagnostic>> {
+    use wgpu::naga::valid::{Capabilities, ValidationFlags, Validator};
+
+    match Validator::new(ValidationFlags::all(), Capabilities::all()).validate(&module) {
+        Ok(_) => Ok(module),
+        Err(error) => Err(vec![wrapped_diagnostic(
+            error_chain(&error),
+            error.location(wrapped),
+        )]),
+    }
+}
+
+/// Joins an error with its sources; naga validation errors nest the useful detail.
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(inner) = source {
+        message = format!("{message}: {inner}");
+        source = inner.source();
+    }
+    message
+}
+
+/// Parses `#line N` / `#line N M` into `(N, Some(M))`.
+fn parse_line_directive(text: &str) -> Option<(usize, Option<usize>)> {
+    let rest = text.trim_start().strip_prefix('#')?.trim_start();
+    let mut parts = rest.strip_prefix("line")?.split_whitespace();
+    let line = parts.next()?.parse().ok()?;
+    let source = parts.next().and_then(|source| source.parse().ok());
+    Some((line, source))
+}
+
+/// Column and width of a glslang `'token' : …` message within `line`.
+fn quoted_token_column(message: &str, line: &str) -> Option<(usize, usize)> {
+    let token = message.strip_prefix('\'')?.split('\'').next()?;
+    if token.is_empty() {
+        return None;
+    }
+    let byte = line.find(token)?;
+    Some((line[..byte].chars().count() + 1, token.chars().count()))
+}
+
+fn unlocated(message: String) -> ShaderDiagnostic {
+    ShaderDiagnostic {
+        message,
+        location: None,
+        excerpt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn scratch_file(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn maps_shaderc_log_through_include_markers() {
+        let temp = tempfile::tempdir().unwrap();
+        let image = scratch_file(
+            temp.path(),
+            "image.glsl",
+            "#include \"noise.glsl\"\nvoid mainImage() {}\n",
+        );
+        let noise = scratch_file(
+            temp.path(),
+            "noise.glsl",
+            "float noise(vec2 p) {\n    return fbm(p);\n}\n",
+        );
+        let wrapped = "#version 450\n#line 1\n#line 1 1\nfloat noise…\n#line 2 0\nvoid main…\n";
+        let lines = LineMap::from_glsl(wrapped, &[image.clone(), noise.clone()]);
+        let log = "wax11 fragment:40: warning: unused\n\
+                   1:2: error: 'fbm' : no matching overloaded function found\n\
+                   1:2: error: '' : compilation terminated\n\
+                   2 errors generated.\n";
+        let error = ShaderCompileError::new("shaderc", parse_glslang_log(log), &lines);
+
+        assert_eq!(error.diagnostics.len(), 1);
+        let diagnostic = &error.diagnostics[0];
+        let location = diagnostic.location.as_ref().expect("mapped location");
+        assert_eq!(location.path, noise);
+        assert_eq!((location.line, location.column), (2, Some(12)));
+        assert_eq!(
+            diagnostic.excerpt.as_deref(),
+            Some("    return fbm(p);\n           ^^^")
+        );
+        let rendered = error.to_string();
+        assert!(rendered.starts_with("shaderc reported 1 error\nerror: 'fbm'"));
+        assert!(rendered.contains("2 |     return fbm(p);"));
+    }
+
+    #[test]
+    fn maps_wrapped_spans_and_leaves_footer_unlocated() {
+        let temp = tempfile::tempdir().unwrap();
+        let image = scratch_file(
+            temp.path(),
+            "spans.glsl",
+            "void mainImage(out vec4 c, in vec2 p) {\n  c = oops;\n}\n",
+        );
+        let wrapped = "prelude\n#line 1\nvoid mainImage(out vec4 c, in vec2 p) {\n  c = oops;\n}\nvoid main() {}\n";
+        let lines = LineMap::from_glsl(wrapped, std::slice::from_ref(&image));
+        let raws = vec![
+            wrapped_diagnostic(
+                "unknown variable".into(),
+                Some(wgpu::naga::SourceLocation {
+                    line_number: 4,
+                    line_position: 7,
+                    offset: 0,
+                    length: 4,
+                }),
+            ),
+            wrapped_diagnostic(
+                "bad footer".into(),
+                Some(wgpu::naga::SourceLocation {
+                    line_number: 6,
+                    line_position: 1,
+                    offset: 0,
+                    length: 4,
+                }),
+            ),
+        ];
+        let error = ShaderCompileError::new("naga glsl", raws, &lines);
+
+        let first = &error.diagnostics[0];
+        assert_eq!(first.location.as_ref().map(|l| l.line), Some(2));
+        assert_eq!(first.excerpt.as_deref(), Some("  c = oops;\n      ^^^^"));
+        assert!(error.diagnostics[1].location.is_none());
+        assert!(error
+            .to_string()
+            .contains("bad footer (in the generated wrapper)"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Headless exports: one still, a numbered frame sequence, or an animated loop.
+//!
+//! ```text
+//!   RenderPolicy::Export ──▶ GpuState::new_headless (offscreen, scaled, RGBA8 or RGBA16F)
+//!     ├─ no sequence         ──▶ render_export            ──▶ out.png / out.exr
+//!     ├─ sequence, PNG/EXR   ──▶ render_export per frame  ──▶ out-0000.png, out-0001.png, …
+//!     └─ sequence, GIF/APNG  ──▶ render_rgba8 × (frames + crossfade)
+//!                                  ──▶ capture::crossfade_loop ──▶ out.gif / out.apng
+//! ```
+//!
+//! Every frame comes from one `GpuState` and a stepped `FixedTimeSource`, so
+//! buffer feedback carries across the sequence and the output does not depend
+//! on how quickly frames render.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::gpu::{self, FileExportTarget, GpuState};
+use crate::runtime::{time_source_for_policy, ExportFormat, ExportSequence, RenderPolicy};
+use crate::types::RendererConfig;
+
+/// Renders `config.policy` (which must be an export) without any window or compositor.
+pub(crate) fn run(config: &RendererConfig) -> Result<()> {
+    let RenderPolicy::Export {
+        path,
+        format,
+        sequence,
+        ..
+    } = &config.policy
+    else {
+        return Err(anyhow!("headless export requires the export render policy"));
+    };
+    let mut state = GpuState::new_headless(config)?;
+    tracing::debug!(
+        adapter = %state.adapter_profile().name,
+        software = state.adapter_profile().is_software(),
+        "rendering export headlessly"
+    );
+    let mut time_source = time_source_for_policy(&config.policy)?;
+
+    match sequence {
+        None if format.is_animated() => Err(anyhow!(
+            "{format:?} export needs a frame sequence (frame count and step)"
+        )),
+        None => {
+            let target = FileExportTarget {
+                path: path.clone(),
+                format: *format,
+            };
+            let path = state.render_export([0.0; 4], Some(time_source.sample()), &target)?;
+            tracing::info!("still frame captured at {}", path.display());
+            Ok(())
+        }
+        Some(sequence) if format.is_animated() => {
+            let (mut width, mut height, mut frames) = (0, 0, Vec::new());
+            for index in 0..sequence.frames + sequence.crossfade {
+                let pixels;
+                (width, height, pixels) = state
+                    .render_rgba8([0.0; 4], Some(time_source.sample()))
+                    .with_context(|| format!("failed to render loop frame {index}"))?;
+                frames.push(pixels);
+            }
+            write_loop(path, *format, sequence, width, height, frames)?;
+            tracing::info!(
+                frames = sequence.frames,
+                crossfade = sequence.crossfade,
+                "animated loop exported to {}",
+                path.display()
+            );
+            Ok(())
+        }
+        Some(sequence) => {
+            for index in 0..sequence.frames {
+                let target = FileExportTarget {
+                    path: sequence.frame_path(path, index),
+                    format: *format,
+                };
+                state
+                    .render_export([0.0; 4], Some(time_source.sample()), &target)
+                    .with_context(|| {
+                        format!(
+                            "failed to export sequence frame {index} of {}",
+                            sequence.frames
+                        )
+                    })?;
+            }
+            tracing::info!(
+                frames = sequence.frames,
+                first = %sequence.frame_path(path, 0).display(),
+                "frame sequence exported"
+            );
+            Ok(())
+        }
+    }
+}
+
+fn write_loop(
+    path: &Path,
+    format: ExportFormat,
+    sequence: &ExportSequence,
+    width: u32,
+    height: u32,
+    frames: Vec<Vec<u8>>,
+) -> Result<()> {
+    let frames = gpu::crossfade_loop(frames, sequence.crossfade as usize);
+    match format {
+        ExportFormat::Gif => gpu::write_gif(path, width, height, frames, sequence.step),
+        ExportFormat::Apng => gpu::write_apng(path, width, height, frames, sequence.step),
+        ExportFormat::Png | ExportFormat::Exr => {
+            Err(anyhow!("{format:?} is not an animated export format"))
+        }
+    }
+}
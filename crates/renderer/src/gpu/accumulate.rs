@@ -0,0 +1,273 @@
+//! Sample accumulation for progressive stills.
+//!
+//! Each refinement sample renders the image pass into an offscreen target with
+//! the pixel grid shifted by a sub-pixel jitter. The sample is then blended
+//! into a float accumulation target with weight `1 / (n + 1)`, which keeps a
+//! running mean, and the mean is copied to the frame so every sample presents
+//! the best image so far. Averaging is linear, as for every `BLIT_SHADER` pass.
+//!
+//! ```text
+//! image pass (jittered) ──▶ sample target ──blend 1/(n+1)──▶ accumulation (RGBA16F)
+//!                                                                  │
+//!                                                      frame ◀──copy┘
+//! ```
+
+use winit::dpi::PhysicalSize;
+
+use super::mipmap::BLIT_SHADER;
+
+const ACCUMULATION_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Element `index` of the Halton low-discrepancy sequence in `base`, in [0, 1).
+pub(crate) fn halton(mut index: u32, base: u32) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// Sub-pixel offset for sample `index`; the first sample stays on pixel centres.
+pub(crate) fn sample_jitter(index: u32) -> [f32; 2] {
+    if index == 0 {
+        return [0.0; 2];
+    }
+    [halton(index, 2) - 0.5, halton(index, 3) - 0.5]
+}
+
+/// Sample and accumulation targets plus the passes that blend and present them.
+pub(crate) struct Accumulator {
+    _sample_texture: wgpu::Texture,
+    sample_view: wgpu::TextureView,
+    _accumulation_texture: wgpu::Texture,
+    accumulation_view: wgpu::TextureView,
+    accumulate_pipeline: wgpu::RenderPipeline,
+    accumulate_bind_group: wgpu::BindGroup,
+    present_pipeline: wgpu::RenderPipeline,
+    present_bind_group: wgpu::BindGroup,
+    samples: u32,
+}
+
+impl Accumulator {
+    /// Builds empty targets for an `output` sized frame in `format`.
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        output: PhysicalSize<u32>,
+    ) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("accumulate shader"),
+            source: wgpu::ShaderSource::Wgsl(BLIT_SHADER.into()),
+        });
+        // Blends the new sample over the running mean with the weight set as the blend constant.
+        let running_mean = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Constant,
+            dst_factor: wgpu::BlendFactor::OneMinusConstant,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let accumulate_pipeline = Self::create_pipeline(
+            device,
+            &module,
+            "accumulate pipeline",
+            wgpu::ColorTargetState {
+                format: ACCUMULATION_FORMAT,
+                blend: Some(wgpu::BlendState {
+                    color: running_mean,
+                    alpha: running_mean,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            },
+        );
+        let present_pipeline = Self::create_pipeline(
+            device,
+            &module,
+            "accumulate present pipeline",
+            format.into(),
+        );
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("accumulate sampler"),
+            ..Default::default()
+        });
+
+        let (sample_texture, sample_view) =
+            Self::create_target(device, "accumulate sample target", format, output);
+        let (accumulation_texture, accumulation_view) =
+            Self::create_target(device, "accumulation target", ACCUMULATION_FORMAT, output);
+        let accumulate_bind_group =
+            Self::create_bind_group(device, &accumulate_pipeline, &sample_view, &sampler);
+        let present_bind_group =
+            Self::create_bind_group(device, &present_pipeline, &accumulation_view, &sampler);
+        Self {
+            _sample_texture: sample_texture,
+            sample_view,
+            _accumulation_texture: accumulation_texture,
+            accumulation_view,
+            accumulate_pipeline,
+            accumulate_bind_group,
+            present_pipeline,
+            present_bind_group,
+            samples: 0,
+        }
+    }
+
+    /// View of the target the image pass renders the next sample into.
+    pub(crate) fn view(&self) -> &wgpu::TextureView {
+        &self.sample_view
+    }
+
+    /// Discards the running mean so the next sample starts a new one.
+    pub(crate) fn reset(&mut self) {
+        self.samples = 0;
+    }
+
+    /// Encodes the passes that fold the sample target into the running mean
+    /// and copy the mean to `target`.
+    pub(crate) fn encode(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+    ) {
+        let weight = 1.0 / f64::from(self.samples + 1);
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("accumulate pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.accumulation_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.accumulate_pipeline);
+            pass.set_bind_group(0, &self.accumulate_bind_group, &[]);
+            pass.set_blend_constant(wgpu::Color {
+                r: weight,
+                g: weight,
+                b: weight,
+                a: weight,
+            });
+            pass.draw(0..3, 0..1);
+        }
+        self.samples += 1;
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("accumulate present pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.present_pipeline);
+        pass.set_bind_group(0, &self.present_bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        module: &wgpu::ShaderModule,
+        label: &str,
+        target: wgpu::ColorTargetState,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(target)],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    fn create_target(
+        device: &wgpu::Device,
+        label: &str,
+        format: wgpu::TextureFormat,
+        size: PhysicalSize<u32>,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        pipeline: &wgpu::RenderPipeline,
+        source: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("accumulate bind group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_starts_centred_and_stays_inside_the_pixel() {
+        assert_eq!(sample_jitter(0), [0.0, 0.0]);
+        assert_eq!(halton(1, 2), 0.5);
+        assert_eq!(halton(3, 2), 0.75);
+        assert!((halton(2, 3) - 2.0 / 3.0).abs() < 1e-6);
+        for index in 1..64 {
+            let [x, y] = sample_jitter(index);
+            assert!((-0.5..0.5).contains(&x) && (-0.5..0.5).contains(&y));
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Copies a rendered frame back to the CPU and writes it to disk.
+//!
+//! ```text
+//!   frame texture (surface with COPY_SRC, or the headless offscreen target)
+//!     │  copy_texture_to_buffer (rows padded to 256 bytes)
+//!     ▼
+//!   mapped buffer ──▶ tightly packed rows
+//!     ├─ RGBA8 (BGRA swizzled)          ──▶ PNG
+//!     │    └─ × N (+ crossfade_loop)     ──▶ GIF / APNG
+//!     └─ RGBA16F (sRGB decoded if gamma) ──▶ linear-light EXR
+//! ```
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Reads an 8-bit RGBA or BGRA texture into tightly packed RGBA rows.
+pub(crate) fn read_rgba8(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Result<Vec<u8>> {
+    let swap_red_blue = match texture.format() {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        other => bail!("cannot capture frames in {other:?}; expected an 8-bit RGBA target"),
+    };
+    let mut pixels = read_texture(device, queue, texture, 4)?;
+    if swap_red_blue {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    Ok(pixels)
+}
+
+/// Reads an `Rgba16Float` texture into tightly packed RGBA `f32` rows.
+pub(crate) fn read_rgba16f(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Result<Vec<f32>> {
+    if texture.format() != wgpu::TextureFormat::Rgba16Float {
+        bail!(
+            "cannot capture HDR frames in {:?}; expected an Rgba16Float target",
+            texture.format()
+        );
+    }
+    let bytes = read_texture(device, queue, texture, 8)?;
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|half| half::f16::from_le_bytes([half[0], half[1]]).to_f32())
+        .collect())
+}
+
+fn read_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    bytes_per_pixel: u32,
+) -> Result<Vec<u8>> {
+    let (width, height) = (texture.width(), texture.height());
+    let row_bytes = width * bytes_per_pixel;
+    let padded_row_bytes = row_bytes.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("frame readback"),
+        size: u64::from(padded_row_bytes) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("frame readback encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device
+        .poll(wgpu::PollType::Wait)
+        .map_err(|err| anyhow!("GPU poll failed: {err}"))?;
+    receiver
+        .recv()
+        .map_err(|_| anyhow!("readback callback dropped"))?
+        .map_err(|err| anyhow!("failed to map frame readback: {err}"))?;
+
+    let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+    {
+        let mapped = slice.get_mapped_range();
+        for row in mapped.chunks_exact(padded_row_bytes as usize) {
+            pixels.extend_from_slice(&row[..row_bytes as usize]);
+        }
+    }
+    readback.unmap();
+    Ok(pixels)
+}
+
+/// Writes RGBA8 pixels as an opaque PNG.
+pub(crate) fn write_png(path: &Path, width: u32, height: u32, mut pixels: Vec<u8>) -> Result<()> {
+    make_opaque(&mut pixels);
+    let image = image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| anyhow!("captured frame does not match {width}x{height}"))?;
+    create_parent_dir(path)?;
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// NeuQuant sampling speed for GIF palettes (1 = best, 30 = fastest); 10 is the
+/// `gif` crate's default trade-off.
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+/// Folds the last `crossfade` frames, rendered past the end of the loop, over
+/// the first ones: frame `i` fades from the continuation into the start, so the
+/// jump from the final frame back to frame 0 follows the shader's own motion.
+pub(crate) fn crossfade_loop(mut frames: Vec<Vec<u8>>, crossfade: usize) -> Vec<Vec<u8>> {
+    let loop_frames = frames.len().saturating_sub(crossfade);
+    let tail = frames.split_off(loop_frames);
+    for (index, (head, tail)) in frames.iter_mut().zip(&tail).enumerate() {
+        let weight = (index + 1) as f32 / (tail.len() + 1) as f32;
+        for (start, continuation) in head.iter_mut().zip(tail) {
+            let (start_value, continuation_value) = (f32::from(*start), f32::from(*continuation));
+            *start =
+                (continuation_value + (start_value - continuation_value) * weight).round() as u8;
+        }
+    }
+    frames
+}
+
+/// Writes RGBA8 frames as an endlessly looping GIF, quantising each frame to
+/// its own 256-colour palette.
+pub(crate) fn write_gif(
+    path: &Path,
+    width: u32,
+    height: u32,
+    frames: Vec<Vec<u8>>,
+    step: f32,
+) -> Result<()> {
+    use image::codecs::gif::{GifEncoder, Repeat};
+
+    create_parent_dir(path)?;
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), GIF_QUANTIZE_SPEED);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .context("failed to start GIF")?;
+    let delay = image::Delay::from_saturating_duration(Duration::from_secs_f32(step));
+    for mut pixels in frames {
+        make_opaque(&mut pixels);
+        let image = image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| anyhow!("captured frame does not match {width}x{height}"))?;
+        encoder
+            .encode_frame(image::Frame::from_parts(image, 0, 0, delay))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Writes RGBA8 frames as an endlessly looping APNG.
+pub(crate) fn write_apng(
+    path: &Path,
+    width: u32,
+    height: u32,
+    frames: Vec<Vec<u8>>,
+    step: f32,
+) -> Result<()> {
+    create_parent_dir(path)?;
+    let write = || -> Result<(), png::EncodingError> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames.len() as u32, 0)?;
+        let (numerator, denominator) = apng_delay(step);
+        encoder.set_frame_delay(numerator, denominator)?;
+        let mut writer = encoder.write_header()?;
+        for mut pixels in frames {
+            make_opaque(&mut pixels);
+            writer.write_image_data(&pixels)?;
+        }
+        writer.finish()
+    };
+    write().with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// APNG delays are a `u16` fraction of a second: exact for whole frame rates,
+/// milliseconds otherwise.
+fn apng_delay(step: f32) -> (u16, u16) {
+    let fps = 1.0 / step;
+    if (fps - fps.round()).abs() < 1e-3 && (1.0..=f32::from(u16::MAX)).contains(&fps.round()) {
+        (1, fps.round() as u16)
+    } else {
+        (
+            (step * 1000.0).round().clamp(1.0, f32::from(u16::MAX)) as u16,
+            1000,
+        )
+    }
+}
+
+/// Writes RGBA `f32` pixels as a linear-light EXR. Gamma-mode shaders output
+/// sRGB-encoded values, so those are decoded first; values above 1.0 pass
+/// through untouched. Alpha is forced opaque as for PNG.
+pub(crate) fn write_exr(
+    path: &Path,
+    width: u32,
+    height: u32,
+    mut pixels: Vec<f32>,
+    decode_srgb: bool,
+) -> Result<()> {
+    for pixel in pixels.chunks_exact_mut(4) {
+        if decode_srgb {
+            for channel in &mut pixel[..3] {
+                *channel = srgb_to_linear(*channel);
+            }
+        }
+        pixel[3] = 1.0;
+    }
+    let image = image::Rgba32FImage::from_raw(width, height, pixels)
+        .ok_or_else(|| anyhow!("captured frame does not match {width}x{height}"))?;
+    create_parent_dir(path)?;
+    image
+        .save_with_format(path, image::ImageFormat::OpenExr)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// The sRGB transfer function, extended past 1.0 and mirrored below 0.0.
+fn srgb_to_linear(value: f32) -> f32 {
+    let magnitude = value.abs();
+    let linear = if magnitude <= 0.04045 {
+        magnitude / 12.92
+    } else {
+        ((magnitude + 0.055) / 1.055).powf(2.4)
+    };
+    linear.copysign(value)
+}
+
+/// ShaderToy presents the image pass without blending; drop whatever alpha a
+/// shader left behind.
+fn make_opaque(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel[3] = u8::MAX;
+    }
+}
+
+fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use super::*;
+
+    #[test]
+    fn png_is_written_opaque_into_missing_directories() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("nested").join("frame.png");
+        write_png(&path, 2, 1, vec![255, 0, 0, 0, 0, 0, 255, 128]).unwrap();
+
+        let image = image::open(&path).unwrap().to_rgba8();
+        assert_eq!(image.as_raw(), &[255, 0, 0, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn crossfade_fades_the_continuation_into_the_first_frames() {
+        // Four loop frames plus one rendered past the end.
+        let frames = vec![vec![0], vec![10], vec![20], vec![30], vec![40]];
+        assert_eq!(
+            crossfade_loop(frames.clone(), 1),
+            [vec![20], vec![10], vec![20], vec![30]]
+        );
+        assert_eq!(crossfade_loop(frames, 0).len(), 5);
+    }
+
+    #[test]
+    fn animated_loops_round_trip_frame_counts() {
+        use image::AnimationDecoder;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let frames = vec![[255, 0, 0, 0].repeat(4), [0, 0, 255, 0].repeat(4)];
+        write_gif(&dir.join("loop.gif"), 2, 2, frames.clone(), 0.1).unwrap();
+        write_apng(&dir.join("loop.apng"), 2, 2, frames, 1.0 / 30.0).unwrap();
+
+        let gif = image::codecs::gif::GifDecoder::new(BufReader::new(
+            File::open(dir.join("loop.gif")).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(gif.into_frames().count(), 2);
+        let apng = image::codecs::png::PngDecoder::new(BufReader::new(
+            File::open(dir.join("loop.apng")).unwrap(),
+        ))
+        .unwrap();
+        assert!(apng.is_apng().unwrap());
+        assert_eq!(apng.apng().unwrap().into_frames().count(), 2);
+        assert_eq!(apng_delay(1.0 / 30.0), (1, 30));
+        assert_eq!(apng_delay(0.0425), (43, 1000));
+    }
+
+    #[test]
+    fn exr_keeps_hdr_values_in_linear_light() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("frame.exr");
+        write_exr(
+            &path,
+            2,
+            1,
+            vec![0.5, 4.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0],
+            true,
+        )
+        .unwrap();
+
+        let image = image::open(&path).unwrap().to_rgba32f();
+        let pixels = image.as_raw();
+        assert!((pixels[0] - 0.214).abs() < 1e-3, "{pixels:?}");
+        assert!(pixels[1] > 20.0, "{pixels:?}");
+        assert_eq!(&pixels[3..5], &[1.0, 1.0]);
+    }
+}
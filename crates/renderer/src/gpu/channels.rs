@@ -1,15 +1,23 @@
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use anyhow::{anyhow, Context, Result};
 use image::imageops::flip_vertical_in_place;
 use image::GenericImageView;
 use wgpu::util::{DeviceExt, TextureDataOrder};
 
+use crate::animation::AnimatedImage;
+use crate::audio::{
+    self, AudioStream, LiveCapture, PendingClip, AUDIO_TEXTURE_HEIGHT, AUDIO_TEXTURE_WIDTH,
+};
 use crate::types::{
-    ChannelBindings, ChannelSource, ChannelTextureKind, CHANNEL_COUNT, CUBEMAP_FACE_STEMS,
+    ChannelBindings, ChannelFilter, ChannelPlayback, ChannelSampler, ChannelSource,
+    ChannelTextureKind, ChannelWrap, LiveAudio, CHANNEL_COUNT, CUBEMAP_FACE_STEMS,
 };
+use crate::volume;
 
 use super::context::SurfaceColorSpace;
+use super::mipmap::{self, MipmapGenerator};
 
 pub(crate) const KEYBOARD_TEXTURE_WIDTH: u32 = 256;
 pub(crate) const KEYBOARD_TEXTURE_HEIGHT: u32 = 3;
@@ -21,13 +29,100 @@ pub(crate) struct ChannelResources {
     pub sampler: wgpu::Sampler,
     pub resolution: [f32; 4],
     keyboard: bool,
+    audio: Option<Mutex<AudioStream>>,
+    animation: Option<Mutex<AnimationState>>,
+}
+
+/// Decoded frames of an animated channel plus the frame currently uploaded.
+struct AnimationState {
+    image: AnimatedImage,
+    playback: ChannelPlayback,
+    shown: usize,
 }
 
 impl ChannelResources {
+    /// Replaces the default sampler with one built from the channel's settings.
+    fn with_sampler(mut self, device: &wgpu::Device, settings: ChannelSampler) -> Self {
+        self.sampler = create_channel_sampler(device, settings);
+        self
+    }
+
     pub(crate) fn is_keyboard(&self) -> bool {
         self.keyboard
     }
 
+    /// Sample rate of a live audio channel, reported through `iSampleRate`.
+    pub(crate) fn live_sample_rate(&self) -> Option<u32> {
+        self.audio
+            .as_ref()?
+            .lock()
+            .expect("audio stream poisoned")
+            .live_sample_rate()
+    }
+
+    /// Re-analyses an audio channel at shader time `seconds` and uploads the new
+    /// FFT/waveform rows. Returns the playback position for `iChannelTime`, or
+    /// `None` when this is not an audio channel.
+    pub(crate) fn update_audio(&self, queue: &wgpu::Queue, seconds: f32) -> Option<f32> {
+        let mut stream = self.audio.as_ref()?.lock().expect("audio stream poisoned");
+        let (position, texels) = stream.update(seconds);
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(AUDIO_TEXTURE_WIDTH),
+                rows_per_image: Some(AUDIO_TEXTURE_HEIGHT),
+            },
+            wgpu::Extent3d {
+                width: AUDIO_TEXTURE_WIDTH,
+                height: AUDIO_TEXTURE_HEIGHT,
+                depth_or_array_layers: 1,
+            },
+        );
+        Some(position)
+    }
+
+    /// Uploads the frame due at shader time `seconds` if it changed. Returns the
+    /// playback position for `iChannelTime`, or `None` when this is not an animated channel.
+    pub(crate) fn update_animation(&self, queue: &wgpu::Queue, seconds: f32) -> Option<f32> {
+        let mut state = self
+            .animation
+            .as_ref()?
+            .lock()
+            .expect("animation state poisoned");
+        let (frame, position) = state.image.frame_at(seconds, state.playback);
+        if frame != state.shown {
+            let (width, height) = (state.image.width, state.image.height);
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                state.image.frame(frame),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * 4),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+            state.shown = frame;
+        }
+        Some(position)
+    }
+
     pub(crate) fn update_keyboard(&self, queue: &wgpu::Queue, data: &[u8]) {
         if !self.keyboard {
             return;
@@ -67,18 +162,34 @@ impl ChannelResources {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create_resources(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
+    mipmaps: &MipmapGenerator,
     bindings: &ChannelBindings,
     kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+    sampled: &[bool; CHANNEL_COUNT],
     color_space: SurfaceColorSpace,
+    sound: Option<&PendingClip>,
 ) -> Result<Vec<ChannelResources>> {
     let mut resources = Vec::with_capacity(CHANNEL_COUNT);
     for (index, (binding, kind)) in bindings.slots().iter().zip(kinds.iter()).enumerate() {
+        let settings = bindings.sampler(index);
+        // Channels the shader never reads keep a placeholder instead of decoding
+        // images or opening audio streams nobody will see.
+        let binding = binding.as_ref().filter(|_| sampled[index]);
         let resource = match (binding, kind) {
             (Some(ChannelSource::Texture { path }), ChannelTextureKind::Texture2d) => {
-                match load_texture_channel(device, queue, index, path, color_space) {
+                match load_texture_channel(
+                    device,
+                    queue,
+                    mipmaps,
+                    index,
+                    path,
+                    color_space,
+                    settings,
+                ) {
                     Ok(resource) => resource,
                     Err(error) => {
                         tracing::warn!(
@@ -92,7 +203,15 @@ pub(crate) fn create_resources(
                 }
             }
             (Some(ChannelSource::Cubemap { directory }), ChannelTextureKind::Cubemap) => {
-                match load_cubemap_channel(device, queue, index, directory, color_space) {
+                match load_cubemap_channel(
+                    device,
+                    queue,
+                    mipmaps,
+                    index,
+                    directory,
+                    color_space,
+                    settings,
+                ) {
                     Ok(resource) => resource,
                     Err(error) => {
                         tracing::warn!(
@@ -105,18 +224,121 @@ pub(crate) fn create_resources(
                     }
                 }
             }
+            (Some(ChannelSource::Volume { path }), ChannelTextureKind::Texture3d) => {
+                match load_volume_channel(device, queue, index, path, settings) {
+                    Ok(resource) => resource,
+                    Err(error) => {
+                        tracing::warn!(
+                            channel = index,
+                            path = %path.display(),
+                            error = %error,
+                            "failed to load volume channel; using placeholder volume"
+                        );
+                        create_placeholder_volume(device, queue, index as u32)
+                    }
+                }
+            }
+            (Some(ChannelSource::Animation { path, playback }), ChannelTextureKind::Texture2d) => {
+                match load_animation_channel(
+                    device,
+                    queue,
+                    index,
+                    path,
+                    *playback,
+                    color_space,
+                    settings,
+                ) {
+                    Ok(resource) => resource,
+                    Err(error) => {
+                        tracing::warn!(
+                            channel = index,
+                            path = %path.display(),
+                            error = %error,
+                            "failed to load animated channel; using placeholder texture"
+                        );
+                        create_placeholder_texture(device, queue, index as u32, color_space)?
+                    }
+                }
+            }
             (Some(ChannelSource::Keyboard), ChannelTextureKind::Texture2d) => {
                 create_keyboard_channel(device, queue, index as u32, color_space)?
             }
+            (Some(ChannelSource::Audio { path }), ChannelTextureKind::Texture2d) => {
+                match load_audio_channel(device, index, path) {
+                    Ok(resource) => resource.with_sampler(device, settings),
+                    Err(error) => {
+                        tracing::warn!(
+                            channel = index,
+                            path = %path.display(),
+                            error = %error,
+                            "failed to load audio channel; using placeholder"
+                        );
+                        create_placeholder_texture(device, queue, index as u32, color_space)?
+                    }
+                }
+            }
+            (Some(ChannelSource::LiveAudio(live)), ChannelTextureKind::Texture2d) => {
+                match open_live_audio_channel(device, index, live) {
+                    Ok(resource) => resource.with_sampler(device, settings),
+                    Err(error) => {
+                        tracing::warn!(
+                            channel = index,
+                            input = ?live.input,
+                            error = %error,
+                            "failed to open live audio channel; using placeholder"
+                        );
+                        create_placeholder_texture(device, queue, index as u32, color_space)?
+                    }
+                }
+            }
+            (Some(ChannelSource::SoundPass { name }), ChannelTextureKind::Texture2d) => match sound
+            {
+                Some(clip) => {
+                    create_audio_channel(device, index, AudioStream::from_pending(clip.clone()))
+                        .with_sampler(device, settings)
+                }
+                None => {
+                    tracing::warn!(
+                        channel = index,
+                        pass = %name,
+                        "sound pass output unavailable; using placeholder"
+                    );
+                    create_placeholder_texture(device, queue, index as u32, color_space)?
+                }
+            },
+            // Buffer outputs are swapped in per frame; the placeholder keeps the slot valid
+            // and carries the sampler used when reading the pass.
+            (Some(ChannelSource::Buffer { .. }), ChannelTextureKind::Texture2d) => {
+                create_placeholder_texture(device, queue, index as u32, color_space)?
+                    .with_sampler(device, settings)
+            }
+            (Some(ChannelSource::CubemapPass { .. }), ChannelTextureKind::Cubemap) => {
+                create_placeholder_cubemap(device, queue, index as u32, color_space)?
+                    .with_sampler(device, settings)
+            }
             (None, ChannelTextureKind::Texture2d) => {
                 create_placeholder_texture(device, queue, index as u32, color_space)?
             }
             (None, ChannelTextureKind::Cubemap) => {
                 create_placeholder_cubemap(device, queue, index as u32, color_space)?
             }
+            (None, ChannelTextureKind::Texture3d) => {
+                create_placeholder_volume(device, queue, index as u32)
+            }
             (Some(ChannelSource::Texture { .. }), ChannelTextureKind::Cubemap)
             | (Some(ChannelSource::Cubemap { .. }), ChannelTextureKind::Texture2d)
-            | (Some(ChannelSource::Keyboard), ChannelTextureKind::Cubemap) => {
+            | (Some(ChannelSource::Animation { .. }), ChannelTextureKind::Cubemap)
+            | (Some(ChannelSource::Keyboard), ChannelTextureKind::Cubemap)
+            | (Some(ChannelSource::Audio { .. }), ChannelTextureKind::Cubemap)
+            | (Some(ChannelSource::LiveAudio(_)), ChannelTextureKind::Cubemap)
+            | (Some(ChannelSource::SoundPass { .. }), ChannelTextureKind::Cubemap)
+            | (Some(ChannelSource::Buffer { .. }), ChannelTextureKind::Cubemap)
+            | (Some(ChannelSource::CubemapPass { .. }), ChannelTextureKind::Texture2d)
+            | (
+                Some(ChannelSource::Volume { .. }),
+                ChannelTextureKind::Texture2d | ChannelTextureKind::Cubemap,
+            )
+            | (Some(_), ChannelTextureKind::Texture3d) => {
                 tracing::warn!(
                     channel = index,
                     "channel binding kind mismatch; using placeholder resource"
@@ -128,6 +350,9 @@ pub(crate) fn create_resources(
                     ChannelTextureKind::Cubemap => {
                         create_placeholder_cubemap(device, queue, index as u32, color_space)?
                     }
+                    ChannelTextureKind::Texture3d => {
+                        create_placeholder_volume(device, queue, index as u32)
+                    }
                 }
             }
         };
@@ -137,6 +362,126 @@ pub(crate) fn create_resources(
     Ok(resources)
 }
 
+/// Updates every audio and animated channel in `resources` for shader time `seconds`,
+/// returning the per-channel playback positions to feed into `iChannelTime`.
+pub(crate) fn update_timed_channels(
+    resources: &[ChannelResources],
+    queue: &wgpu::Queue,
+    seconds: f32,
+) -> [Option<f32>; CHANNEL_COUNT] {
+    std::array::from_fn(|channel| {
+        let resource = resources.get(channel)?;
+        resource
+            .update_audio(queue, seconds)
+            .or_else(|| resource.update_animation(queue, seconds))
+    })
+}
+
+/// Builds a sampler matching ShaderToy's `filter`/`wrap` sampler options.
+fn create_channel_sampler(device: &wgpu::Device, settings: ChannelSampler) -> wgpu::Sampler {
+    let address_mode = match settings.wrap {
+        ChannelWrap::Clamp => wgpu::AddressMode::ClampToEdge,
+        ChannelWrap::Repeat => wgpu::AddressMode::Repeat,
+    };
+    let (filter, mipmap_filter) = match settings.filter {
+        ChannelFilter::Nearest => (wgpu::FilterMode::Nearest, wgpu::FilterMode::Nearest),
+        ChannelFilter::Linear => (wgpu::FilterMode::Linear, wgpu::FilterMode::Nearest),
+        ChannelFilter::Mipmap => (wgpu::FilterMode::Linear, wgpu::FilterMode::Linear),
+    };
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("channel sampler"),
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        address_mode_w: address_mode,
+        mag_filter: filter,
+        min_filter: filter,
+        mipmap_filter,
+        ..Default::default()
+    })
+}
+
+/// RGBA8 format for image channels: explicit `srgb` wins, otherwise follow the surface.
+fn image_texture_format(
+    color_space: SurfaceColorSpace,
+    settings: ChannelSampler,
+) -> wgpu::TextureFormat {
+    let srgb = settings
+        .srgb
+        .unwrap_or(color_space == SurfaceColorSpace::Linear);
+    if srgb {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    }
+}
+
+/// Uploads decoded RGBA8 layers, allocating and filling a full mip chain when the
+/// channel samples with `filter = "mipmap"`.
+#[allow(clippy::too_many_arguments)]
+fn upload_image_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    mipmaps: &MipmapGenerator,
+    label: &str,
+    (width, height, layers): (u32, u32, u32),
+    format: wgpu::TextureFormat,
+    settings: ChannelSampler,
+    data: &[u8],
+) -> wgpu::Texture {
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: layers,
+    };
+    if settings.filter != ChannelFilter::Mipmap {
+        return device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some(label),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            TextureDataOrder::LayerMajor,
+            data,
+        );
+    }
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size,
+        mip_level_count: mipmap::mip_level_count(width, height),
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(width * 4),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    mipmaps.generate(device, queue, &texture);
+    texture
+}
+
 fn create_placeholder_texture(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
@@ -185,9 +530,66 @@ fn create_placeholder_texture(
         sampler,
         resolution: [1.0, 1.0, 1.0, 0.0],
         keyboard: false,
+        audio: None,
+        animation: None,
     })
 }
 
+fn create_placeholder_volume(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    index: u32,
+) -> ChannelResources {
+    let texture = create_volume_texture(
+        device,
+        queue,
+        &format!("placeholder volume #{index}"),
+        (1, 1, 1),
+        &[255u8, 255, 255, 25],
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let sampler = create_channel_sampler(device, ChannelSampler::default());
+
+    ChannelResources {
+        texture,
+        view,
+        sampler,
+        resolution: [1.0, 1.0, 1.0, 0.0],
+        keyboard: false,
+        audio: None,
+        animation: None,
+    }
+}
+
+/// 3D `Rgba8Unorm` texture; volumes hold data, so they are never sRGB-decoded.
+fn create_volume_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    label: &str,
+    (width, height, depth): (u32, u32, u32),
+    data: &[u8],
+) -> wgpu::Texture {
+    device.create_texture_with_data(
+        queue,
+        &wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: depth,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        TextureDataOrder::LayerMajor,
+        data,
+    )
+}
+
 fn create_placeholder_cubemap(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
@@ -246,6 +648,8 @@ fn create_placeholder_cubemap(
         sampler,
         resolution: [1.0, 1.0, 6.0, 0.0],
         keyboard: false,
+        audio: None,
+        animation: None,
     })
 }
 
@@ -305,15 +709,108 @@ fn create_keyboard_channel(
             0.0,
         ],
         keyboard: true,
+        audio: None,
+        animation: None,
     })
 }
 
+fn load_audio_channel(
+    device: &wgpu::Device,
+    index: usize,
+    path: &Path,
+) -> Result<ChannelResources> {
+    let clip = audio::decode_audio_file(path)
+        .with_context(|| format!("failed to decode audio for channel {index}"))?;
+    tracing::debug!(
+        channel = index,
+        path = %path.display(),
+        sample_rate = clip.sample_rate(),
+        duration = clip.duration_seconds(),
+        "decoded audio channel"
+    );
+    Ok(create_audio_channel(
+        device,
+        index,
+        AudioStream::from_clip(clip),
+    ))
+}
+
+fn open_live_audio_channel(
+    device: &wgpu::Device,
+    index: usize,
+    live: &LiveAudio,
+) -> Result<ChannelResources> {
+    let capture = LiveCapture::open(live)
+        .with_context(|| format!("failed to open live audio for channel {index}"))?;
+    tracing::debug!(
+        channel = index,
+        input = ?live.input,
+        sample_rate = live.sample_rate,
+        "attached live audio channel"
+    );
+    Ok(create_audio_channel(
+        device,
+        index,
+        AudioStream::from_live(capture),
+    ))
+}
+
+fn create_audio_channel(
+    device: &wgpu::Device,
+    index: usize,
+    stream: AudioStream,
+) -> ChannelResources {
+    // Spectrum and waveform bytes are data, not colour, so the format stays linear
+    // regardless of the surface colour space. Contents are uploaded every frame.
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(&format!("audio channel texture #{index}")),
+        size: wgpu::Extent3d {
+            width: AUDIO_TEXTURE_WIDTH,
+            height: AUDIO_TEXTURE_HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    ChannelResources {
+        texture,
+        view,
+        sampler,
+        resolution: [
+            AUDIO_TEXTURE_WIDTH as f32,
+            AUDIO_TEXTURE_HEIGHT as f32,
+            1.0,
+            0.0,
+        ],
+        keyboard: false,
+        audio: Some(Mutex::new(stream)),
+        animation: None,
+    }
+}
+
 fn load_texture_channel(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
+    mipmaps: &MipmapGenerator,
     index: usize,
     path: &Path,
     color_space: SurfaceColorSpace,
+    settings: ChannelSampler,
 ) -> Result<ChannelResources> {
     let image = image::open(path).with_context(|| {
         format!(
@@ -324,42 +821,23 @@ fn load_texture_channel(
     })?;
     let (width, height) = image.dimensions();
     let mut rgba = image.to_rgba8();
-    flip_vertical_in_place(&mut rgba);
+    if settings.vflip {
+        flip_vertical_in_place(&mut rgba);
+    }
 
-    let texture_format = match color_space {
-        SurfaceColorSpace::Gamma => wgpu::TextureFormat::Rgba8Unorm,
-        SurfaceColorSpace::Linear => wgpu::TextureFormat::Rgba8UnormSrgb,
-    };
-    let texture = device.create_texture_with_data(
+    let texture = upload_image_texture(
+        device,
         queue,
-        &wgpu::TextureDescriptor {
-            label: Some(&format!("channel texture #{index}")),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: texture_format,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        },
-        TextureDataOrder::LayerMajor,
+        mipmaps,
+        &format!("channel texture #{index}"),
+        (width, height, 1),
+        image_texture_format(color_space, settings),
+        settings,
         &rgba,
     );
 
     let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-        address_mode_u: wgpu::AddressMode::ClampToEdge,
-        address_mode_v: wgpu::AddressMode::ClampToEdge,
-        address_mode_w: wgpu::AddressMode::ClampToEdge,
-        mag_filter: wgpu::FilterMode::Linear,
-        min_filter: wgpu::FilterMode::Linear,
-        mipmap_filter: wgpu::FilterMode::Linear,
-        ..Default::default()
-    });
+    let sampler = create_channel_sampler(device, settings);
 
     Ok(ChannelResources {
         texture,
@@ -367,15 +845,19 @@ fn load_texture_channel(
         sampler,
         resolution: [width as f32, height as f32, 1.0, 0.0],
         keyboard: false,
+        audio: None,
+        animation: None,
     })
 }
 
 fn load_cubemap_channel(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
+    mipmaps: &MipmapGenerator,
     index: usize,
     directory: &Path,
     color_space: SurfaceColorSpace,
+    settings: ChannelSampler,
 ) -> Result<ChannelResources> {
     if !directory.is_dir() {
         anyhow::bail!(
@@ -417,58 +899,132 @@ fn load_cubemap_channel(
     let mut data = Vec::with_capacity((width * height * 4 * 6) as usize);
     for (_, face) in faces {
         let mut rgba = face.to_rgba8();
-        flip_vertical_in_place(&mut rgba);
+        if settings.vflip {
+            flip_vertical_in_place(&mut rgba);
+        }
         data.extend_from_slice(&rgba);
     }
 
-    let texture_format = match color_space {
-        SurfaceColorSpace::Gamma => wgpu::TextureFormat::Rgba8Unorm,
-        SurfaceColorSpace::Linear => wgpu::TextureFormat::Rgba8UnormSrgb,
-    };
+    let texture = upload_image_texture(
+        device,
+        queue,
+        mipmaps,
+        &format!("cubemap texture #{index}"),
+        (width, height, 6),
+        image_texture_format(color_space, settings),
+        settings,
+        &data,
+    );
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some(&format!("cubemap view #{index}")),
+        dimension: Some(wgpu::TextureViewDimension::Cube),
+        array_layer_count: Some(6),
+        ..Default::default()
+    });
+
+    let sampler = create_channel_sampler(device, settings);
 
+    Ok(ChannelResources {
+        texture,
+        view,
+        sampler,
+        resolution: [width as f32, height as f32, 6.0, 0.0],
+        keyboard: false,
+        audio: None,
+        animation: None,
+    })
+}
+
+/// Animated channels upload one frame at a time, so they never carry a mip chain;
+/// `filter = "mipmap"` samples them like `linear`.
+fn load_animation_channel(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    index: usize,
+    path: &Path,
+    playback: ChannelPlayback,
+    color_space: SurfaceColorSpace,
+    settings: ChannelSampler,
+) -> Result<ChannelResources> {
+    let image = AnimatedImage::load(path, settings.vflip)?;
+    let (width, height) = (image.width, image.height);
     let texture = device.create_texture_with_data(
         queue,
         &wgpu::TextureDescriptor {
-            label: Some(&format!("cubemap texture #{index}")),
+            label: Some(&format!("animated channel texture #{index}")),
             size: wgpu::Extent3d {
                 width,
                 height,
-                depth_or_array_layers: 6,
+                depth_or_array_layers: 1,
             },
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: texture_format,
+            format: image_texture_format(color_space, settings),
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         },
         TextureDataOrder::LayerMajor,
-        &data,
+        image.frame(0),
+    );
+    tracing::debug!(
+        channel = index,
+        frames = image.frame_count(),
+        width,
+        height,
+        "loaded animated channel"
     );
 
-    let view = texture.create_view(&wgpu::TextureViewDescriptor {
-        label: Some(&format!("cubemap view #{index}")),
-        dimension: Some(wgpu::TextureViewDimension::Cube),
-        array_layer_count: Some(6),
-        ..Default::default()
-    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let sampler = create_channel_sampler(device, settings);
 
-    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-        address_mode_u: wgpu::AddressMode::ClampToEdge,
-        address_mode_v: wgpu::AddressMode::ClampToEdge,
-        address_mode_w: wgpu::AddressMode::ClampToEdge,
-        mag_filter: wgpu::FilterMode::Linear,
-        min_filter: wgpu::FilterMode::Linear,
-        mipmap_filter: wgpu::FilterMode::Linear,
-        ..Default::default()
-    });
+    Ok(ChannelResources {
+        texture,
+        view,
+        sampler,
+        resolution: [width as f32, height as f32, 1.0, 0.0],
+        keyboard: false,
+        audio: None,
+        animation: Some(Mutex::new(AnimationState {
+            image,
+            playback,
+            shown: 0,
+        })),
+    })
+}
+
+fn load_volume_channel(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    index: usize,
+    path: &Path,
+    settings: ChannelSampler,
+) -> Result<ChannelResources> {
+    let volume = volume::load_volume(path)?;
+    let texture = create_volume_texture(
+        device,
+        queue,
+        &format!("volume texture #{index}"),
+        (volume.width, volume.height, volume.depth),
+        &volume.rgba,
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let sampler = create_channel_sampler(device, settings);
 
     Ok(ChannelResources {
         texture,
         view,
         sampler,
-        resolution: [width as f32, height as f32, 6.0, 0.0],
+        resolution: [
+            volume.width as f32,
+            volume.height as f32,
+            volume.depth as f32,
+            0.0,
+        ],
         keyboard: false,
+        audio: None,
+        animation: None,
     })
 }
 
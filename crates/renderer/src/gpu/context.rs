@@ -7,27 +7,82 @@ use crate::types::{
     AdapterProfile, Antialiasing, ColorSpaceMode, GpuMemoryMode, GpuPowerPreference, VsyncMode,
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum SurfaceColorSpace {
     Gamma,
     Linear,
 }
 
+impl SurfaceColorSpace {
+    pub(crate) fn from_mode(mode: ColorSpaceMode) -> Self {
+        match mode {
+            ColorSpaceMode::Auto | ColorSpaceMode::Gamma => SurfaceColorSpace::Gamma,
+            ColorSpaceMode::Linear => SurfaceColorSpace::Linear,
+        }
+    }
+}
+
+/// Where the image pass lands: a window/layer swapchain, or an offscreen
+/// texture for headless export.
+enum RenderTarget {
+    Surface {
+        surface: wgpu::Surface<'static>,
+        config: wgpu::SurfaceConfiguration,
+        caps: wgpu::SurfaceCapabilities,
+    },
+    Offscreen {
+        texture: wgpu::Texture,
+    },
+}
+
+/// One acquired frame; surface frames must be presented, offscreen frames are kept.
+pub(crate) enum Frame {
+    Surface(wgpu::SurfaceTexture),
+    Offscreen(wgpu::Texture),
+}
+
+impl Frame {
+    pub(crate) fn texture(&self) -> &wgpu::Texture {
+        match self {
+            Frame::Surface(frame) => &frame.texture,
+            Frame::Offscreen(texture) => texture,
+        }
+    }
+
+    pub(crate) fn present(self) {
+        if let Frame::Surface(frame) = self {
+            frame.present();
+        }
+    }
+}
+
 pub(crate) struct GpuContext {
     pub _instance: wgpu::Instance,
-    pub surface: wgpu::Surface<'static>,
+    target: RenderTarget,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    pub config: wgpu::SurfaceConfiguration,
     pub size: PhysicalSize<u32>,
     pub sample_count: u32,
     pub surface_format: wgpu::TextureFormat,
+    /// Float format used for offscreen buffer passes.
+    pub buffer_format: wgpu::TextureFormat,
     pub color_space: SurfaceColorSpace,
     pub adapter_profile: AdapterProfile,
-    pub _surface_supports_copy: bool,
+    /// File key for `wgpu::PipelineCache` data; `None` when the device lacks
+    /// `Features::PIPELINE_CACHE`.
+    pub pipeline_cache_key: Option<String>,
     #[allow(dead_code)]
     vsync_mode: VsyncMode,
-    surface_caps: wgpu::SurfaceCapabilities,
+}
+
+/// Device and per-adapter choices shared by the surface and headless paths.
+struct DeviceSetup {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    sample_count: u32,
+    buffer_format: wgpu::TextureFormat,
+    adapter_profile: AdapterProfile,
+    pipeline_cache_key: Option<String>,
 }
 
 impl GpuContext {
@@ -45,12 +100,7 @@ impl GpuContext {
     where
         T: HasDisplayHandle + HasWindowHandle,
     {
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            flags: wgpu::InstanceFlags::default(),
-            memory_budget_thresholds: wgpu::MemoryBudgetThresholds::default(),
-            backend_options: wgpu::BackendOptions::default(),
-        });
+        let instance = create_instance();
 
         let window_handle = target
             .window_handle()
@@ -67,46 +117,10 @@ impl GpuContext {
         }
         .context("failed to create rendering surface")?;
 
-        let power_preference = match gpu_power {
-            GpuPowerPreference::Low => wgpu::PowerPreference::LowPower,
-            GpuPowerPreference::High => wgpu::PowerPreference::HighPerformance,
-        };
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
-        }))
-        .context("failed to find a suitable GPU adapter")?;
-
-        let adapter_info = adapter.get_info();
-        let limits = adapter.limits();
-        let adapter_profile = AdapterProfile::from_wgpu(&adapter_info, &limits);
-        let is_software = adapter_profile.is_software();
-        tracing::debug!(
-            name = %adapter_profile.name,
-            backend = ?adapter_profile.backend,
-            device_type = ?adapter_profile.device_type,
-            is_software,
-            "selected GPU adapter"
-        );
-
-        let max_dimension = limits.max_texture_dimension_2d;
-        let requested_width = initial_size.width.max(1);
-        let requested_height = initial_size.height.max(1);
-        if requested_width > max_dimension || requested_height > max_dimension {
-            anyhow::bail!(
-                "GPU max texture dimension is {max_dimension}, requested surface is {width}x{height}",
-                max_dimension = max_dimension,
-                width = requested_width,
-                height = requested_height
-            );
-        }
+        let adapter = request_adapter(&instance, gpu_power, Some(&surface))?;
 
         let surface_caps = surface.get_capabilities(&adapter);
-        let color_space = match color_space {
-            ColorSpaceMode::Auto | ColorSpaceMode::Gamma => SurfaceColorSpace::Gamma,
-            ColorSpaceMode::Linear => SurfaceColorSpace::Linear,
-        };
+        let color_space = SurfaceColorSpace::from_mode(color_space);
 
         let surface_format = match color_space {
             SurfaceColorSpace::Linear => surface_caps
@@ -143,76 +157,13 @@ impl GpuContext {
                 }),
         };
 
-        let format_features = adapter.get_texture_format_features(surface_format);
-        let mut supported_samples = format_features.flags.supported_sample_counts();
-        if !supported_samples.contains(&1) {
-            supported_samples.push(1);
-        }
-        supported_samples.sort_unstable();
-        supported_samples.dedup();
-
-        let mut sample_count = match antialiasing {
-            Antialiasing::Auto => *supported_samples.last().unwrap_or(&1),
-            Antialiasing::Off => 1,
-            Antialiasing::Samples(requested) => {
-                if supported_samples.contains(&requested) {
-                    requested
-                } else {
-                    let fallback = supported_samples
-                        .iter()
-                        .copied()
-                        .filter(|&count| count <= requested)
-                        .max()
-                        .unwrap_or(*supported_samples.first().unwrap_or(&1));
-                    tracing::warn!(
-                        requested,
-                        fallback,
-                        ?supported_samples,
-                        "requested MSAA sample count not supported; falling back"
-                    );
-                    fallback
-                }
-            }
-        };
-
-        if sample_count > 1
-            && !format_features
-                .flags
-                .contains(TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
-        {
-            tracing::warn!(
-                ?surface_format,
-                "surface format does not support MSAA resolve; disabling MSAA"
-            );
-            sample_count = 1;
-        }
-
-        if is_software && sample_count > 1 {
-            tracing::warn!(
-                sample_count,
-                "software rasterizer detected; disabling MSAA for performance"
-            );
-            sample_count = 1;
-        }
-
-        let mut required_features = wgpu::Features::empty();
-        if sample_count > 4 {
-            required_features |= wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
-        }
-
-        let memory_hints = match gpu_memory {
-            GpuMemoryMode::Balanced => wgpu::MemoryHints::MemoryUsage,
-            GpuMemoryMode::Performance => wgpu::MemoryHints::Performance,
-        };
-
-        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
-            label: Some("wax11 device"),
-            required_features,
-            required_limits: limits.clone(),
-            memory_hints,
-            trace: wgpu::Trace::default(),
-        }))
-        .context("failed to create GPU device")?;
+        let setup = DeviceSetup::new(
+            &adapter,
+            surface_format,
+            initial_size,
+            antialiasing,
+            gpu_memory,
+        )?;
 
         let desired_maximum_frame_latency = gpu_latency.clamp(1, 3);
         if desired_maximum_frame_latency != gpu_latency {
@@ -223,7 +174,7 @@ impl GpuContext {
             );
         }
 
-        let size = PhysicalSize::new(requested_width, requested_height);
+        let size = PhysicalSize::new(initial_size.width.max(1), initial_size.height.max(1));
         let surface_supports_copy = surface_caps.usages.contains(wgpu::TextureUsages::COPY_SRC);
         let mut surface_usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
         if surface_supports_copy {
@@ -268,74 +219,154 @@ impl GpuContext {
         let config = wgpu::SurfaceConfiguration {
             usage: surface_usage,
             format: surface_format,
-            width: size.width.max(1),
-            height: size.height.max(1),
+            width: size.width,
+            height: size.height,
             present_mode,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency,
         };
-        surface.configure(&device, &config);
+        surface.configure(&setup.device, &config);
 
         Ok(Self {
             _instance: instance,
-            surface,
-            device,
-            queue,
-            config,
+            target: RenderTarget::Surface {
+                surface,
+                config,
+                caps: surface_caps,
+            },
+            device: setup.device,
+            queue: setup.queue,
             size: initial_size,
-            sample_count,
+            sample_count: setup.sample_count,
             surface_format,
+            buffer_format: setup.buffer_format,
             color_space,
-            adapter_profile,
-            _surface_supports_copy: surface_supports_copy,
+            adapter_profile: setup.adapter_profile,
+            pipeline_cache_key: setup.pipeline_cache_key,
             vsync_mode,
-            surface_caps,
         })
     }
 
+    /// Creates a context without any window or display connection. Frames are
+    /// rendered into an offscreen texture that exports read back, so any
+    /// adapter works, including software rasterizers like lavapipe/llvmpipe.
+    /// With `hdr` the target is `Rgba16Float` and keeps the shader's raw,
+    /// unclamped output; otherwise it is RGBA8 like a surface.
+    pub(crate) fn headless(
+        size: PhysicalSize<u32>,
+        antialiasing: Antialiasing,
+        color_space: ColorSpaceMode,
+        gpu_power: GpuPowerPreference,
+        gpu_memory: GpuMemoryMode,
+        hdr: bool,
+    ) -> Result<Self> {
+        let instance = create_instance();
+        let adapter = request_adapter(&instance, gpu_power, None)?;
+        let color_space = SurfaceColorSpace::from_mode(color_space);
+        let surface_format = if hdr {
+            wgpu::TextureFormat::Rgba16Float
+        } else if color_space == SurfaceColorSpace::Linear {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        let setup = DeviceSetup::new(&adapter, surface_format, size, antialiasing, gpu_memory)?;
+        let size = PhysicalSize::new(size.width.max(1), size.height.max(1));
+        let texture = create_offscreen_texture(&setup.device, surface_format, size);
+
+        Ok(Self {
+            _instance: instance,
+            target: RenderTarget::Offscreen { texture },
+            device: setup.device,
+            queue: setup.queue,
+            size,
+            sample_count: setup.sample_count,
+            surface_format,
+            buffer_format: setup.buffer_format,
+            color_space,
+            adapter_profile: setup.adapter_profile,
+            pipeline_cache_key: setup.pipeline_cache_key,
+            vsync_mode: VsyncMode::Never,
+        })
+    }
+
+    /// Acquires the texture the next frame renders into.
+    pub(crate) fn acquire_frame(&self) -> Result<Frame, wgpu::SurfaceError> {
+        match &self.target {
+            RenderTarget::Surface { surface, .. } => {
+                surface.get_current_texture().map(Frame::Surface)
+            }
+            RenderTarget::Offscreen { texture } => Ok(Frame::Offscreen(texture.clone())),
+        }
+    }
+
+    /// Whether acquired frames can be copied back to the CPU for export.
+    pub(crate) fn supports_readback(&self) -> bool {
+        match &self.target {
+            RenderTarget::Surface { config, .. } => {
+                config.usage.contains(wgpu::TextureUsages::COPY_SRC)
+            }
+            RenderTarget::Offscreen { .. } => true,
+        }
+    }
+
     pub(crate) fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width == 0 || new_size.height == 0 {
             return;
         }
 
         self.size = new_size;
-        self.config.width = new_size.width.max(1);
-        self.config.height = new_size.height.max(1);
-        self.surface.configure(&self.device, &self.config);
+        match &mut self.target {
+            RenderTarget::Surface {
+                surface, config, ..
+            } => {
+                config.width = new_size.width.max(1);
+                config.height = new_size.height.max(1);
+                surface.configure(&self.device, config);
+            }
+            RenderTarget::Offscreen { texture } => {
+                *texture = create_offscreen_texture(&self.device, self.surface_format, new_size);
+            }
+        }
     }
 
     /// Enable or disable VSync by reconfiguring the surface present mode.
     /// When `enabled` is false, prefers Immediate mode (no vsync) for lowest latency.
     pub(crate) fn set_vsync(&mut self, enabled: bool) {
+        let RenderTarget::Surface {
+            surface,
+            config,
+            caps,
+        } = &mut self.target
+        else {
+            return;
+        };
         let target_mode = if enabled {
             // Prefer Fifo (vsync) for tear-free presentation
-            self.surface_caps
-                .present_modes
+            caps.present_modes
                 .iter()
                 .copied()
                 .find(|mode| *mode == wgpu::PresentMode::Fifo)
-                .unwrap_or(self.config.present_mode)
+                .unwrap_or(config.present_mode)
         } else {
             // Prefer Immediate (no vsync), fallback to Mailbox, then current
-            self.surface_caps
-                .present_modes
+            caps.present_modes
                 .iter()
                 .copied()
                 .find(|mode| *mode == wgpu::PresentMode::Immediate)
                 .or_else(|| {
-                    self.surface_caps
-                        .present_modes
+                    caps.present_modes
                         .iter()
                         .copied()
                         .find(|mode| *mode == wgpu::PresentMode::Mailbox)
                 })
-                .unwrap_or(self.config.present_mode)
+                .unwrap_or(config.present_mode)
         };
 
-        if target_mode != self.config.present_mode {
-            self.config.present_mode = target_mode;
-            self.surface.configure(&self.device, &self.config);
+        if target_mode != config.present_mode {
+            config.present_mode = target_mode;
+            surface.configure(&self.device, config);
             tracing::debug!(
                 ?target_mode,
                 vsync_enabled = enabled,
@@ -344,3 +375,218 @@ impl GpuContext {
         }
     }
 }
+
+impl DeviceSetup {
+    fn new(
+        adapter: &wgpu::Adapter,
+        target_format: wgpu::TextureFormat,
+        size: PhysicalSize<u32>,
+        antialiasing: Antialiasing,
+        gpu_memory: GpuMemoryMode,
+    ) -> Result<Self> {
+        let adapter_info = adapter.get_info();
+        let limits = adapter.limits();
+        let adapter_profile = AdapterProfile::from_wgpu(&adapter_info, &limits);
+        let is_software = adapter_profile.is_software();
+        tracing::debug!(
+            name = %adapter_profile.name,
+            backend = ?adapter_profile.backend,
+            device_type = ?adapter_profile.device_type,
+            is_software,
+            "selected GPU adapter"
+        );
+
+        let max_dimension = limits.max_texture_dimension_2d;
+        let requested_width = size.width.max(1);
+        let requested_height = size.height.max(1);
+        if requested_width > max_dimension || requested_height > max_dimension {
+            anyhow::bail!(
+                "GPU max texture dimension is {max_dimension}, requested surface is {width}x{height}",
+                max_dimension = max_dimension,
+                width = requested_width,
+                height = requested_height
+            );
+        }
+
+        let format_features = adapter.get_texture_format_features(target_format);
+        let mut supported_samples = format_features.flags.supported_sample_counts();
+        if !supported_samples.contains(&1) {
+            supported_samples.push(1);
+        }
+        supported_samples.sort_unstable();
+        supported_samples.dedup();
+
+        let mut sample_count = match antialiasing {
+            Antialiasing::Auto => *supported_samples.last().unwrap_or(&1),
+            Antialiasing::Off => 1,
+            Antialiasing::Samples(requested) => {
+                if supported_samples.contains(&requested) {
+                    requested
+                } else {
+                    let fallback = supported_samples
+                        .iter()
+                        .copied()
+                        .filter(|&count| count <= requested)
+                        .max()
+                        .unwrap_or(*supported_samples.first().unwrap_or(&1));
+                    tracing::warn!(
+                        requested,
+                        fallback,
+                        ?supported_samples,
+                        "requested MSAA sample count not supported; falling back"
+                    );
+                    fallback
+                }
+            }
+        };
+
+        if sample_count > 1
+            && !format_features
+                .flags
+                .contains(TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+        {
+            tracing::warn!(
+                ?target_format,
+                "surface format does not support MSAA resolve; disabling MSAA"
+            );
+            sample_count = 1;
+        }
+
+        if is_software && sample_count > 1 {
+            tracing::warn!(
+                sample_count,
+                "software rasterizer detected; disabling MSAA for performance"
+            );
+            sample_count = 1;
+        }
+
+        let mut required_features = wgpu::Features::empty();
+        if sample_count > 4 {
+            required_features |= wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+        }
+
+        // ShaderToy buffers are 32-bit float; fall back to half floats when the
+        // adapter cannot filter Rgba32Float.
+        let buffer_format = if adapter
+            .features()
+            .contains(wgpu::Features::FLOAT32_FILTERABLE)
+        {
+            required_features |= wgpu::Features::FLOAT32_FILTERABLE;
+            wgpu::TextureFormat::Rgba32Float
+        } else {
+            wgpu::TextureFormat::Rgba16Float
+        };
+
+        // GPU timestamps let the dynamic resolution governor see real frame cost.
+        let timestamps =
+            wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        if adapter.features().contains(timestamps) {
+            required_features |= timestamps;
+        }
+
+        let pipeline_cache_key = adapter
+            .features()
+            .contains(wgpu::Features::PIPELINE_CACHE)
+            .then(|| wgpu::util::pipeline_cache_key(&adapter_info))
+            .flatten();
+        if pipeline_cache_key.is_some() {
+            required_features |= wgpu::Features::PIPELINE_CACHE;
+        }
+
+        let memory_hints = match gpu_memory {
+            GpuMemoryMode::Balanced => wgpu::MemoryHints::MemoryUsage,
+            GpuMemoryMode::Performance => wgpu::MemoryHints::Performance,
+        };
+
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("wax11 device"),
+            required_features,
+            required_limits: limits.clone(),
+            memory_hints,
+            trace: wgpu::Trace::default(),
+        }))
+        .context("failed to create GPU device")?;
+
+        Ok(Self {
+            device,
+            queue,
+            sample_count,
+            buffer_format,
+            adapter_profile,
+            pipeline_cache_key,
+        })
+    }
+}
+
+fn create_instance() -> wgpu::Instance {
+    wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        flags: wgpu::InstanceFlags::default(),
+        memory_budget_thresholds: wgpu::MemoryBudgetThresholds::default(),
+        backend_options: wgpu::BackendOptions::default(),
+    })
+}
+
+/// Picks an adapter for `surface`, or any adapter when surfaceless. Without a
+/// surface we also accept the fallback (software) adapter, so headless work
+/// still runs on machines without a GPU driver.
+fn request_adapter(
+    instance: &wgpu::Instance,
+    gpu_power: GpuPowerPreference,
+    surface: Option<&wgpu::Surface<'_>>,
+) -> Result<wgpu::Adapter> {
+    let power_preference = match gpu_power {
+        GpuPowerPreference::Low => wgpu::PowerPreference::LowPower,
+        GpuPowerPreference::High => wgpu::PowerPreference::HighPerformance,
+    };
+    let request = |force_fallback_adapter| {
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference,
+            compatible_surface: surface,
+            force_fallback_adapter,
+        }))
+    };
+    match request(false) {
+        Ok(adapter) => Ok(adapter),
+        Err(err) if surface.is_none() => request(true).map_err(|_| err),
+        Err(err) => Err(err),
+    }
+    .context("failed to find a suitable GPU adapter")
+}
+
+fn create_offscreen_texture(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    size: PhysicalSize<u32>,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen color target"),
+        size: wgpu::Extent3d {
+            width: size.width.max(1),
+            height: size.height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+/// Creates a device without any surface for offline work such as audio export.
+pub(crate) fn request_headless_device(
+    gpu_power: GpuPowerPreference,
+) -> Result<(wgpu::Device, wgpu::Queue)> {
+    let instance = create_instance();
+    let adapter = request_adapter(&instance, gpu_power, None)?;
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("wax11 headless device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: adapter.limits(),
+        memory_hints: wgpu::MemoryHints::Performance,
+        trace: wgpu::Trace::default(),
+    }))
+    .context("failed to create GPU device")
+}
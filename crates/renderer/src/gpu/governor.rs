@@ -0,0 +1,406 @@
+//! Dynamic resolution: measure frame cost and steer the render scale.
+//!
+//! `FrameTimer` brackets each frame's command encoder with GPU timestamps and
+//! reads them back a few frames later without stalling. Devices without
+//! timestamp queries fall back to the wall-clock interval between frames,
+//! which shows when frames run late but not how much headroom is left.
+//!
+//! `ResolutionGovernor` smooths those samples and moves the scale of the
+//! offscreen upscale target between the configured bounds:
+//!
+//! ```text
+//!   cost ──EMA──▶ over budget × 10% for 6 frames ──▶ lower (∝ √(budget / cost))
+//!            └──▶ next step fits in 85% of budget for 90 frames ──▶ raise by 0.05
+//!                 (interval timing: on time for 90 frames; the wait doubles
+//!                  each time a raise has to be undone)
+//! ```
+//!
+//! Every change is followed by a cooldown so the new scale is measured before
+//! the next decision, which together with the gap between the two thresholds
+//! keeps the scale from oscillating.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::types::DynamicScale;
+
+/// Scales move in multiples of this step so small cost changes do not resize targets.
+const SCALE_STEP: f32 = 0.05;
+/// Largest single reduction, so one slow frame burst cannot drop straight to the floor.
+const MAX_STEP_DOWN: f32 = 0.2;
+/// Weight of each new sample in the moving average.
+const SMOOTHING: f32 = 0.1;
+/// Average cost above `budget * OVER_BUDGET` counts as over budget.
+const OVER_BUDGET: f32 = 1.1;
+/// A raise must be predicted to cost less than `budget * RAISE_HEADROOM`.
+const RAISE_HEADROOM: f32 = 0.85;
+/// Interval timing counts a frame as on time up to `budget * ON_TIME`.
+const ON_TIME: f32 = 1.05;
+/// Intervals this many budgets long are idle gaps (occlusion, still frames), not cost.
+const IDLE_GAP: f32 = 4.0;
+const LOWER_AFTER_FRAMES: u32 = 6;
+const RAISE_AFTER_FRAMES: u32 = 90;
+const COOLDOWN_FRAMES: u32 = 30;
+const MAX_BACKOFF: u32 = 16;
+
+/// One measurement of how long a frame took.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FrameCost {
+    /// GPU time between the first and last command of the frame.
+    Gpu(Duration),
+    /// Wall-clock time since the previous frame started.
+    Interval(Duration),
+}
+
+/// Adjusts the render scale to hold a frame budget.
+#[derive(Debug)]
+pub(crate) struct ResolutionGovernor {
+    min_scale: f32,
+    max_scale: f32,
+    budget: f32,
+    scale: f32,
+    average: Option<f32>,
+    over_frames: u32,
+    under_frames: u32,
+    since_change: u32,
+    last_change_raised: bool,
+    backoff: u32,
+}
+
+impl ResolutionGovernor {
+    /// Starts at the top of the configured range.
+    pub(crate) fn new(config: DynamicScale) -> Self {
+        Self {
+            min_scale: config.min_scale,
+            max_scale: config.max_scale,
+            budget: config.frame_budget.as_secs_f32(),
+            scale: config.max_scale,
+            average: None,
+            over_frames: 0,
+            under_frames: 0,
+            since_change: 0,
+            last_change_raised: false,
+            backoff: 1,
+        }
+    }
+
+    pub(crate) fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Smoothed cost in seconds, once any sample has been recorded.
+    pub(crate) fn average_cost(&self) -> Option<f32> {
+        self.average
+    }
+
+    /// Records one frame and returns the new scale when it should change.
+    pub(crate) fn observe(&mut self, cost: FrameCost) -> Option<f32> {
+        let (seconds, gpu_timed) = match cost {
+            FrameCost::Gpu(duration) => (duration.as_secs_f32(), true),
+            FrameCost::Interval(duration) => (duration.as_secs_f32(), false),
+        };
+        if !gpu_timed && seconds > self.budget * IDLE_GAP {
+            return None;
+        }
+        let average = match self.average {
+            Some(average) => average + (seconds - average) * SMOOTHING,
+            None => seconds,
+        };
+        self.average = Some(average);
+        self.since_change = self.since_change.saturating_add(1);
+        if self.since_change < COOLDOWN_FRAMES {
+            return None;
+        }
+
+        if average > self.budget * OVER_BUDGET {
+            self.under_frames = 0;
+            self.over_frames += 1;
+            if self.over_frames >= LOWER_AFTER_FRAMES {
+                // Cost follows pixel count, which follows the square of the scale.
+                let wanted = self.scale * (self.budget / average).sqrt();
+                let lowered = quantize_down(wanted.max(self.scale - MAX_STEP_DOWN));
+                return self.change_to(lowered.max(self.min_scale));
+            }
+            return None;
+        }
+        self.over_frames = 0;
+
+        let raised = (self.scale + SCALE_STEP).min(self.max_scale);
+        let fits = if gpu_timed {
+            average * (raised / self.scale).powi(2) <= self.budget * RAISE_HEADROOM
+        } else {
+            average <= self.budget * ON_TIME
+        };
+        if raised > self.scale && fits {
+            self.under_frames += 1;
+            if self.under_frames >= RAISE_AFTER_FRAMES * self.backoff {
+                return self.change_to(raised);
+            }
+        } else {
+            self.under_frames = 0;
+        }
+        None
+    }
+
+    fn change_to(&mut self, scale: f32) -> Option<f32> {
+        let scale = scale.clamp(self.min_scale, self.max_scale);
+        self.over_frames = 0;
+        self.under_frames = 0;
+        if (scale - self.scale).abs() < f32::EPSILON {
+            return None;
+        }
+        let raised = scale > self.scale;
+        if raised && self.last_change_raised {
+            // The previous raise held, so probing upwards is safe again.
+            self.backoff = 1;
+        } else if !raised && self.last_change_raised {
+            self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+        }
+        self.last_change_raised = raised;
+        self.scale = scale;
+        self.average = None;
+        self.since_change = 0;
+        Some(scale)
+    }
+}
+
+fn quantize_down(scale: f32) -> f32 {
+    // Nudge before flooring so 0.7 / 0.05 landing on 13.999… still reads as 14 steps.
+    ((scale / SCALE_STEP) + 1e-3).floor() * SCALE_STEP
+}
+
+const SLOT_IDLE: u8 = 0;
+const SLOT_MAPPING: u8 = 1;
+const SLOT_READY: u8 = 2;
+const READBACK_SLOTS: usize = 3;
+const TIMESTAMP_BYTES: u64 = 2 * std::mem::size_of::<u64>() as u64;
+
+struct ReadbackSlot {
+    buffer: wgpu::Buffer,
+    state: Arc<AtomicU8>,
+}
+
+/// GPU timestamps around each frame, read back asynchronously.
+pub(crate) struct FrameTimer {
+    query_set: wgpu::QuerySet,
+    resolve: wgpu::Buffer,
+    slots: Vec<ReadbackSlot>,
+    next: usize,
+    recording: bool,
+    period_ns: f32,
+}
+
+impl FrameTimer {
+    /// Returns `None` when the device was created without in-encoder timestamps.
+    pub(crate) fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        let required =
+            wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        if !device.features().contains(required) {
+            return None;
+        }
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("frame timer queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame timer resolve"),
+            size: TIMESTAMP_BYTES,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let slots = (0..READBACK_SLOTS)
+            .map(|_| ReadbackSlot {
+                buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("frame timer readback"),
+                    size: TIMESTAMP_BYTES,
+                    usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }),
+                state: Arc::new(AtomicU8::new(SLOT_IDLE)),
+            })
+            .collect();
+        Some(Self {
+            query_set,
+            resolve,
+            slots,
+            next: 0,
+            recording: false,
+            period_ns: queue.get_timestamp_period(),
+        })
+    }
+
+    /// Writes the start timestamp, unless every readback slot is still in flight.
+    pub(crate) fn begin(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.recording = self.slots[self.next].state.load(Ordering::Acquire) == SLOT_IDLE;
+        if self.recording {
+            encoder.write_timestamp(&self.query_set, 0);
+        }
+    }
+
+    /// Writes the end timestamp and copies both into the current readback slot.
+    pub(crate) fn end(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if !self.recording {
+            return;
+        }
+        encoder.write_timestamp(&self.query_set, 1);
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve,
+            0,
+            &self.slots[self.next].buffer,
+            0,
+            TIMESTAMP_BYTES,
+        );
+    }
+
+    /// Starts mapping the slot written this frame; call after the frame was submitted.
+    pub(crate) fn submitted(&mut self) {
+        if !std::mem::take(&mut self.recording) {
+            return;
+        }
+        let slot = &self.slots[self.next];
+        slot.state.store(SLOT_MAPPING, Ordering::Release);
+        let state = Arc::clone(&slot.state);
+        slot.buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let next = if result.is_ok() {
+                    SLOT_READY
+                } else {
+                    SLOT_IDLE
+                };
+                state.store(next, Ordering::Release);
+            });
+        self.next = (self.next + 1) % self.slots.len();
+    }
+
+    /// Returns the GPU time of the newest finished frame, without blocking.
+    pub(crate) fn collect(&mut self, device: &wgpu::Device) -> Option<Duration> {
+        let _ = device.poll(wgpu::PollType::Poll);
+        let mut newest = None;
+        // Walk from the oldest slot so the newest reading wins.
+        for offset in 0..self.slots.len() {
+            let slot = &self.slots[(self.next + offset) % self.slots.len()];
+            if slot.state.load(Ordering::Acquire) != SLOT_READY {
+                continue;
+            }
+            let ticks = {
+                let data = slot.buffer.slice(..).get_mapped_range();
+                let start = u64::from_le_bytes(data[0..8].try_into().expect("8 bytes"));
+                let end = u64::from_le_bytes(data[8..16].try_into().expect("8 bytes"));
+                end.saturating_sub(start)
+            };
+            slot.buffer.unmap();
+            slot.state.store(SLOT_IDLE, Ordering::Release);
+            newest = Some(Duration::from_nanos(
+                (ticks as f64 * self.period_ns as f64) as u64,
+            ));
+        }
+        newest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governor(min_scale: f32, max_scale: f32) -> ResolutionGovernor {
+        ResolutionGovernor::new(DynamicScale {
+            min_scale,
+            max_scale,
+            frame_budget: Duration::from_millis(16),
+        })
+    }
+
+    fn run(governor: &mut ResolutionGovernor, cost: FrameCost, frames: u32) -> Vec<f32> {
+        (0..frames).filter_map(|_| governor.observe(cost)).collect()
+    }
+
+    #[test]
+    fn lowers_in_proportion_to_the_overrun_and_respects_bounds() {
+        let mut governor = governor(0.4, 1.0);
+        // Twice the budget wants 1/√2 of the scale.
+        let changes = run(
+            &mut governor,
+            FrameCost::Gpu(Duration::from_millis(32)),
+            COOLDOWN_FRAMES + LOWER_AFTER_FRAMES,
+        );
+        assert_eq!(changes.len(), 1);
+        assert!((changes[0] - 0.8).abs() < 1e-4, "{changes:?}");
+
+        // A hopeless shader bottoms out at the floor instead of overshooting it.
+        run(
+            &mut governor,
+            FrameCost::Gpu(Duration::from_millis(200)),
+            1000,
+        );
+        assert!((governor.scale() - 0.4).abs() < 1e-4);
+    }
+
+    #[test]
+    fn holds_steady_inside_the_hysteresis_band() {
+        let mut governor = governor(0.4, 1.0);
+        run(
+            &mut governor,
+            FrameCost::Gpu(Duration::from_millis(40)),
+            COOLDOWN_FRAMES + LOWER_AFTER_FRAMES,
+        );
+        let lowered = governor.scale();
+        // Just under budget: neither over budget nor cheap enough to afford a raise.
+        let changes = run(
+            &mut governor,
+            FrameCost::Gpu(Duration::from_millis(15)),
+            2000,
+        );
+        assert!(changes.is_empty(), "{changes:?}");
+        assert_eq!(governor.scale(), lowered);
+    }
+
+    #[test]
+    fn raises_one_step_at_a_time_when_there_is_headroom() {
+        let mut governor = governor(0.4, 1.0);
+        run(
+            &mut governor,
+            FrameCost::Gpu(Duration::from_millis(64)),
+            COOLDOWN_FRAMES + LOWER_AFTER_FRAMES,
+        );
+        let lowered = governor.scale();
+        let changes = run(
+            &mut governor,
+            FrameCost::Gpu(Duration::from_millis(4)),
+            COOLDOWN_FRAMES + RAISE_AFTER_FRAMES,
+        );
+        assert_eq!(changes.len(), 1);
+        assert!((changes[0] - (lowered + SCALE_STEP)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn interval_timing_backs_off_after_a_failed_raise() {
+        let mut governor = governor(0.4, 1.0);
+        let late = FrameCost::Interval(Duration::from_millis(33));
+        let on_time = FrameCost::Interval(Duration::from_millis(16));
+        run(&mut governor, late, COOLDOWN_FRAMES + LOWER_AFTER_FRAMES);
+        run(&mut governor, on_time, COOLDOWN_FRAMES + RAISE_AFTER_FRAMES);
+        run(&mut governor, late, COOLDOWN_FRAMES + LOWER_AFTER_FRAMES);
+        assert_eq!(governor.backoff, 2);
+        // The next probe waits twice as long.
+        let early = run(&mut governor, on_time, COOLDOWN_FRAMES + RAISE_AFTER_FRAMES);
+        assert!(early.is_empty());
+        let probe = run(&mut governor, on_time, RAISE_AFTER_FRAMES);
+        assert_eq!(probe.len(), 1);
+    }
+
+    #[test]
+    fn idle_gaps_are_not_counted_as_cost() {
+        let mut governor = governor(0.4, 1.0);
+        let changes = run(
+            &mut governor,
+            FrameCost::Interval(Duration::from_secs(1)),
+            500,
+        );
+        assert!(changes.is_empty());
+        assert!(governor.average_cost().is_none());
+    }
+}
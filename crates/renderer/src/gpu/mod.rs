@@ -3,11 +3,25 @@
 //! The new architecture keeps the public surface (`GpuState`) stable while
 //! dramatically simplifying the path from uniforms to pixels:
 //! - `context` owns wgpu instance/device/surface wiring and knows how to
-//!   rebuild swapchain state when the window resizes.
+//!   rebuild swapchain state when the window resizes; its headless variant
+//!   renders into an offscreen texture without any display connection.
+//! - `accumulate` averages jittered full-resolution samples into a float
+//!   target for progressive stills and presents the running mean.
+//! - `capture` reads finished frames back to the CPU and writes export files.
 //! - `channels` materialises ShaderToy channel resources (textures, cubemaps,
-//!   keyboard) and exposes their resolutions for uniforms.
+//!   volumes, keyboard) and exposes their resolutions for uniforms.
+//! - `governor` times frames on the GPU and moves the upscale target's render
+//!   scale within configured bounds to hold a frame budget.
+//! - `mipmap` blits full mip chains for image channels sampled with
+//!   `filter = "mipmap"`.
+//! - `multipass` renders ShaderToy buffer passes into ping-pong float targets
+//!   and the cubemap pass into a six-face cube target ahead of the image pass.
+//! - `sound` evaluates the ShaderToy sound pass in sample blocks, for WAV
+//!   export or as an audio channel other passes can sample.
 //! - `pipeline` compiles wrapped GLSL into render pipelines with a single
 //!   bind group layout.
+//! - `upscale` renders below native resolution into a scaled target and
+//!   stretches it over the frame with a nearest, bilinear or sharpening filter.
 //! - `uniforms` mirrors the injected ShaderToy macros and writes changes
 //!   straight through the queue each frame.
 //! - `timeline` tracks pending shader swaps, warmup frames, and crossfade
@@ -16,15 +30,24 @@
 //!   `window` and `wallpaper`.
 //!
 //! Phases still to come (tracked for follow-up work):
-//! - Reintroduce still-frame exports.
 //! - Restore fill-method experimentation and GPU power/latency knobs.
 //! - Async pipeline warmup once animation smoothness is nailed down.
 
+mod accumulate;
+mod capture;
 mod channels;
 mod context;
+mod governor;
+mod mipmap;
+mod multipass;
 mod pipeline;
+mod sound;
 mod state;
 mod timeline;
 mod uniforms;
+mod upscale;
 
+pub(crate) use capture::{crossfade_loop, write_apng, write_gif};
+pub(crate) use context::SurfaceColorSpace;
+pub(crate) use sound::export_sound;
 pub(crate) use state::{FileExportTarget, GpuState, RenderExportError};
@@ -0,0 +1,648 @@
+//! Offscreen ShaderToy buffer passes ("Buffer A–D") and the cubemap pass ("Cube A").
+//!
+//! Each buffer pass owns a pipeline plus two float textures. Every frame a pass
+//! renders into its back texture and then flips it to the front, so readers see:
+//!
+//! ```text
+//!   pass i reads buffer j   j < i  → this frame's output
+//!                           j >= i → previous frame (self-feedback when j == i)
+//!   cube pass reads j             → this frame's output
+//!   image pass reads j / cube     → this frame's output
+//! ```
+//!
+//! The cubemap pass renders after all buffers, drawing each of the six faces of
+//! a fixed-size cube texture before flipping it, so buffers that sample the cube
+//! see the previous frame.
+//!
+//! Bind groups that reference buffer textures depend on which half is the front,
+//! so they are assembled per frame; everything else is built once alongside the
+//! image pipeline and shared through `PipelineHandle`.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use tracing::warn;
+
+use crate::audio::PendingClip;
+use crate::compile::PassTarget;
+use crate::preprocess::load_pass_source;
+use crate::types::{BufferPass, ChannelBindings, ChannelSource, ShaderCompiler, CHANNEL_COUNT};
+
+use super::channels::{self, ChannelResources};
+use super::context::SurfaceColorSpace;
+use super::pipeline::{
+    compile_pass_shader, create_channel_layout, create_render_pipeline, PipelineLayouts,
+};
+
+/// Edge length of each face rendered by the cubemap pass, matching ShaderToy.
+const CUBEMAP_FACE_SIZE: u32 = 1024;
+
+/// Number of faces drawn by the cubemap pass.
+pub(crate) const CUBEMAP_FACE_COUNT: u32 = 6;
+
+/// Offscreen output a channel reads from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PassOutput {
+    /// Index into the chain's buffer passes.
+    Buffer(usize),
+    /// The cubemap pass.
+    Cubemap,
+}
+
+/// Per-channel offscreen references, resolved against the chain.
+type BufferInputs = [Option<PassOutput>; CHANNEL_COUNT];
+
+struct BufferPassPipeline {
+    name: String,
+    pipeline: wgpu::RenderPipeline,
+    channel_layout: wgpu::BindGroupLayout,
+    channel_resources: Vec<ChannelResources>,
+    inputs: BufferInputs,
+}
+
+struct BufferTexture {
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+struct CubemapTexture {
+    _texture: wgpu::Texture,
+    faces: [wgpu::TextureView; CUBEMAP_FACE_COUNT as usize],
+    cube: wgpu::TextureView,
+}
+
+struct CubemapTargets {
+    textures: [CubemapTexture; 2],
+    front: usize,
+}
+
+impl CubemapTargets {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat, name: &str) -> Self {
+        let textures = std::array::from_fn(|half| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(&format!("cubemap '{name}' target #{half}")),
+                size: wgpu::Extent3d {
+                    width: CUBEMAP_FACE_SIZE,
+                    height: CUBEMAP_FACE_SIZE,
+                    depth_or_array_layers: CUBEMAP_FACE_COUNT,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let faces = std::array::from_fn(|face| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("cubemap face view"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: face as u32,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            });
+            let cube = texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("cubemap pass view"),
+                dimension: Some(wgpu::TextureViewDimension::Cube),
+                ..Default::default()
+            });
+            CubemapTexture {
+                _texture: texture,
+                faces,
+                cube,
+            }
+        });
+        Self { textures, front: 0 }
+    }
+
+    fn front_view(&self) -> &wgpu::TextureView {
+        &self.textures[self.front].cube
+    }
+
+    fn back_face_view(&self, face: u32) -> &wgpu::TextureView {
+        &self.textures[1 - self.front].faces[face as usize]
+    }
+}
+
+struct BufferTargets {
+    size: (u32, u32),
+    textures: Vec<[BufferTexture; 2]>,
+    front: Vec<usize>,
+    cubemap: Option<CubemapTargets>,
+}
+
+impl BufferTargets {
+    fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        size: (u32, u32),
+        passes: &[BufferPassPipeline],
+        cubemap: Option<&BufferPassPipeline>,
+    ) -> Self {
+        let textures = passes
+            .iter()
+            .map(|pass| {
+                std::array::from_fn(|half| {
+                    let texture = device.create_texture(&wgpu::TextureDescriptor {
+                        label: Some(&format!("buffer '{}' target #{half}", pass.name)),
+                        size: wgpu::Extent3d {
+                            width: size.0,
+                            height: size.1,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                            | wgpu::TextureUsages::TEXTURE_BINDING,
+                        view_formats: &[],
+                    });
+                    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                    BufferTexture {
+                        _texture: texture,
+                        view,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            size,
+            textures,
+            front: vec![0; passes.len()],
+            cubemap: cubemap.map(|pass| CubemapTargets::new(device, format, &pass.name)),
+        }
+    }
+
+    fn front_view(&self, index: usize) -> &wgpu::TextureView {
+        &self.textures[index][self.front[index]].view
+    }
+
+    fn back_view(&self, index: usize) -> &wgpu::TextureView {
+        &self.textures[index][1 - self.front[index]].view
+    }
+
+    fn output_view(&self, output: PassOutput) -> Option<&wgpu::TextureView> {
+        match output {
+            PassOutput::Buffer(index) => Some(self.front_view(index)),
+            PassOutput::Cubemap => self.cubemap.as_ref().map(CubemapTargets::front_view),
+        }
+    }
+}
+
+/// Buffer and cubemap passes attached to an image pipeline.
+pub(crate) struct BufferChain {
+    passes: Vec<BufferPassPipeline>,
+    cubemap: Option<BufferPassPipeline>,
+    image_inputs: BufferInputs,
+    format: wgpu::TextureFormat,
+    targets: Mutex<Option<BufferTargets>>,
+}
+
+impl BufferChain {
+    /// Compiles every offscreen pass declared on `bindings`; returns `None` for single-pass shaders.
+    ///
+    /// `sound` is the rendered sound pass, handed to channels that sample it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layouts: &PipelineLayouts,
+        format: wgpu::TextureFormat,
+        bindings: &ChannelBindings,
+        color_space: SurfaceColorSpace,
+        shader_compiler: ShaderCompiler,
+        include_dirs: &[PathBuf],
+        sound: Option<&PendingClip>,
+    ) -> Result<Option<Self>> {
+        let declared = bindings.buffer_passes();
+        if declared.is_empty() && bindings.cubemap_pass().is_none() {
+            return Ok(None);
+        }
+
+        let compile = |pass: &BufferPass, target: PassTarget| {
+            compile_pass(
+                device,
+                queue,
+                layouts,
+                format,
+                pass,
+                target,
+                bindings,
+                color_space,
+                shader_compiler,
+                include_dirs,
+                sound,
+            )
+        };
+        let passes = declared
+            .iter()
+            .map(|pass| compile(pass, PassTarget::Buffer))
+            .collect::<Result<Vec<_>>>()?;
+        let cubemap = bindings
+            .cubemap_pass()
+            .map(|pass| compile(pass, PassTarget::Cubemap))
+            .transpose()?;
+
+        Ok(Some(Self {
+            passes,
+            cubemap,
+            image_inputs: resolve_inputs("image", bindings, bindings),
+            format,
+            targets: Mutex::new(None),
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn has_cubemap_pass(&self) -> bool {
+        self.cubemap.is_some()
+    }
+
+    /// `iChannelResolution` values for buffer pass `index`, with offscreen inputs at target size.
+    pub fn pass_channel_resolutions(&self, index: usize) -> [[f32; 4]; CHANNEL_COUNT] {
+        self.channel_resolutions(&self.passes[index])
+    }
+
+    /// `iChannelResolution` values for the cubemap pass.
+    pub fn cubemap_channel_resolutions(&self) -> [[f32; 4]; CHANNEL_COUNT] {
+        self.cubemap
+            .as_ref()
+            .map(|pass| self.channel_resolutions(pass))
+            .unwrap_or([[0.0; 4]; CHANNEL_COUNT])
+    }
+
+    /// `iChannelResolution` overrides for image channels that read an offscreen pass.
+    pub fn image_channel_resolutions(&self) -> [Option<[f32; 4]>; CHANNEL_COUNT] {
+        let target = self.target_resolution();
+        std::array::from_fn(|channel| {
+            self.image_inputs[channel].and_then(|output| output_resolution(output, target))
+        })
+    }
+
+    /// Returns true when the image pass samples at least one offscreen pass.
+    pub fn feeds_image(&self) -> bool {
+        self.image_inputs.iter().any(Option::is_some)
+    }
+
+    pub fn has_keyboard_channel(&self) -> bool {
+        self.offscreen_passes().any(|pass| {
+            pass.channel_resources
+                .iter()
+                .any(|resource| resource.is_keyboard())
+        })
+    }
+
+    /// Sample rate of the first live audio channel read by an offscreen pass.
+    pub fn live_sample_rate(&self) -> Option<u32> {
+        self.offscreen_passes().find_map(|pass| {
+            pass.channel_resources
+                .iter()
+                .find_map(ChannelResources::live_sample_rate)
+        })
+    }
+
+    pub fn update_keyboard_channels(&self, queue: &wgpu::Queue, data: &[u8]) {
+        for pass in self.offscreen_passes() {
+            for resource in &pass.channel_resources {
+                resource.update_keyboard(queue, data);
+            }
+        }
+    }
+
+    /// Refreshes audio and animated channels of buffer pass `index`, returning their playback positions.
+    pub fn update_pass_channels(
+        &self,
+        queue: &wgpu::Queue,
+        index: usize,
+        seconds: f32,
+    ) -> [Option<f32>; CHANNEL_COUNT] {
+        channels::update_timed_channels(&self.passes[index].channel_resources, queue, seconds)
+    }
+
+    /// Refreshes audio and animated channels of the cubemap pass, returning their playback positions.
+    pub fn update_cubemap_channels(
+        &self,
+        queue: &wgpu::Queue,
+        seconds: f32,
+    ) -> [Option<f32>; CHANNEL_COUNT] {
+        self.cubemap
+            .as_ref()
+            .map(|pass| channels::update_timed_channels(&pass.channel_resources, queue, seconds))
+            .unwrap_or_default()
+    }
+
+    fn offscreen_passes(&self) -> impl Iterator<Item = &BufferPassPipeline> {
+        self.passes.iter().chain(self.cubemap.as_ref())
+    }
+
+    fn channel_resolutions(&self, pass: &BufferPassPipeline) -> [[f32; 4]; CHANNEL_COUNT] {
+        let target = self.target_resolution();
+        std::array::from_fn(|channel| {
+            pass.inputs[channel]
+                .and_then(|output| output_resolution(output, target))
+                .or_else(|| {
+                    pass.channel_resources
+                        .get(channel)
+                        .map(|resource| resource.resolution)
+                })
+                .unwrap_or([0.0; 4])
+        })
+    }
+
+    /// Makes sure targets match `size`, recreating (and clearing) them when it changed.
+    pub fn prepare(&self, device: &wgpu::Device, size: (u32, u32)) {
+        let size = (size.0.max(1), size.1.max(1));
+        let mut targets = self.targets.lock().expect("buffer targets poisoned");
+        if targets
+            .as_ref()
+            .is_some_and(|existing| existing.size == size)
+        {
+            return;
+        }
+        *targets = Some(BufferTargets::new(
+            device,
+            self.format,
+            size,
+            &self.passes,
+            self.cubemap.as_ref(),
+        ));
+    }
+
+    /// Drops accumulated feedback so the next frame starts from cleared buffers.
+    pub fn reset(&self) {
+        *self.targets.lock().expect("buffer targets poisoned") = None;
+    }
+
+    /// Encodes buffer pass `index` into its back texture and flips it to the front.
+    ///
+    /// Uniforms for the pass must already be copied into the bound uniform buffer.
+    pub fn encode_pass(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        index: usize,
+        uniform_bind_group: &wgpu::BindGroup,
+    ) {
+        let mut guard = self.targets.lock().expect("buffer targets poisoned");
+        let Some(targets) = guard.as_mut() else {
+            return;
+        };
+        let pass = &self.passes[index];
+        let bind_group = self.pass_bind_group(device, pass, targets);
+        encode_draw(
+            encoder,
+            pass,
+            targets.back_view(index),
+            uniform_bind_group,
+            &bind_group,
+            "buffer pass",
+        );
+        targets.front[index] = 1 - targets.front[index];
+    }
+
+    /// Encodes every face of the cubemap pass, then flips the cube target to the front.
+    ///
+    /// `stage_face` runs before each face and must copy that face's uniforms
+    /// (with `wax11_CubeFace` set) into the bound uniform buffer.
+    pub fn encode_cubemap(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        uniform_bind_group: &wgpu::BindGroup,
+        mut stage_face: impl FnMut(&mut wgpu::CommandEncoder, u32),
+    ) {
+        let Some(pass) = self.cubemap.as_ref() else {
+            return;
+        };
+        let mut guard = self.targets.lock().expect("buffer targets poisoned");
+        let Some(targets) = guard.as_mut() else {
+            return;
+        };
+        let bind_group = self.pass_bind_group(device, pass, targets);
+        let Some(cubemap) = targets.cubemap.as_mut() else {
+            return;
+        };
+        for face in 0..CUBEMAP_FACE_COUNT {
+            stage_face(encoder, face);
+            encode_draw(
+                encoder,
+                pass,
+                cubemap.back_face_view(face),
+                uniform_bind_group,
+                &bind_group,
+                "cubemap pass",
+            );
+        }
+        cubemap.front = 1 - cubemap.front;
+    }
+
+    /// Face edge length used for the cubemap pass `iResolution`.
+    pub fn cubemap_face_size(&self) -> u32 {
+        CUBEMAP_FACE_SIZE
+    }
+
+    fn pass_bind_group(
+        &self,
+        device: &wgpu::Device,
+        pass: &BufferPassPipeline,
+        targets: &BufferTargets,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("buffer channel bind group"),
+            layout: &pass.channel_layout,
+            entries: &self.channel_entries(&pass.channel_resources, &pass.inputs, targets),
+        })
+    }
+
+    /// Builds the image pass bind group with the latest buffer outputs substituted in.
+    pub fn image_bind_group(
+        &self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        resources: &[ChannelResources],
+    ) -> Option<wgpu::BindGroup> {
+        if !self.feeds_image() {
+            return None;
+        }
+        let guard = self.targets.lock().expect("buffer targets poisoned");
+        let targets = guard.as_ref()?;
+        Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("channel bind group"),
+            layout,
+            entries: &self.channel_entries(resources, &self.image_inputs, targets),
+        }))
+    }
+
+    /// Current buffer size for `iChannelResolution`, if targets exist.
+    pub fn target_resolution(&self) -> Option<[f32; 4]> {
+        let guard = self.targets.lock().expect("buffer targets poisoned");
+        guard
+            .as_ref()
+            .map(|targets| [targets.size.0 as f32, targets.size.1 as f32, 1.0, 0.0])
+    }
+
+    fn channel_entries<'a>(
+        &'a self,
+        resources: &'a [ChannelResources],
+        inputs: &BufferInputs,
+        targets: &'a BufferTargets,
+    ) -> Vec<wgpu::BindGroupEntry<'a>> {
+        let mut entries = Vec::with_capacity(resources.len() * 2);
+        for (index, resource) in resources.iter().enumerate() {
+            let offscreen = inputs
+                .get(index)
+                .copied()
+                .flatten()
+                .and_then(|output| targets.output_view(output));
+            // The placeholder's sampler carries the channel's filter and wrap settings.
+            let view = offscreen.unwrap_or(&resource.view);
+            entries.push(wgpu::BindGroupEntry {
+                binding: (index as u32) * 2,
+                resource: wgpu::BindingResource::TextureView(view),
+            });
+            entries.push(wgpu::BindGroupEntry {
+                binding: (index as u32) * 2 + 1,
+                resource: wgpu::BindingResource::Sampler(&resource.sampler),
+            });
+        }
+        entries
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_pass(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layouts: &PipelineLayouts,
+    format: wgpu::TextureFormat,
+    pass: &BufferPass,
+    target: PassTarget,
+    owner: &ChannelBindings,
+    color_space: SurfaceColorSpace,
+    shader_compiler: ShaderCompiler,
+    include_dirs: &[PathBuf],
+    sound: Option<&PendingClip>,
+) -> Result<BufferPassPipeline> {
+    let code = load_pass_source(&pass.source, pass.channels.language(), include_dirs)
+        .with_context(|| {
+            format!(
+                "failed to read buffer '{}' at {}",
+                pass.name,
+                pass.source.display()
+            )
+        })?;
+    let kinds = pass.channels.layout_signature();
+    let compiled = compile_pass_shader(
+        device,
+        layouts,
+        &code,
+        target,
+        &pass.channels,
+        &kinds,
+        color_space,
+        shader_compiler,
+        &pass.name,
+    )
+    .with_context(|| format!("failed to compile buffer '{}'", pass.name))?;
+    let channel_resources = channels::create_resources(
+        device,
+        queue,
+        layouts.mipmaps(),
+        &pass.channels,
+        &kinds,
+        &compiled.sampled_channels(),
+        color_space,
+        sound,
+    )?;
+    let module = compiled.module;
+    let channel_layout = create_channel_layout(device, &kinds);
+    let pipeline = create_render_pipeline(
+        device,
+        layouts,
+        &channel_layout,
+        &module,
+        format,
+        1,
+        None,
+        "buffer pipeline",
+    );
+    Ok(BufferPassPipeline {
+        name: pass.name.clone(),
+        pipeline,
+        channel_layout,
+        channel_resources,
+        inputs: resolve_inputs(&pass.name, &pass.channels, owner),
+    })
+}
+
+fn encode_draw(
+    encoder: &mut wgpu::CommandEncoder,
+    pass: &BufferPassPipeline,
+    view: &wgpu::TextureView,
+    uniform_bind_group: &wgpu::BindGroup,
+    channel_bind_group: &wgpu::BindGroup,
+    label: &str,
+) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+    render_pass.set_pipeline(&pass.pipeline);
+    render_pass.set_bind_group(0, uniform_bind_group, &[]);
+    render_pass.set_bind_group(1, channel_bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
+
+fn output_resolution(output: PassOutput, target: Option<[f32; 4]>) -> Option<[f32; 4]> {
+    match output {
+        PassOutput::Buffer(_) => target,
+        PassOutput::Cubemap => Some([CUBEMAP_FACE_SIZE as f32, CUBEMAP_FACE_SIZE as f32, 1.0, 0.0]),
+    }
+}
+
+fn resolve_inputs(pass: &str, channels: &ChannelBindings, owner: &ChannelBindings) -> BufferInputs {
+    let mut inputs = [None; CHANNEL_COUNT];
+    for (channel, source) in channels.slots().iter().enumerate() {
+        let resolved = match source {
+            Some(ChannelSource::Buffer { name }) => owner
+                .buffer_passes()
+                .iter()
+                .position(|candidate| &candidate.name == name)
+                .map(PassOutput::Buffer)
+                .ok_or(name),
+            Some(ChannelSource::CubemapPass { name }) => owner
+                .cubemap_pass()
+                .filter(|candidate| &candidate.name == name)
+                .map(|_| PassOutput::Cubemap)
+                .ok_or(name),
+            _ => continue,
+        };
+        match resolved {
+            Ok(output) => inputs[channel] = Some(output),
+            Err(name) => warn!(
+                pass,
+                channel,
+                buffer = %name,
+                "buffer input references an unknown pass; using placeholder"
+            ),
+        }
+    }
+    inputs
+}
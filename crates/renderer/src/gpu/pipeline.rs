@@ -1,20 +1,59 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
 
-use crate::compile::{compile_fragment_shader, compile_vertex_shader};
+use crate::audio::PendingClip;
+use crate::compile::{
+    channel_kind_mismatches, compile_fragment_shader, compile_vertex_shader, CompiledShader,
+    PassTarget,
+};
+use crate::preprocess::{load_pass_source, PassSource};
+use crate::reflect::ShaderUsage;
+use crate::shader_cache::ShaderCache;
 use crate::types::{ChannelBindings, ChannelTextureKind, ShaderCompiler, CHANNEL_COUNT};
 
 use super::channels::{self, ChannelResources};
 use super::context::SurfaceColorSpace;
+use super::mipmap::MipmapGenerator;
+use super::multipass::BufferChain;
+use super::sound;
+
+/// Additive blending lets crossfading pipelines accumulate into one target.
+const CROSSFADE_BLEND: wgpu::BlendState = wgpu::BlendState {
+    color: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+};
 
 pub(crate) struct PipelineLayouts {
     pub uniform_layout: wgpu::BindGroupLayout,
     pub vertex_module: wgpu::ShaderModule,
+    shader_cache: Option<ShaderCache>,
+    /// Driver pipeline cache and the adapter key its data is stored under.
+    pipeline_cache: Option<(wgpu::PipelineCache, String)>,
+    /// Sound-pass clips by source key, so pipeline rebuilds reuse the audio.
+    sound_clips: Arc<Mutex<HashMap<u64, PendingClip>>>,
+    mipmaps: Arc<MipmapGenerator>,
 }
 
 impl PipelineLayouts {
-    pub fn new(device: &wgpu::Device, shader_compiler: ShaderCompiler) -> Result<Self> {
+    /// `pipeline_cache_key` is `Some` only when the device was created with
+    /// `wgpu::Features::PIPELINE_CACHE`.
+    pub fn new(
+        device: &wgpu::Device,
+        shader_compiler: ShaderCompiler,
+        shader_cache: Option<ShaderCache>,
+        pipeline_cache_key: Option<String>,
+    ) -> Result<Self> {
         let uniform_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("uniform layout"),
             entries: &[wgpu::BindGroupLayoutEntry {
@@ -29,13 +68,58 @@ impl PipelineLayouts {
             }],
         });
 
-        let vertex_module = compile_vertex_shader(device, shader_compiler)?;
+        let vertex_module = compile_vertex_shader(device, shader_compiler, shader_cache.as_ref())?;
+
+        let pipeline_cache = shader_cache
+            .as_ref()
+            .zip(pipeline_cache_key)
+            .map(|(cache, key)| {
+                let data = cache.load_pipeline_data(&key);
+                // SAFETY: the data was produced by `PipelineCache::get_data` for an
+                // adapter with the same `pipeline_cache_key`, and `fallback` lets the
+                // driver discard it if it is stale or corrupt.
+                let pipeline_cache = unsafe {
+                    device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: Some("wax11 pipeline cache"),
+                        data: data.as_deref(),
+                        fallback: true,
+                    })
+                };
+                (pipeline_cache, key)
+            });
 
         Ok(Self {
             uniform_layout,
             vertex_module,
+            shader_cache,
+            pipeline_cache,
+            sound_clips: Arc::default(),
+            mipmaps: Arc::new(MipmapGenerator::new(device)),
         })
     }
+
+    pub fn shader_cache(&self) -> Option<&ShaderCache> {
+        self.shader_cache.as_ref()
+    }
+
+    pub fn sound_clips(&self) -> &Mutex<HashMap<u64, PendingClip>> {
+        &self.sound_clips
+    }
+
+    pub fn mipmaps(&self) -> &MipmapGenerator {
+        &self.mipmaps
+    }
+
+    /// Writes the driver pipeline cache to disk after new pipelines were built.
+    pub fn persist_pipeline_cache(&self) {
+        let (Some(cache), Some((pipeline_cache, key))) = (&self.shader_cache, &self.pipeline_cache)
+        else {
+            return;
+        };
+        if let Some(data) = pipeline_cache.get_data() {
+            cache.store_pipeline_data(key, &data);
+        }
+    }
 }
 
 impl Clone for PipelineLayouts {
@@ -43,6 +127,10 @@ impl Clone for PipelineLayouts {
         Self {
             uniform_layout: self.uniform_layout.clone(),
             vertex_module: self.vertex_module.clone(),
+            shader_cache: self.shader_cache.clone(),
+            pipeline_cache: self.pipeline_cache.clone(),
+            sound_clips: self.sound_clips.clone(),
+            mipmaps: self.mipmaps.clone(),
         }
     }
 }
@@ -51,8 +139,14 @@ pub(crate) struct ShaderPipeline {
     pub pipeline: wgpu::RenderPipeline,
     pub channel_bind_group: wgpu::BindGroup,
     pub channel_resources: Vec<ChannelResources>,
-    pub _channel_layout: wgpu::BindGroupLayout,
+    pub channel_layout: wgpu::BindGroupLayout,
+    pub buffers: Option<BufferChain>,
+    /// Sound-pass audio read by the channels, possibly still rendering.
+    sound_clip: Option<PendingClip>,
     has_keyboard: bool,
+    live_sample_rate: Option<u32>,
+    /// Reflected inputs of the image pass, when naga could analyse it.
+    usage: Option<ShaderUsage>,
     pub shader_source: PathBuf,
 }
 
@@ -64,118 +158,107 @@ impl ShaderPipeline {
         layouts: &PipelineLayouts,
         surface_format: wgpu::TextureFormat,
         sample_count: u32,
+        buffer_format: wgpu::TextureFormat,
         shader_path: &Path,
         channel_bindings: &ChannelBindings,
         channel_kinds: &[ChannelTextureKind; CHANNEL_COUNT],
         color_space: SurfaceColorSpace,
         shader_compiler: ShaderCompiler,
+        include_dirs: &[PathBuf],
     ) -> Result<Self> {
-        let shader_code = std::fs::read_to_string(shader_path)
-            .with_context(|| format!("failed to read shader at {}", shader_path.display()))?;
-        let fragment_module = compile_fragment_shader(device, &shader_code, shader_compiler)
-            .context("failed to compile shader")?;
+        let shader_code = load_pass_source(shader_path, channel_bindings.language(), include_dirs)?;
+        let fragment = compile_pass_shader(
+            device,
+            layouts,
+            &shader_code,
+            PassTarget::Image,
+            channel_bindings,
+            channel_kinds,
+            color_space,
+            shader_compiler,
+            "image",
+        )
+        .context("failed to compile shader")?;
 
+        let sound_clip = sound::render_sound_channel(
+            device,
+            queue,
+            layouts,
+            channel_bindings,
+            color_space,
+            shader_compiler,
+            include_dirs,
+        );
         let channel_resources = channels::create_resources(
             device,
             queue,
+            layouts.mipmaps(),
             channel_bindings,
             channel_kinds,
+            &fragment.sampled_channels(),
             color_space,
+            sound_clip.as_ref(),
         )?;
-        let channel_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("channel layout"),
-            entries: &build_channel_layout_entries(channel_kinds),
-        });
+        let channel_layout = create_channel_layout(device, channel_kinds);
         let channel_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("channel bind group"),
             layout: &channel_layout,
             entries: &build_channel_entries(&channel_resources),
         });
 
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("shader pipeline layout"),
-            bind_group_layouts: &[&layouts.uniform_layout, &channel_layout],
-            push_constant_ranges: &[],
-        });
+        let pipeline = create_render_pipeline(
+            device,
+            layouts,
+            &channel_layout,
+            &fragment.module,
+            surface_format,
+            sample_count,
+            Some(CROSSFADE_BLEND),
+            "shader pipeline",
+        );
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("shader pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &layouts.vertex_module,
-                entry_point: Some("main"),
-                buffers: &[],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: sample_count,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &fragment_module,
-                entry_point: Some("main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            multiview: None,
-            cache: None,
-        });
+        let buffers = BufferChain::new(
+            device,
+            queue,
+            layouts,
+            buffer_format,
+            channel_bindings,
+            color_space,
+            shader_compiler,
+            include_dirs,
+            sound_clip.as_ref(),
+        )?;
 
         let has_keyboard = channel_resources
             .iter()
-            .any(|resource| resource.is_keyboard());
+            .any(|resource| resource.is_keyboard())
+            || buffers
+                .as_ref()
+                .is_some_and(|chain| chain.has_keyboard_channel());
 
-        Ok(Self::from_parts(
+        let live_sample_rate = channel_resources
+            .iter()
+            .find_map(ChannelResources::live_sample_rate)
+            .or_else(|| buffers.as_ref().and_then(BufferChain::live_sample_rate));
+
+        Ok(Self {
             pipeline,
             channel_bind_group,
             channel_resources,
             channel_layout,
+            buffers,
+            sound_clip,
             has_keyboard,
-            shader_path.to_path_buf(),
-        ))
+            live_sample_rate,
+            usage: fragment.usage,
+            shader_source: shader_path.to_path_buf(),
+        })
     }
 
-    pub(crate) fn from_parts(
-        pipeline: wgpu::RenderPipeline,
-        channel_bind_group: wgpu::BindGroup,
-        channel_resources: Vec<ChannelResources>,
-        channel_layout: wgpu::BindGroupLayout,
-        has_keyboard: bool,
-        shader_source: PathBuf,
-    ) -> Self {
-        Self {
-            pipeline,
-            channel_bind_group,
-            channel_resources,
-            _channel_layout: channel_layout,
-            has_keyboard,
-            shader_source,
+    /// Blocks until the sound pass these channels read has been rendered.
+    pub fn wait_for_sound(&self) {
+        if let Some(clip) = &self.sound_clip {
+            clip.wait();
         }
     }
 
@@ -183,6 +266,17 @@ impl ShaderPipeline {
         self.has_keyboard
     }
 
+    /// True when the image pass reads no time, frame, mouse or date uniform and
+    /// samples no channel, so a single rendered frame never goes stale.
+    pub fn is_time_independent(&self) -> bool {
+        self.usage.is_some_and(|usage| usage.is_time_independent())
+    }
+
+    /// `iSampleRate` override when any pass reads a live audio stream.
+    pub fn live_sample_rate(&self) -> Option<u32> {
+        self.live_sample_rate
+    }
+
     pub fn update_keyboard_channels(&self, queue: &wgpu::Queue, data: &[u8]) {
         if !self.has_keyboard_channel() {
             return;
@@ -190,7 +284,151 @@ impl ShaderPipeline {
         for resource in &self.channel_resources {
             resource.update_keyboard(queue, data);
         }
+        if let Some(chain) = &self.buffers {
+            chain.update_keyboard_channels(queue, data);
+        }
+    }
+}
+
+/// Compiles one pass and reconciles its channel bindings with the channels the
+/// shader samples, warning by pack and channel about reads of unbound channels
+/// and, when compilation fails, about channels bound with the wrong kind.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn compile_pass_shader(
+    device: &wgpu::Device,
+    layouts: &PipelineLayouts,
+    source: &PassSource,
+    target: PassTarget,
+    bindings: &ChannelBindings,
+    kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+    color_space: SurfaceColorSpace,
+    shader_compiler: ShaderCompiler,
+    pass: &str,
+) -> Result<CompiledShader> {
+    let pack = pack_name(source);
+    let compiled = compile_fragment_shader(
+        device,
+        source,
+        target,
+        kinds,
+        color_space,
+        shader_compiler,
+        layouts.shader_cache(),
+    );
+    let compiled = match compiled {
+        Ok(compiled) => compiled,
+        Err(err) => {
+            for (channel, sampled_as) in channel_kind_mismatches(source, target, kinds) {
+                tracing::warn!(
+                    pack = %pack,
+                    pass,
+                    channel,
+                    "iChannel{channel} is bound as a {} but the shader samples it as a {}",
+                    kinds[channel].describe(),
+                    sampled_as.describe(),
+                );
+            }
+            return Err(err);
+        }
+    };
+
+    if let Some(usage) = compiled.usage {
+        for (channel, &sampled) in usage.channels.iter().enumerate() {
+            match (sampled, bindings.channel(channel)) {
+                (true, None) => tracing::warn!(
+                    pack = %pack,
+                    pass,
+                    channel,
+                    "shader samples iChannel{channel} but the pack binds nothing to it"
+                ),
+                (false, Some(_)) => tracing::debug!(
+                    pack = %pack,
+                    pass,
+                    channel,
+                    "shader never samples iChannel{channel}; skipping its input"
+                ),
+                _ => {}
+            }
+        }
     }
+    Ok(compiled)
+}
+
+/// Directory name of the pack a pass belongs to, for log messages.
+fn pack_name(source: &PassSource) -> String {
+    let path = source.files.first().map(PathBuf::as_path);
+    path.and_then(Path::parent)
+        .and_then(Path::file_name)
+        .or_else(|| path.and_then(Path::file_name))
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "<unknown>".to_owned())
+}
+
+pub(crate) fn create_channel_layout(
+    device: &wgpu::Device,
+    kinds: &[ChannelTextureKind; CHANNEL_COUNT],
+) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("channel layout"),
+        entries: &build_channel_layout_entries(kinds),
+    })
+}
+
+/// Builds a full-screen triangle pipeline for a wrapped fragment module.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_render_pipeline(
+    device: &wgpu::Device,
+    layouts: &PipelineLayouts,
+    channel_layout: &wgpu::BindGroupLayout,
+    fragment_module: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    blend: Option<wgpu::BlendState>,
+    label: &str,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("shader pipeline layout"),
+        bind_group_layouts: &[&layouts.uniform_layout, channel_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &layouts.vertex_module,
+            entry_point: Some("main"),
+            buffers: &[],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fragment_module,
+            entry_point: Some("main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        multiview: None,
+        cache: layouts.pipeline_cache.as_ref().map(|(cache, _)| cache),
+    })
 }
 
 pub(crate) fn build_channel_entries(
@@ -218,6 +456,7 @@ pub(crate) fn build_channel_layout_entries(
         let dimension = match kind {
             ChannelTextureKind::Texture2d => wgpu::TextureViewDimension::D2,
             ChannelTextureKind::Cubemap => wgpu::TextureViewDimension::Cube,
+            ChannelTextureKind::Texture3d => wgpu::TextureViewDimension::D3,
         };
         entries.push(wgpu::BindGroupLayoutEntry {
             binding: (index as u32) * 2,
@@ -0,0 +1,467 @@
+//! Offline evaluation of the ShaderToy "Sound" pass.
+//!
+//! ShaderToy synthesises audio on the GPU: `mainSound` runs once per sample and
+//! the results are read back in blocks. We do the same with a fixed-size float
+//! target where every texel is one stereo sample:
+//!
+//! ```text
+//!   block b (iFrame = b)            readback
+//!   ┌──────────── 512 ───────────┐
+//!   │ samp = b·512² + y·512 + x  │ ──▶ [L R L R …] ──▶ WAV export
+//!   │            …               │              └───▶ AudioClip (sound channel)
+//!   └────────────────────────────┘ 512
+//! ```
+//!
+//! The rendered samples either go to a WAV file (`export_sound`) or become an
+//! `AudioClip` that image/buffer passes read like any other audio channel. That
+//! clip renders on a worker thread, once per sound pass source.
+
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::audio::{self, AudioClip, PendingClip};
+use crate::compile::PassTarget;
+use crate::preprocess::load_pass_source;
+use crate::shader_cache::{key_hasher, ShaderCache};
+use crate::types::{BufferPass, ChannelBindings, ChannelSource, ShaderCompiler, SoundExportConfig};
+
+use super::channels::{self, ChannelResources};
+use super::context::{self, SurfaceColorSpace};
+use super::pipeline::{
+    build_channel_entries, compile_pass_shader, create_channel_layout, create_render_pipeline,
+    PipelineLayouts,
+};
+use super::uniforms::{ShadertoyUniforms, DEFAULT_SAMPLE_RATE};
+
+/// Edge length of one block of samples.
+const SOUND_BLOCK_SIZE: u32 = 512;
+/// Samples produced by a single draw.
+const SOUND_BLOCK_SAMPLES: usize = (SOUND_BLOCK_SIZE * SOUND_BLOCK_SIZE) as usize;
+/// Two 16-bit samples per texel, packed little-endian (`rg` = left, `ba` = right).
+const SOUND_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+const SOUND_TEXEL_BYTES: u32 = 4;
+/// Length of the clip exposed to passes that sample the sound pass; it loops.
+const SOUND_CHANNEL_SECONDS: f32 = 60.0;
+
+/// Compiled sound pass plus the target and readback buffer it renders through.
+pub(crate) struct SoundRenderer {
+    name: String,
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    channel_bind_group: wgpu::BindGroup,
+    channel_resources: Vec<ChannelResources>,
+    target: wgpu::Texture,
+    target_view: wgpu::TextureView,
+    readback: wgpu::Buffer,
+    sample_rate: u32,
+}
+
+impl SoundRenderer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layouts: &PipelineLayouts,
+        pass: &BufferPass,
+        sample_rate: u32,
+        color_space: SurfaceColorSpace,
+        shader_compiler: ShaderCompiler,
+        include_dirs: &[PathBuf],
+    ) -> Result<Self> {
+        let code = load_pass_source(&pass.source, pass.channels.language(), include_dirs)
+            .with_context(|| {
+                format!(
+                    "failed to read sound pass '{}' at {}",
+                    pass.name,
+                    pass.source.display()
+                )
+            })?;
+        let kinds = pass.channels.layout_signature();
+        let compiled = compile_pass_shader(
+            device,
+            layouts,
+            &code,
+            PassTarget::Sound,
+            &pass.channels,
+            &kinds,
+            color_space,
+            shader_compiler,
+            &pass.name,
+        )
+        .with_context(|| format!("failed to compile sound pass '{}'", pass.name))?;
+        // The sound pass cannot read itself, so it never receives a clip.
+        let channel_resources = channels::create_resources(
+            device,
+            queue,
+            layouts.mipmaps(),
+            &pass.channels,
+            &kinds,
+            &compiled.sampled_channels(),
+            color_space,
+            None,
+        )?;
+        let module = compiled.module;
+        let channel_layout = create_channel_layout(device, &kinds);
+        let channel_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sound channel bind group"),
+            layout: &channel_layout,
+            entries: &build_channel_entries(&channel_resources),
+        });
+        let pipeline = create_render_pipeline(
+            device,
+            layouts,
+            &channel_layout,
+            &module,
+            SOUND_FORMAT,
+            1,
+            None,
+            "sound pipeline",
+        );
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sound uniform buffer"),
+            size: std::mem::size_of::<ShadertoyUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sound uniform bind group"),
+            layout: &layouts.uniform_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&format!("sound '{}' target", pass.name)),
+            size: wgpu::Extent3d {
+                width: SOUND_BLOCK_SIZE,
+                height: SOUND_BLOCK_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SOUND_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sound readback"),
+            size: u64::from(SOUND_BLOCK_SIZE * SOUND_BLOCK_SIZE * SOUND_TEXEL_BYTES),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Ok(Self {
+            name: pass.name.clone(),
+            pipeline,
+            uniform_buffer,
+            uniform_bind_group,
+            channel_bind_group,
+            channel_resources,
+            target,
+            target_view,
+            readback,
+            sample_rate,
+        })
+    }
+
+    /// Evaluates `mainSound` for the first `frames` samples, returning interleaved stereo.
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        frames: usize,
+    ) -> Result<Vec<f32>> {
+        let mut samples = Vec::with_capacity(frames * 2);
+        let blocks = frames.div_ceil(SOUND_BLOCK_SAMPLES);
+        for block in 0..blocks {
+            self.render_block(device, queue, block, &mut samples)
+                .with_context(|| format!("failed to render sound pass '{}'", self.name))?;
+        }
+        samples.truncate(frames * 2);
+        Ok(samples)
+    }
+
+    fn render_block(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        block: usize,
+        out: &mut Vec<f32>,
+    ) -> Result<()> {
+        let start_seconds = (block * SOUND_BLOCK_SAMPLES) as f32 / self.sample_rate as f32;
+        let mut uniforms = ShadertoyUniforms::new(SOUND_BLOCK_SIZE, SOUND_BLOCK_SIZE);
+        uniforms.i_time = start_seconds;
+        uniforms.i_frame = i32::try_from(block).map_err(|_| anyhow!("sound is too long"))?;
+        uniforms.set_sample_rate(self.sample_rate as f32);
+        let times = channels::update_timed_channels(&self.channel_resources, queue, start_seconds);
+        for (index, resource) in self.channel_resources.iter().enumerate() {
+            uniforms.set_channel_resolution(index, resource.resolution);
+            uniforms.set_channel_time(index, times[index].unwrap_or(start_seconds));
+        }
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("sound encoder"),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("sound pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.target_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.channel_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+        encoder.copy_texture_to_buffer(
+            self.target.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(SOUND_BLOCK_SIZE * SOUND_TEXEL_BYTES),
+                    rows_per_image: Some(SOUND_BLOCK_SIZE),
+                },
+            },
+            wgpu::Extent3d {
+                width: SOUND_BLOCK_SIZE,
+                height: SOUND_BLOCK_SIZE,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = self.readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device
+            .poll(wgpu::PollType::Wait)
+            .map_err(|err| anyhow!("GPU poll failed: {err}"))?;
+        receiver
+            .recv()
+            .map_err(|_| anyhow!("readback callback dropped"))?
+            .map_err(|err| anyhow!("failed to map sound readback: {err}"))?;
+        {
+            let mapped = slice.get_mapped_range();
+            out.extend(
+                mapped
+                    .chunks_exact(2)
+                    .map(|pair| unpack_sample([pair[0], pair[1]])),
+            );
+        }
+        self.readback.unmap();
+        Ok(())
+    }
+}
+
+/// Starts rendering the sound pass into a looping mono clip when any pass samples it.
+///
+/// The clip renders on a worker thread so pipeline construction never waits on
+/// it; channels reading it stay silent until it lands. Clips are kept per source
+/// on `layouts`, so swaps and resize-driven rebuilds reuse the audio.
+pub(crate) fn render_sound_channel(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layouts: &PipelineLayouts,
+    bindings: &ChannelBindings,
+    color_space: SurfaceColorSpace,
+    shader_compiler: ShaderCompiler,
+    include_dirs: &[PathBuf],
+) -> Option<PendingClip> {
+    let pass = bindings.sound_pass()?;
+    let reads_sound = |channels: &ChannelBindings| {
+        channels
+            .slots()
+            .iter()
+            .any(|slot| matches!(slot, Some(ChannelSource::SoundPass { .. })))
+    };
+    let sampled = reads_sound(bindings)
+        || bindings
+            .buffer_passes()
+            .iter()
+            .chain(bindings.cubemap_pass())
+            .any(|buffer| reads_sound(&buffer.channels));
+    if !sampled {
+        return None;
+    }
+
+    let code = match load_pass_source(&pass.source, pass.channels.language(), include_dirs) {
+        Ok(source) => source.code,
+        Err(error) => {
+            tracing::warn!(
+                pass = %pass.name,
+                error = %format!("{error:#}"),
+                "failed to read sound pass; channels reading it stay silent"
+            );
+            return None;
+        }
+    };
+    let mut hasher = key_hasher();
+    code.hash(&mut hasher);
+    format!("{:?}", pass.channels).hash(&mut hasher);
+    color_space.hash(&mut hasher);
+    shader_compiler.hash(&mut hasher);
+    let key = hasher.finish();
+
+    let mut clips = layouts.sound_clips().lock().expect("sound clips poisoned");
+    if let Some(clip) = clips.get(&key) {
+        return Some(clip.clone());
+    }
+    clips.retain(|_, clip| !clip.is_unshared());
+    let clip = PendingClip::default();
+    clips.insert(key, clip.clone());
+    drop(clips);
+
+    let worker = SoundWorker {
+        device: device.clone(),
+        queue: queue.clone(),
+        layouts: layouts.clone(),
+        pass: pass.clone(),
+        color_space,
+        shader_compiler,
+        include_dirs: include_dirs.to_vec(),
+        clip: clip.clone(),
+    };
+    let spawned = std::thread::Builder::new()
+        .name("wax11-sound".into())
+        .spawn(move || worker.run());
+    if let Err(error) = spawned {
+        tracing::warn!(
+            pass = %pass.name,
+            error = %error,
+            "failed to start sound pass renderer; channels reading it stay silent"
+        );
+        clip.finish(None);
+    }
+    Some(clip)
+}
+
+/// Everything the background sound-pass render needs.
+struct SoundWorker {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layouts: PipelineLayouts,
+    pass: BufferPass,
+    color_space: SurfaceColorSpace,
+    shader_compiler: ShaderCompiler,
+    include_dirs: Vec<PathBuf>,
+    clip: PendingClip,
+}
+
+impl SoundWorker {
+    fn run(self) {
+        let sample_rate = DEFAULT_SAMPLE_RATE as u32;
+        let frames = (SOUND_CHANNEL_SECONDS * DEFAULT_SAMPLE_RATE) as usize;
+        let rendered = SoundRenderer::new(
+            &self.device,
+            &self.queue,
+            &self.layouts,
+            &self.pass,
+            sample_rate,
+            self.color_space,
+            self.shader_compiler,
+            &self.include_dirs,
+        )
+        .and_then(|renderer| renderer.render(&self.device, &self.queue, frames));
+        match rendered {
+            Ok(stereo) => {
+                let mono = stereo
+                    .chunks_exact(2)
+                    .map(|frame| (frame[0] + frame[1]) * 0.5)
+                    .collect();
+                self.clip.finish(Some(AudioClip::new(mono, sample_rate)));
+            }
+            Err(error) => {
+                tracing::warn!(
+                    pass = %self.pass.name,
+                    error = %format!("{error:#}"),
+                    "failed to render sound pass; channels reading it stay silent"
+                );
+                self.clip.finish(None);
+            }
+        }
+    }
+}
+
+/// Renders `config.duration` of the sound pass on a surfaceless device and writes a WAV file.
+pub(crate) fn export_sound(config: &SoundExportConfig) -> Result<()> {
+    if config.sample_rate == 0 {
+        anyhow::bail!("sound export needs a non-zero sample rate");
+    }
+    let frames = frames_for(config.duration, config.sample_rate);
+    if frames == 0 {
+        anyhow::bail!("sound export duration must be positive");
+    }
+
+    let (device, queue) = context::request_headless_device(config.gpu_power)?;
+    let shader_cache = config.shader_cache_dir.as_deref().and_then(|dir| {
+        ShaderCache::open(dir)
+            .map_err(|err| tracing::warn!(error = %err, "shader cache disabled"))
+            .ok()
+    });
+    let layouts = PipelineLayouts::new(&device, config.shader_compiler, shader_cache, None)?;
+    let color_space = SurfaceColorSpace::from_mode(config.color_space);
+    let renderer = SoundRenderer::new(
+        &device,
+        &queue,
+        &layouts,
+        &config.pass,
+        config.sample_rate,
+        color_space,
+        config.shader_compiler,
+        &config.shader_include_dirs,
+    )?;
+    let samples = renderer.render(&device, &queue, frames)?;
+    audio::write_wav_pcm16(&config.path, &samples, 2, config.sample_rate)?;
+    tracing::info!(
+        path = %config.path.display(),
+        seconds = config.duration.as_secs_f32(),
+        sample_rate = config.sample_rate,
+        "exported sound pass"
+    );
+    Ok(())
+}
+
+/// Inverts the footer's packing: 0..=65535 maps back onto [-1, 1).
+fn unpack_sample(bytes: [u8; 2]) -> f32 {
+    (f32::from(u16::from_le_bytes(bytes)) - 32768.0) / 32768.0
+}
+
+fn frames_for(duration: Duration, sample_rate: u32) -> usize {
+    (duration.as_secs_f64() * f64::from(sample_rate)).round() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpacks_footer_sample_encoding() {
+        assert_eq!(unpack_sample([0, 0]), -1.0);
+        assert_eq!(unpack_sample([0, 128]), 0.0);
+        assert!((unpack_sample([255, 255]) - 1.0).abs() < 1e-4);
+        assert_eq!(frames_for(Duration::from_millis(500), 44_100), 22_050);
+    }
+}
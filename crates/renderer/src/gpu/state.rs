@@ -9,21 +9,34 @@ use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use wgpu::util::DeviceExt;
 use winit::dpi::PhysicalSize;
 
-use crate::runtime::{FillMethod, TimeSample};
+use crate::runtime::{
+    ExportFormat, FillMethod, ProgressiveRefine, RefinePass, RenderPolicy, TimeSample,
+};
+use crate::shader_cache::ShaderCache;
 use crate::types::{
     AdapterProfile, Antialiasing, ChannelBindings, ChannelTextureKind, ColorSpaceMode,
-    CrossfadeCurve, GpuMemoryMode, GpuPowerPreference, ShaderCompiler, VsyncMode, CHANNEL_COUNT,
+    CrossfadeCurve, DynamicScale, GpuMemoryMode, GpuPowerPreference, RendererConfig,
+    ShaderCompiler, UpscaleFilter, VsyncMode, CHANNEL_COUNT,
 };
 
-use super::channels::{KEYBOARD_BYTES_PER_PIXEL, KEYBOARD_TEXTURE_HEIGHT, KEYBOARD_TEXTURE_WIDTH};
-use super::context::{GpuContext, SurfaceColorSpace};
+use super::accumulate::{sample_jitter, Accumulator};
+use super::capture;
+use super::channels::{
+    self, KEYBOARD_BYTES_PER_PIXEL, KEYBOARD_TEXTURE_HEIGHT, KEYBOARD_TEXTURE_WIDTH,
+};
+use super::context::{Frame, GpuContext, SurfaceColorSpace};
+use super::governor::{FrameCost, FrameTimer, ResolutionGovernor};
+use super::multipass::BufferChain;
 use super::pipeline::{PipelineLayouts, ShaderPipeline};
 use super::timeline::FadeEnvelope;
-use super::uniforms::{fill_parameters, logical_dimensions, ShadertoyUniforms};
+use super::uniforms::{
+    fill_parameters, logical_dimensions, ShadertoyUniforms, DEFAULT_SAMPLE_RATE,
+};
+use super::upscale::{scale_extent, Upscaler};
 
 const PIPELINE_CACHE_MODE: PipelineCacheMode = PipelineCacheMode::OnDemand;
 const PIPELINE_BUILD_STRATEGY: PipelineBuildStrategy = PipelineBuildStrategy::Threaded;
@@ -72,11 +85,13 @@ struct PipelineKey {
     sample_count: u32,
     surface_format: wgpu::TextureFormat,
     linear_color_space: bool,
+    buffer_sources: Vec<PathBuf>,
 }
 
 impl PipelineKey {
     fn new(
         shader_path: &Path,
+        channel_bindings: &ChannelBindings,
         channel_signature: u8,
         compiler: ShaderCompiler,
         sample_count: u32,
@@ -90,6 +105,12 @@ impl PipelineKey {
             sample_count,
             surface_format,
             linear_color_space: matches!(color_space, SurfaceColorSpace::Linear),
+            buffer_sources: channel_bindings
+                .buffer_passes()
+                .iter()
+                .chain(channel_bindings.cubemap_pass())
+                .map(|pass| pass.source.clone())
+                .collect(),
         }
     }
 }
@@ -148,23 +169,38 @@ impl PipelineFuture {
     }
 }
 
+fn apply_channel_times(uniforms: &mut ShadertoyUniforms, times: [Option<f32>; CHANNEL_COUNT]) {
+    for (channel, time) in times.into_iter().enumerate() {
+        if let Some(time) = time {
+            uniforms.set_channel_time(channel, time);
+        }
+    }
+}
+
+/// Packs each channel's kind into two bits so pipelines are cached per layout.
 fn compute_channel_signature(kinds: &[ChannelTextureKind; CHANNEL_COUNT]) -> u8 {
     kinds.iter().enumerate().fold(0u8, |acc, (index, kind)| {
-        let bit = matches!(kind, ChannelTextureKind::Cubemap) as u8;
-        acc | (bit << index)
+        let bits = match kind {
+            ChannelTextureKind::Texture2d => 0u8,
+            ChannelTextureKind::Cubemap => 1,
+            ChannelTextureKind::Texture3d => 2,
+        };
+        acc | (bits << (index * 2))
     })
 }
 
 #[derive(Debug, Clone)]
 pub struct FileExportTarget {
     pub path: PathBuf,
-    pub _format: crate::runtime::ExportFormat,
+    pub format: ExportFormat,
 }
 
 #[derive(Debug)]
 pub enum RenderExportError {
     Surface(wgpu::SurfaceError),
     Unsupported,
+    /// Reading the frame back or writing the file failed.
+    Capture(anyhow::Error),
 }
 
 impl RenderExportError {
@@ -186,7 +222,10 @@ impl fmt::Display for RenderExportError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             RenderExportError::Surface(err) => write!(f, "surface error: {err:?}"),
-            RenderExportError::Unsupported => write!(f, "still-frame export is not implemented"),
+            RenderExportError::Unsupported => {
+                write!(f, "still-frame export is not supported for this target")
+            }
+            RenderExportError::Capture(err) => write!(f, "{err:#}"),
         }
     }
 }
@@ -196,6 +235,7 @@ impl std::error::Error for RenderExportError {
         match self {
             RenderExportError::Surface(err) => Some(err),
             RenderExportError::Unsupported => None,
+            RenderExportError::Capture(err) => Some(err.as_ref()),
         }
     }
 }
@@ -208,6 +248,7 @@ pub(crate) struct GpuState {
     uniforms: ShadertoyUniforms,
     channel_kinds: [ChannelTextureKind; CHANNEL_COUNT],
     shader_compiler: ShaderCompiler,
+    shader_include_dirs: Arc<[PathBuf]>,
     render_scale: f32,
     fill_method: FillMethod,
     crossfade_curve: CrossfadeCurve,
@@ -217,6 +258,13 @@ pub(crate) struct GpuState {
     pipeline_cache: PipelineCache,
     fade: Option<FadeEnvelope>,
     multisample_target: Option<MultisampleTarget>,
+    upscaler: Option<Upscaler>,
+    upscale_filter: UpscaleFilter,
+    governor: Option<ResolutionGovernor>,
+    accumulator: Option<Accumulator>,
+    jitter: [f32; 2],
+    frame_timer: Option<FrameTimer>,
+    last_render_start: Option<Instant>,
     start_time: Instant,
     last_frame_time: Instant,
     frame_count: u32,
@@ -340,7 +388,11 @@ impl GpuState {
         antialiasing: Antialiasing,
         color_space: ColorSpaceMode,
         shader_compiler: ShaderCompiler,
+        shader_include_dirs: &[PathBuf],
+        shader_cache_dir: Option<&Path>,
         render_scale: f32,
+        upscale_filter: UpscaleFilter,
+        dynamic_scale: Option<DynamicScale>,
         fill_method: FillMethod,
         gpu_power: GpuPowerPreference,
         gpu_memory: GpuMemoryMode,
@@ -361,8 +413,87 @@ impl GpuState {
             gpu_latency,
             vsync_mode,
         )?;
+        Self::with_context(
+            context,
+            shader_source,
+            channel_bindings,
+            shader_compiler,
+            shader_include_dirs,
+            shader_cache_dir,
+            render_scale,
+            upscale_filter,
+            dynamic_scale,
+            fill_method,
+            crossfade_curve,
+            vsync_mode,
+        )
+    }
+
+    /// Builds a surfaceless state that renders `config.surface_size` frames into
+    /// an offscreen texture, for exports without a window or compositor. Export
+    /// policies scale the target and pick a float target for EXR. Unlike the live
+    /// paths, it waits for the sound pass so every exported frame hears it.
+    pub(crate) fn new_headless(config: &RendererConfig) -> Result<Self> {
+        let (scale, hdr) = match &config.policy {
+            RenderPolicy::Export { scale, format, .. } => (*scale, *format == ExportFormat::Exr),
+            _ => (1.0, false),
+        };
+        let context = GpuContext::headless(
+            PhysicalSize::new(
+                scale_extent(config.surface_size.0, scale),
+                scale_extent(config.surface_size.1, scale),
+            ),
+            config.antialiasing,
+            config.color_space,
+            config.gpu_power,
+            config.gpu_memory,
+            hdr,
+        )?;
+        let state = Self::with_context(
+            context,
+            &config.shader_source,
+            &config.channel_bindings,
+            config.shader_compiler,
+            &config.shader_include_dirs,
+            config.shader_cache_dir.as_deref(),
+            config.render_scale,
+            config.upscale_filter,
+            None,
+            config.fill_method,
+            config.crossfade_curve,
+            config.vsync_mode,
+        )?;
+        state.current.0.wait_for_sound();
+        Ok(state)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn with_context(
+        context: GpuContext,
+        shader_source: &Path,
+        channel_bindings: &ChannelBindings,
+        shader_compiler: ShaderCompiler,
+        shader_include_dirs: &[PathBuf],
+        shader_cache_dir: Option<&Path>,
+        render_scale: f32,
+        upscale_filter: UpscaleFilter,
+        dynamic_scale: Option<DynamicScale>,
+        fill_method: FillMethod,
+        crossfade_curve: CrossfadeCurve,
+        vsync_mode: VsyncMode,
+    ) -> Result<Self> {
         let channel_kinds = channel_bindings.layout_signature();
-        let layouts = PipelineLayouts::new(&context.device, shader_compiler)?;
+        let shader_cache = shader_cache_dir.and_then(|dir| {
+            ShaderCache::open(dir)
+                .map_err(|err| warn!(error = %err, "shader cache disabled"))
+                .ok()
+        });
+        let layouts = PipelineLayouts::new(
+            &context.device,
+            shader_compiler,
+            shader_cache,
+            context.pipeline_cache_key.clone(),
+        )?;
 
         let uniform_buffer = context.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("uniform buffer"),
@@ -387,18 +518,22 @@ impl GpuState {
             &layouts,
             context.surface_format,
             context.sample_count,
+            context.buffer_format,
             shader_source,
             channel_bindings,
             &channel_kinds,
             context.color_space,
             shader_compiler,
+            shader_include_dirs,
         )?;
 
+        layouts.persist_pipeline_cache();
         let current = PipelineHandle::from_pipeline(current);
         let mut pipeline_cache = PipelineCache::new(PIPELINE_CACHE_MODE);
         if pipeline_cache.enabled() {
             let key = PipelineKey::new(
                 shader_source,
+                channel_bindings,
                 compute_channel_signature(&channel_kinds),
                 shader_compiler,
                 context.sample_count,
@@ -412,18 +547,18 @@ impl GpuState {
         uniforms.set_fade(1.0);
         Self::write_uniforms(&context.queue, &uniform_buffer, &uniforms);
 
-        let multisample_target = if context.sample_count > 1 {
-            Some(MultisampleTarget::new(
-                &context.device,
-                context.surface_format,
-                context.size,
-                context.sample_count,
-            ))
-        } else {
-            None
-        };
+        let governor = dynamic_scale.map(ResolutionGovernor::new);
+        let frame_timer = governor
+            .as_ref()
+            .and_then(|_| FrameTimer::new(&context.device, &context.queue));
+        if governor.is_some() && frame_timer.is_none() {
+            debug!("GPU timestamps unavailable; dynamic scale follows the frame interval");
+        }
+        let render_scale = governor
+            .as_ref()
+            .map_or(render_scale, ResolutionGovernor::scale);
 
-        Ok(Self {
+        let mut state = Self {
             context,
             layouts,
             uniform_buffer,
@@ -431,6 +566,7 @@ impl GpuState {
             uniforms,
             channel_kinds,
             shader_compiler,
+            shader_include_dirs: shader_include_dirs.into(),
             render_scale,
             fill_method,
             crossfade_curve,
@@ -439,7 +575,14 @@ impl GpuState {
             pending: None,
             pipeline_cache,
             fade: None,
-            multisample_target,
+            multisample_target: None,
+            upscaler: None,
+            upscale_filter,
+            governor,
+            accumulator: None,
+            jitter: [0.0; 2],
+            frame_timer,
+            last_render_start: None,
             start_time: Instant::now(),
             last_frame_time: Instant::now(),
             frame_count: 0,
@@ -449,7 +592,9 @@ impl GpuState {
             frames_per_second: 60.0,
             vsync_mode,
             is_crossfading: false,
-        })
+        };
+        state.apply_render_scale();
+        Ok(state)
     }
 
     pub(crate) fn size(&self) -> PhysicalSize<u32> {
@@ -464,6 +609,15 @@ impl GpuState {
         &self.context.adapter_profile
     }
 
+    /// True once no swap is in flight and the current shader reads nothing that
+    /// changes between frames, so the last presented frame can stay on screen.
+    pub(crate) fn is_time_independent(&self) -> bool {
+        self.pending.is_none()
+            && self.previous.is_none()
+            && !self.is_crossfading
+            && self.current.is_time_independent()
+    }
+
     pub(crate) fn has_keyboard_channel(&self) -> bool {
         self.current.has_keyboard_channel()
             || self
@@ -511,11 +665,37 @@ impl GpuState {
             new_size.width as f32,
             new_size.height as f32,
         );
+        self.accumulator = None;
+        self.apply_render_scale();
+    }
+
+    /// Sizes the upscale and MSAA targets for the output size and `render_scale`.
+    ///
+    /// Below native scale the image pass renders offscreen and is stretched
+    /// over the frame, so MSAA has to match the scaled target instead.
+    fn apply_render_scale(&mut self) {
+        let device = &self.context.device;
+        if self.render_scale < 1.0 {
+            match self.upscaler.as_mut() {
+                Some(upscaler) => upscaler.resize(device, self.context.size, self.render_scale),
+                None => {
+                    self.upscaler = Some(Upscaler::new(
+                        device,
+                        self.context.surface_format,
+                        self.context.size,
+                        self.render_scale,
+                        self.upscale_filter,
+                    ))
+                }
+            }
+        } else {
+            self.upscaler = None;
+        }
         self.multisample_target = if self.context.sample_count > 1 {
             Some(MultisampleTarget::new(
-                &self.context.device,
+                device,
                 self.context.surface_format,
-                self.context.size,
+                self.shading_geometry().0,
                 self.context.sample_count,
             ))
         } else {
@@ -523,6 +703,39 @@ impl GpuState {
         };
     }
 
+    /// Feeds the last frame's cost to the governor and rescales when it asks to.
+    fn govern_render_scale(&mut self, now: Instant) {
+        let Some(governor) = self.governor.as_mut() else {
+            return;
+        };
+        let cost = match self.frame_timer.as_mut() {
+            Some(timer) => timer.collect(&self.context.device).map(FrameCost::Gpu),
+            None => self
+                .last_render_start
+                .map(|last| FrameCost::Interval(now.saturating_duration_since(last))),
+        };
+        self.last_render_start = Some(now);
+        let Some(cost) = cost else {
+            return;
+        };
+        let average = governor.average_cost();
+        let Some(scale) = governor.observe(cost) else {
+            return;
+        };
+        // Buffer targets follow the render scale, so a change restarts their feedback.
+        let has_buffers = |pipeline: &PipelineHandle| pipeline.buffers.is_some();
+        info!(
+            from = %format_args!("{:.2}", self.render_scale),
+            to = %format_args!("{scale:.2}"),
+            average_ms = %format_args!("{:.1}", average.unwrap_or_default() * 1000.0),
+            resets_feedback = has_buffers(&self.current)
+                || self.previous.as_ref().is_some_and(has_buffers),
+            "render scale changed"
+        );
+        self.render_scale = scale;
+        self.apply_render_scale();
+    }
+
     pub(crate) fn set_shader(
         &mut self,
         shader_source: &Path,
@@ -539,6 +752,7 @@ impl GpuState {
 
         let key = PipelineKey::new(
             shader_source,
+            channel_bindings,
             compute_channel_signature(&self.channel_kinds),
             self.shader_compiler,
             self.context.sample_count,
@@ -583,12 +797,15 @@ impl GpuState {
             &self.layouts,
             self.context.surface_format,
             self.context.sample_count,
+            self.context.buffer_format,
             shader_source,
             channel_bindings,
             &self.channel_kinds,
             self.context.color_space,
             self.shader_compiler,
+            &self.shader_include_dirs,
         )?;
+        self.layouts.persist_pipeline_cache();
         Ok(PipelineHandle::from_pipeline(pipeline))
     }
 
@@ -602,9 +819,11 @@ impl GpuState {
         let layouts = self.layouts.clone();
         let surface_format = self.context.surface_format;
         let sample_count = self.context.sample_count;
+        let buffer_format = self.context.buffer_format;
         let channel_kinds = self.channel_kinds;
         let color_space = self.context.color_space;
         let compiler = self.shader_compiler;
+        let include_dirs = Arc::clone(&self.shader_include_dirs);
 
         let (sender, receiver) = mpsc::channel();
 
@@ -615,13 +834,18 @@ impl GpuState {
                 &layouts,
                 surface_format,
                 sample_count,
+                buffer_format,
                 shader_path.as_path(),
                 &channel_bindings,
                 &channel_kinds,
                 color_space,
                 compiler,
+                &include_dirs,
             )
             .map(PipelineHandle::from_pipeline);
+            if result.is_ok() {
+                layouts.persist_pipeline_cache();
+            }
             let _ = sender.send(result);
         });
 
@@ -638,24 +862,123 @@ impl GpuState {
         Ok(())
     }
 
+    /// Renders and presents the current pass of a progressive still, then
+    /// advances `refine` to the next one.
+    pub(crate) fn render_refine(
+        &mut self,
+        mouse: [f32; 4],
+        time_sample: Option<TimeSample>,
+        refine: &mut ProgressiveRefine,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let Some(mut pass) = refine.current() else {
+            return self.render(mouse, time_sample);
+        };
+        // Buffer targets follow the render scale and feed back into themselves,
+        // so shaders with buffer passes get a single full-resolution frame.
+        if self.current.buffers.is_some() {
+            pass = RefinePass::Sample { index: 0, count: 1 };
+            refine.finish();
+        }
+        let (scale, accumulate) = match pass {
+            RefinePass::Scaled(scale) => (scale, None),
+            RefinePass::Sample { index, count } => (1.0, (count > 1).then_some(index)),
+        };
+        if scale != self.render_scale {
+            self.render_scale = scale;
+            self.apply_render_scale();
+        }
+        match accumulate {
+            Some(index) => {
+                let accumulator = self.accumulator.get_or_insert_with(|| {
+                    Accumulator::new(
+                        &self.context.device,
+                        self.context.surface_format,
+                        self.context.size,
+                    )
+                });
+                if index == 0 {
+                    accumulator.reset();
+                }
+                self.jitter = sample_jitter(index);
+            }
+            None => {
+                self.accumulator = None;
+                self.jitter = [0.0; 2];
+            }
+        }
+        debug!(?pass, "rendering progressive pass");
+        let result = self.render(mouse, time_sample);
+        if result.is_ok() {
+            refine.advance();
+        }
+        if refine.is_finished() {
+            self.accumulator = None;
+            self.jitter = [0.0; 2];
+        }
+        result
+    }
+
+    /// Renders one frame, reads it back and writes it to `target.path`. Surface
+    /// frames are still presented afterwards.
     pub(crate) fn render_export(
         &mut self,
-        _mouse: [f32; 4],
-        _time_sample: Option<TimeSample>,
-        _target: &FileExportTarget,
+        mouse: [f32; 4],
+        time_sample: Option<TimeSample>,
+        target: &FileExportTarget,
     ) -> Result<PathBuf, RenderExportError> {
-        Err(RenderExportError::Unsupported)
+        match target.format {
+            ExportFormat::Png => {
+                let (width, height, pixels) = self.render_rgba8(mouse, time_sample)?;
+                capture::write_png(&target.path, width, height, pixels)
+            }
+            ExportFormat::Exr => {
+                let (width, height, pixels) =
+                    self.render_readback(mouse, time_sample, capture::read_rgba16f)?;
+                let decode_srgb = self.context.color_space == SurfaceColorSpace::Gamma;
+                capture::write_exr(&target.path, width, height, pixels, decode_srgb)
+            }
+            // Animated formats are assembled from `render_rgba8` frames by `export`.
+            ExportFormat::Gif | ExportFormat::Apng => return Err(RenderExportError::Unsupported),
+        }
+        .map_err(RenderExportError::Capture)?;
+        Ok(target.path.clone())
+    }
+
+    /// Renders one frame and returns its size and RGBA8 pixels.
+    pub(crate) fn render_rgba8(
+        &mut self,
+        mouse: [f32; 4],
+        time_sample: Option<TimeSample>,
+    ) -> Result<(u32, u32, Vec<u8>), RenderExportError> {
+        self.render_readback(mouse, time_sample, capture::read_rgba8)
+    }
+
+    fn render_readback<T>(
+        &mut self,
+        mouse: [f32; 4],
+        time_sample: Option<TimeSample>,
+        read: fn(&wgpu::Device, &wgpu::Queue, &wgpu::Texture) -> Result<T>,
+    ) -> Result<(u32, u32, T), RenderExportError> {
+        if !self.context.supports_readback() {
+            return Err(RenderExportError::Unsupported);
+        }
+        let frame = self.render_internal(mouse, time_sample)?;
+        let texture = frame.texture();
+        let (width, height) = (texture.width(), texture.height());
+        let pixels = read(&self.context.device, &self.context.queue, texture);
+        frame.present();
+        Ok((width, height, pixels.map_err(RenderExportError::Capture)?))
     }
 
     fn render_internal(
         &mut self,
         mouse: [f32; 4],
         time_sample: Option<TimeSample>,
-    ) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
+    ) -> Result<Frame, wgpu::SurfaceError> {
         // Acquire the next frame texture early. This call can block, so we do it before
         // handling shader transitions to avoid compounding delays.
         let frame_acquisition_start = Instant::now();
-        let frame = self.context.surface.get_current_texture()?;
+        let frame = self.context.acquire_frame()?;
         let frame_acquisition_duration = frame_acquisition_start.elapsed();
         let frame_time_budget = Duration::from_secs_f32(1.0 / self.frames_per_second);
 
@@ -669,6 +992,7 @@ impl GpuState {
         }
 
         let now = Instant::now();
+        self.govern_render_scale(now);
         self.frames_since_last_update += 1;
         let elapsed_since_fps_update = now.saturating_duration_since(self.last_fps_update);
         if elapsed_since_fps_update >= Duration::from_secs(1) {
@@ -708,8 +1032,15 @@ impl GpuState {
         }
 
         let view = frame
-            .texture
+            .texture()
             .create_view(&wgpu::TextureViewDescriptor::default());
+        // Shader passes draw into the sample or scaled target when accumulating
+        // or upscaling; it reaches the frame through the pass encoded after them.
+        let draw_view = match (self.accumulator.as_ref(), self.upscaler.as_ref()) {
+            (Some(accumulator), _) => accumulator.view().clone(),
+            (None, Some(upscaler)) => upscaler.view().clone(),
+            (None, None) => view.clone(),
+        };
         let mut encoder =
             self.context
                 .device
@@ -717,6 +1048,10 @@ impl GpuState {
                     label: Some("render encoder"),
                 });
 
+        if let Some(timer) = self.frame_timer.as_mut() {
+            timer.begin(&mut encoder);
+        }
+
         let current_pipeline = self.current.clone();
 
         let mut load = wgpu::LoadOp::Clear(wgpu::Color::BLACK);
@@ -735,11 +1070,11 @@ impl GpuState {
             }
 
             if prev_mix > f32::EPSILON {
-                self.encode_draw(&mut encoder, &view, previous, prev_mix, load);
+                self.encode_draw(&mut encoder, &draw_view, previous, prev_mix, load);
                 load = wgpu::LoadOp::Load;
             }
             if curr_mix > f32::EPSILON {
-                self.encode_draw(&mut encoder, &view, &current_pipeline, curr_mix, load);
+                self.encode_draw(&mut encoder, &draw_view, &current_pipeline, curr_mix, load);
             }
             if finished {
                 previous_pipeline = None;
@@ -751,7 +1086,7 @@ impl GpuState {
                 }
             }
         } else {
-            self.encode_draw(&mut encoder, &view, &current_pipeline, 1.0, load);
+            self.encode_draw(&mut encoder, &draw_view, &current_pipeline, 1.0, load);
             previous_pipeline = None;
             fade_state = None;
         }
@@ -767,7 +1102,7 @@ impl GpuState {
                     // driver to compile the shader and allocate resources before the crossfade begins,
                     // preventing a stutter on the first frame of the transition.
                     let prewarm_start = Instant::now();
-                    self.encode_draw(&mut encoder, &view, handle, 0.0, wgpu::LoadOp::Load);
+                    self.encode_draw(&mut encoder, &draw_view, handle, 0.0, wgpu::LoadOp::Load);
                     let prewarm_duration = prewarm_start.elapsed();
                     debug!(
                         shader = %handle.shader_path().display(),
@@ -792,7 +1127,19 @@ impl GpuState {
             }
         }
 
+        if let Some(accumulator) = self.accumulator.as_mut() {
+            accumulator.encode(&mut encoder, &view);
+        } else if let Some(upscaler) = self.upscaler.as_ref() {
+            upscaler.encode(&mut encoder, &view);
+        }
+        if let Some(timer) = self.frame_timer.as_mut() {
+            timer.end(&mut encoder);
+        }
+
         self.context.queue.submit(std::iter::once(encoder.finish()));
+        if let Some(timer) = self.frame_timer.as_mut() {
+            timer.submitted();
+        }
 
         Ok(frame)
     }
@@ -802,18 +1149,42 @@ impl GpuState {
             self.uniforms
                 .set_channel_resolution(index, resource.resolution);
         }
-        self.uniforms.set_fade(mix);
-        let logical = logical_dimensions(self.render_scale, self.fill_method, self.context.size);
-        let (scale_x, scale_y, offset_x, offset_y, wrap_x, wrap_y) = fill_parameters(
-            self.render_scale,
-            self.fill_method,
-            self.context.size,
-            logical,
+        if let Some(chain) = pipeline.buffers.as_ref() {
+            for (index, resolution) in chain.image_channel_resolutions().into_iter().enumerate() {
+                if let Some(resolution) = resolution {
+                    self.uniforms.set_channel_resolution(index, resolution);
+                }
+            }
+        }
+        // Audio and animated channels advance to the current shader time and report their
+        // own (looping) playback position through iChannelTime.
+        let seconds = self.uniforms.i_time;
+        for channel in 0..CHANNEL_COUNT {
+            self.uniforms.set_channel_time(channel, seconds);
+        }
+        let times = channels::update_timed_channels(
+            &pipeline.channel_resources,
+            &self.context.queue,
+            seconds,
         );
+        apply_channel_times(&mut self.uniforms, times);
+        self.uniforms.set_sample_rate(
+            pipeline
+                .live_sample_rate()
+                .map_or(DEFAULT_SAMPLE_RATE, |rate| rate as f32),
+        );
+        self.uniforms.set_fade(mix);
+        let (surface, fill_scale, fill_method) = self.shading_geometry();
+        let logical = logical_dimensions(fill_scale, fill_method, surface);
+        let (scale_x, scale_y, mut offset_x, mut offset_y, wrap_x, wrap_y) =
+            fill_parameters(fill_scale, fill_method, surface, logical);
+        // Progressive samples shift the pixel grid by a sub-pixel jitter.
+        offset_x += self.jitter[0] * scale_x;
+        offset_y += self.jitter[1] * scale_y;
         self.uniforms.set_resolution(logical.0, logical.1);
         self.uniforms.set_surface(
-            self.context.size.width as f32,
-            self.context.size.height as f32,
+            surface.width as f32,
+            surface.height as f32,
             logical.0,
             logical.1,
         );
@@ -838,23 +1209,23 @@ impl GpuState {
         // frame of a crossfade.
         let is_prewarming = mix <= 0.0;
 
+        // Buffer and cubemap passes feed the image pass, so they run first and only for visible draws.
+        if !is_prewarming {
+            if let Some(chain) = pipeline.buffers.as_ref() {
+                self.encode_buffers(encoder, chain);
+            }
+        }
+
         // Upload uniforms for this pass via a staging buffer and copy on the encoder so
         // each pass sees its own uniform values (prevents crossfade mix bleeding).
-        let staging = self
-            .context
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("uniform staging"),
-                contents: bytemuck::bytes_of(&self.uniforms),
-                usage: wgpu::BufferUsages::COPY_SRC,
-            });
-        encoder.copy_buffer_to_buffer(
-            &staging,
-            0,
-            &self.uniform_buffer,
-            0,
-            std::mem::size_of::<ShadertoyUniforms>() as u64,
-        );
+        self.stage_uniforms(encoder, &self.uniforms);
+        let buffer_bind_group = pipeline.buffers.as_ref().and_then(|chain| {
+            chain.image_bind_group(
+                &self.context.device,
+                &pipeline.channel_layout,
+                &pipeline.channel_resources,
+            )
+        });
         let (attachment_view, resolve_target) = if let Some(msaa) = self.multisample_target.as_ref()
         {
             (&msaa.view, Some(view))
@@ -878,17 +1249,121 @@ impl GpuState {
         });
         render_pass.set_pipeline(&pipeline.pipeline);
         render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-        render_pass.set_bind_group(1, &pipeline.channel_bind_group, &[]);
+        render_pass.set_bind_group(
+            1,
+            buffer_bind_group
+                .as_ref()
+                .unwrap_or(&pipeline.channel_bind_group),
+            &[],
+        );
 
         if !is_prewarming {
             render_pass.draw(0..3, 0..1);
         }
     }
 
+    /// Surface size, fill scale and fill method the image pass shades with.
+    ///
+    /// When upscaling, the scaled target already carries `render_scale`, so the
+    /// fill maps 1:1 onto it and centred content shrinks along with the target.
+    fn shading_geometry(&self) -> (PhysicalSize<u32>, f32, FillMethod) {
+        let Some(upscaler) = self.upscaler.as_ref() else {
+            return (self.context.size, self.render_scale, self.fill_method);
+        };
+        let fill_method = match self.fill_method {
+            FillMethod::Center {
+                content_width,
+                content_height,
+            } => FillMethod::Center {
+                content_width: scale_extent(content_width, self.render_scale),
+                content_height: scale_extent(content_height, self.render_scale),
+            },
+            other => other,
+        };
+        (upscaler.size(), 1.0, fill_method)
+    }
+
+    fn encode_buffers(&self, encoder: &mut wgpu::CommandEncoder, chain: &BufferChain) {
+        let (surface, fill_scale, fill_method) = self.shading_geometry();
+        let logical = logical_dimensions(fill_scale, fill_method, surface);
+        let size = (logical.0.round() as u32, logical.1.round() as u32);
+        chain.prepare(&self.context.device, size);
+        let (width, height) = (size.0.max(1) as f32, size.1.max(1) as f32);
+
+        for index in 0..chain.len() {
+            // Buffers always cover their whole target; fill remapping only applies to the image pass.
+            let mut uniforms = self.uniforms;
+            for (channel, resolution) in chain.pass_channel_resolutions(index).iter().enumerate() {
+                uniforms.set_channel_resolution(channel, *resolution);
+            }
+            let times =
+                chain.update_pass_channels(&self.context.queue, index, self.uniforms.i_time);
+            apply_channel_times(&mut uniforms, times);
+            uniforms.set_resolution(width, height);
+            uniforms.set_surface(width, height, width, height);
+            uniforms.set_fill(1.0, 1.0, 0.0, 0.0);
+            uniforms.set_fill_wrap(0.0, 0.0);
+            uniforms.set_fade(1.0);
+            self.stage_uniforms(encoder, &uniforms);
+            chain.encode_pass(
+                &self.context.device,
+                encoder,
+                index,
+                &self.uniform_bind_group,
+            );
+        }
+
+        if chain.has_cubemap_pass() {
+            let face_size = chain.cubemap_face_size() as f32;
+            let mut uniforms = self.uniforms;
+            for (channel, resolution) in chain.cubemap_channel_resolutions().iter().enumerate() {
+                uniforms.set_channel_resolution(channel, *resolution);
+            }
+            let times = chain.update_cubemap_channels(&self.context.queue, self.uniforms.i_time);
+            apply_channel_times(&mut uniforms, times);
+            uniforms.set_resolution(face_size, face_size);
+            uniforms.set_surface(face_size, face_size, face_size, face_size);
+            uniforms.set_fill(1.0, 1.0, 0.0, 0.0);
+            uniforms.set_fill_wrap(0.0, 0.0);
+            uniforms.set_fade(1.0);
+            chain.encode_cubemap(
+                &self.context.device,
+                encoder,
+                &self.uniform_bind_group,
+                |encoder, face| {
+                    uniforms.set_cube_face(face);
+                    self.stage_uniforms(encoder, &uniforms);
+                },
+            );
+        }
+    }
+
+    fn stage_uniforms(&self, encoder: &mut wgpu::CommandEncoder, uniforms: &ShadertoyUniforms) {
+        let staging = self
+            .context
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("uniform staging"),
+                contents: bytemuck::bytes_of(uniforms),
+                usage: wgpu::BufferUsages::COPY_SRC,
+            });
+        encoder.copy_buffer_to_buffer(
+            &staging,
+            0,
+            &self.uniform_buffer,
+            0,
+            std::mem::size_of::<ShadertoyUniforms>() as u64,
+        );
+    }
+
     fn promote_pending(&mut self, pending: PendingPipeline, now: Instant) {
         let handle = pending
             .handle
             .expect("pending pipeline promoted without a resolved handle");
+        // Cached pipelines may carry feedback from an earlier run; start them from scratch.
+        if let Some(chain) = handle.buffers.as_ref() {
+            chain.reset();
+        }
 
         if pending.crossfade <= Duration::from_millis(16) {
             self.current = handle;
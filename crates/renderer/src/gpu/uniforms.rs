@@ -23,7 +23,7 @@ pub(crate) struct ShadertoyUniforms {
     pub i_time: f32,
     pub i_time_delta: f32,
     pub i_frame: i32,
-    pub i_padding0: f32,
+    pub i_cube_face: f32,
     pub i_mouse: [f32; 4],
     pub i_date: [f32; 4],
     pub i_sample_rate: f32,
@@ -39,6 +39,9 @@ pub(crate) struct ShadertoyUniforms {
 unsafe impl Zeroable for ShadertoyUniforms {}
 unsafe impl Pod for ShadertoyUniforms {}
 
+/// `iSampleRate` reported when no live audio stream defines one (ShaderToy's default).
+pub(crate) const DEFAULT_SAMPLE_RATE: f32 = 44100.0;
+
 impl ShadertoyUniforms {
     pub fn new(width: u32, height: u32) -> Self {
         let mut uniforms = Self {
@@ -46,10 +49,10 @@ impl ShadertoyUniforms {
             i_time: 0.0,
             i_time_delta: 0.0,
             i_frame: 0,
-            i_padding0: 0.0,
+            i_cube_face: 0.0,
             i_mouse: [0.0; 4],
             i_date: [0.0; 4],
-            i_sample_rate: 44100.0,
+            i_sample_rate: DEFAULT_SAMPLE_RATE,
             i_fade: 1.0,
             i_padding1: Std140Vec2 { value: [0.0, 0.0] },
             i_channel_time: [[0.0; 4]; CHANNEL_COUNT],
@@ -96,12 +99,28 @@ impl ShadertoyUniforms {
         self.i_fade = fade;
     }
 
+    /// Selects the face a cubemap pass renders next (0..6, Vulkan face order).
+    pub fn set_cube_face(&mut self, face: u32) {
+        self.i_cube_face = face as f32;
+    }
+
     pub fn set_channel_resolution(&mut self, index: usize, resolution: [f32; 4]) {
         if let Some(slot) = self.i_channel_resolution.get_mut(index) {
             *slot = resolution;
         }
     }
 
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.i_sample_rate = sample_rate;
+    }
+
+    /// Overrides `iChannelTime` for one channel (audio inputs report their playback position).
+    pub fn set_channel_time(&mut self, index: usize, seconds: f32) {
+        if let Some(slot) = self.i_channel_time.get_mut(index) {
+            slot[0] = seconds;
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn update_time(
         &mut self,
@@ -256,3 +275,19 @@ pub(crate) fn fill_parameters(
 
     (scale_x, scale_y, offset_x, offset_y, wrap_x, wrap_y)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_match_glsl_uniform_block() {
+        // std140 offsets of `ShaderParams` in `compile.rs`, where `_iTimeMirror`
+        // occupies the fourth float of `_iResolution`.
+        assert_eq!(std::mem::offset_of!(ShadertoyUniforms, i_time), 16);
+        assert_eq!(std::mem::offset_of!(ShadertoyUniforms, i_frame), 24);
+        assert_eq!(std::mem::offset_of!(ShadertoyUniforms, i_cube_face), 28);
+        assert_eq!(std::mem::offset_of!(ShadertoyUniforms, i_mouse), 32);
+        assert_eq!(std::mem::offset_of!(ShadertoyUniforms, i_sample_rate), 64);
+    }
+}
@@ -0,0 +1,231 @@
+//! Offscreen upscale pass for `render_scale` below 1.0.
+//!
+//! The image pass renders into a target `render_scale` times the output size,
+//! so fragment cost drops with the square of the scale. A full-screen pass
+//! then stretches that target over the frame with the selected filter:
+//! nearest, bilinear, or bilinear followed by contrast-adaptive sharpening in
+//! the spirit of FSR's RCAS pass.
+//!
+//! ```text
+//! image pass ──▶ scaled target (W·s × H·s) ──upscale──▶ frame (W × H)
+//! ```
+
+use winit::dpi::PhysicalSize;
+
+use super::mipmap::BLIT_SHADER;
+use crate::types::UpscaleFilter;
+
+/// Sharpening entry point appended to [`BLIT_SHADER`], whose bindings it samples.
+const SHARPEN_SHADER: &str = r#"
+// 0.0 is the gentlest sharpening, 1.0 the strongest.
+const SHARPNESS: f32 = 0.5;
+
+@fragment
+fn fs_sharpen(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    let centre = textureSample(source, source_sampler, in.uv);
+    let north = textureSample(source, source_sampler, in.uv - vec2<f32>(0.0, texel.y)).rgb;
+    let south = textureSample(source, source_sampler, in.uv + vec2<f32>(0.0, texel.y)).rgb;
+    let west = textureSample(source, source_sampler, in.uv - vec2<f32>(texel.x, 0.0)).rgb;
+    let east = textureSample(source, source_sampler, in.uv + vec2<f32>(texel.x, 0.0)).rgb;
+
+    // Sharpen less where the neighbourhood is already close to black or white,
+    // which keeps edges from ringing. HDR values above 1.0 pass through untouched.
+    let lo = min(centre.rgb, min(min(north, south), min(west, east)));
+    let hi = max(centre.rgb, max(max(north, south), max(west, east)));
+    let amount = sqrt(clamp(min(lo, 1.0 - hi) / max(hi, vec3<f32>(1e-5)), vec3<f32>(0.0), vec3<f32>(1.0)));
+    let weight = amount * (-1.0 / mix(8.0, 5.0, SHARPNESS));
+    let color = (centre.rgb + (north + south + west + east) * weight) / (1.0 + 4.0 * weight);
+    return vec4<f32>(color, centre.a);
+}
+"#;
+
+/// Scales one extent by `scale`, never collapsing below a single pixel.
+pub(crate) fn scale_extent(extent: u32, scale: f32) -> u32 {
+    ((extent as f32 * scale).round() as u32).max(1)
+}
+
+/// Scaled render target plus the pass that stretches it over the frame.
+pub(crate) struct Upscaler {
+    size: PhysicalSize<u32>,
+    format: wgpu::TextureFormat,
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Upscaler {
+    /// Builds the scaled target for an `output` sized frame in `format`.
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        output: PhysicalSize<u32>,
+        scale: f32,
+        filter: UpscaleFilter,
+    ) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("upscale shader"),
+            source: wgpu::ShaderSource::Wgsl([BLIT_SHADER, SHARPEN_SHADER].concat().into()),
+        });
+        let fragment_entry = match filter {
+            UpscaleFilter::Nearest | UpscaleFilter::Bilinear => "fs_main",
+            UpscaleFilter::Sharpen => "fs_sharpen",
+        };
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("upscale pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some(fragment_entry),
+                targets: &[Some(format.into())],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            multiview: None,
+            cache: None,
+        });
+        let filter_mode = match filter {
+            UpscaleFilter::Nearest => wgpu::FilterMode::Nearest,
+            UpscaleFilter::Bilinear | UpscaleFilter::Sharpen => wgpu::FilterMode::Linear,
+        };
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("upscale sampler"),
+            mag_filter: filter_mode,
+            min_filter: filter_mode,
+            ..Default::default()
+        });
+        let size = Self::scaled_size(output, scale);
+        let (texture, view, bind_group) =
+            Self::create_target(device, &pipeline, &sampler, format, size);
+        Self {
+            size,
+            format,
+            _texture: texture,
+            view,
+            sampler,
+            pipeline,
+            bind_group,
+        }
+    }
+
+    /// Size of the scaled target for an `output` sized frame.
+    pub(crate) fn scaled_size(output: PhysicalSize<u32>, scale: f32) -> PhysicalSize<u32> {
+        PhysicalSize::new(
+            scale_extent(output.width, scale),
+            scale_extent(output.height, scale),
+        )
+    }
+
+    /// Size of the scaled target the image pass renders into.
+    pub(crate) fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    /// View of the scaled target the image pass renders into.
+    pub(crate) fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Recreates the scaled target after the output or the scale changed.
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, output: PhysicalSize<u32>, scale: f32) {
+        let size = Self::scaled_size(output, scale);
+        if size == self.size {
+            return;
+        }
+        let (texture, view, bind_group) =
+            Self::create_target(device, &self.pipeline, &self.sampler, self.format, size);
+        self.size = size;
+        self._texture = texture;
+        self.view = view;
+        self.bind_group = bind_group;
+    }
+
+    /// Encodes the pass that stretches the scaled target over `target`.
+    pub(crate) fn encode(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("upscale pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    fn create_target(
+        device: &wgpu::Device,
+        pipeline: &wgpu::RenderPipeline,
+        sampler: &wgpu::Sampler,
+        format: wgpu::TextureFormat,
+        size: PhysicalSize<u32>,
+    ) -> (wgpu::Texture, wgpu::TextureView, wgpu::BindGroup) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("upscale source target"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("upscale bind group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+        (texture, view, bind_group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_size_rounds_and_never_collapses() {
+        let output = PhysicalSize::new(5120, 2880);
+        assert_eq!(
+            Upscaler::scaled_size(output, 0.5),
+            PhysicalSize::new(2560, 1440)
+        );
+        assert_eq!(
+            Upscaler::scaled_size(PhysicalSize::new(1, 3), 0.25),
+            PhysicalSize::new(1, 1)
+        );
+    }
+}
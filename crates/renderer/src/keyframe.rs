@@ -0,0 +1,414 @@
+//! Automatic keyframe selection for `--still-time auto` and `still_at = "auto"`.
+//!
+//! Many shaders are black or mid-fade at t=0, so a still rendered there says
+//! little about the shader. The search renders evenly spaced candidate frames
+//! headlessly at a small preview size and scores each one:
+//!
+//! ```text
+//! score = 2 · luminance std-dev  +  colourfulness  +  edge density
+//!          (contrast, 0..0.5)       (Hasler–Süsstrunk)  (share of pixels on an edge)
+//! ```
+//!
+//! Candidates see `iFrame` as if played at 60 fps. Shaders with buffer or cube
+//! passes are stepped through every frame of the window instead, so their
+//! feedback builds up as it would on screen; only the frames landing on
+//! candidate times are read back and scored.
+//!
+//! The best-scoring timestamp wins, the earliest one on ties. Results are kept
+//! in the shader cache keyed by the pack sources, channel inputs and search
+//! parameters, so a shader is searched once until it or its inputs change.
+
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::gpu::GpuState;
+use crate::runtime::{FixedTimeSource, RenderPolicy, TimeSample, TimeSource};
+use crate::shader_cache::{key_hasher, ShaderCache};
+use crate::types::{Antialiasing, ChannelBindings, ChannelSource, RendererConfig};
+
+/// Luminance step between neighbouring pixels that counts as an edge.
+const EDGE_THRESHOLD: f32 = 0.1;
+
+/// Frame rate the search assumes for `iFrame` and for stepping feedback passes.
+const SEARCH_FRAME_RATE: f32 = 60.0;
+
+/// Time window and resolution searched by [`select_still_time`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyframeSearch {
+    /// Latest timestamp considered (seconds); candidates are spread evenly over `(0, window]`.
+    pub window: f32,
+    /// Number of candidate frames rendered.
+    pub candidates: u32,
+    /// Longest edge of the candidate renders in pixels.
+    pub preview_extent: u32,
+}
+
+impl Default for KeyframeSearch {
+    fn default() -> Self {
+        Self {
+            window: 20.0,
+            candidates: 20,
+            preview_extent: 256,
+        }
+    }
+}
+
+impl KeyframeSearch {
+    /// Candidate timestamps in ascending order; t=0 is never a candidate.
+    fn times(&self) -> impl Iterator<Item = f32> + '_ {
+        let count = self.candidates.max(1);
+        (1..=count).map(move |index| self.window * index as f32 / count as f32)
+    }
+
+    /// Frames to render, each flagged when it is a candidate to score.
+    ///
+    /// With `stepped` every frame from t=0 is rendered so feedback passes
+    /// advance one step per frame; otherwise only the candidates are.
+    fn frames(&self, stepped: bool) -> Vec<(TimeSample, bool)> {
+        let frame_of = |time: f32| (time * SEARCH_FRAME_RATE).round() as u64;
+        if !stepped {
+            return self
+                .times()
+                .map(|time| (TimeSample::new(time, frame_of(time)), true))
+                .collect();
+        }
+        let candidates: Vec<u64> = self.times().map(frame_of).collect();
+        let last = candidates.last().copied().unwrap_or_default();
+        let mut source = FixedTimeSource::stepped(0.0, 1.0 / SEARCH_FRAME_RATE);
+        (0..=last)
+            .map(|frame| (source.sample(), candidates.contains(&frame)))
+            .collect()
+    }
+
+    /// Preview size with the aspect ratio of `surface`.
+    fn preview_size(&self, surface: (u32, u32)) -> (u32, u32) {
+        let (width, height) = (surface.0.max(1) as f32, surface.1.max(1) as f32);
+        let extent = self.preview_extent.max(1) as f32;
+        let scale = extent / width.max(height);
+        (
+            ((width * scale).round() as u32).max(1),
+            ((height * scale).round() as u32).max(1),
+        )
+    }
+}
+
+/// How interesting one candidate frame looks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FrameScore {
+    pub(crate) luminance_spread: f32,
+    pub(crate) colourfulness: f32,
+    pub(crate) edge_density: f32,
+}
+
+impl FrameScore {
+    /// Scores tightly packed RGBA8 `pixels` of a `width` × `height` frame.
+    pub(crate) fn of_rgba8(width: u32, height: u32, pixels: &[u8]) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let count = (width * height).max(1) as f32;
+        let mut luma = Vec::with_capacity(width * height);
+        let (mut luma_sum, mut luma_sq) = (0.0f32, 0.0f32);
+        let (mut rg_sum, mut rg_sq, mut yb_sum, mut yb_sq) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for pixel in pixels.chunks_exact(4).take(width * height) {
+            let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(|c| c as f32 / 255.0);
+            let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let rg = r - g;
+            let yb = 0.5 * (r + g) - b;
+            luma.push(y);
+            luma_sum += y;
+            luma_sq += y * y;
+            rg_sum += rg;
+            rg_sq += rg * rg;
+            yb_sum += yb;
+            yb_sq += yb * yb;
+        }
+        let spread = |sum: f32, sq: f32| (sq / count - (sum / count).powi(2)).max(0.0).sqrt();
+        let (rg_mean, yb_mean) = (rg_sum / count, yb_sum / count);
+        let colourfulness =
+            spread(rg_sum, rg_sq).hypot(spread(yb_sum, yb_sq)) + 0.3 * rg_mean.hypot(yb_mean);
+
+        let mut edges = 0usize;
+        for y in 0..height.saturating_sub(1) {
+            for x in 0..width.saturating_sub(1) {
+                let here = luma[y * width + x];
+                let step = (luma[y * width + x + 1] - here).abs()
+                    + (luma[(y + 1) * width + x] - here).abs();
+                if step > EDGE_THRESHOLD {
+                    edges += 1;
+                }
+            }
+        }
+        Self {
+            luminance_spread: spread(luma_sum, luma_sq),
+            colourfulness,
+            edge_density: edges as f32 / count,
+        }
+    }
+
+    pub(crate) fn total(&self) -> f32 {
+        2.0 * self.luminance_spread + self.colourfulness + self.edge_density
+    }
+}
+
+/// Picks the most interesting timestamp (seconds) of `config.shader_source`
+/// for a still, rendering `search.candidates` frames headlessly.
+///
+/// The choice is stored in `config.shader_cache_dir` when set and reused by
+/// later calls for the same sources and search.
+pub fn select_still_time(config: &RendererConfig, search: &KeyframeSearch) -> Result<f32> {
+    let preview_size = search.preview_size(config.surface_size);
+    let key = search_key(config, search, preview_size);
+    let cache = config.shader_cache_dir.as_deref().and_then(|dir| {
+        ShaderCache::open(dir)
+            .map_err(|err| tracing::warn!(error = %err, "keyframe cache disabled"))
+            .ok()
+    });
+    if let Some(time) = cache.as_ref().and_then(|cache| cache.load_still_time(key)) {
+        tracing::debug!(time, "using cached still keyframe");
+        return Ok(time);
+    }
+
+    let mut preview = config.clone();
+    preview.surface_size = preview_size;
+    preview.policy = RenderPolicy::Still { time: None };
+    preview.antialiasing = Antialiasing::Off;
+    preview.render_scale = 1.0;
+    preview.dynamic_scale = None;
+    let mut state =
+        GpuState::new_headless(&preview).context("failed to prepare keyframe search")?;
+
+    let bindings = &config.channel_bindings;
+    let stepped = !bindings.buffer_passes().is_empty() || bindings.cubemap_pass().is_some();
+    let mut best: Option<(f32, f32)> = None;
+    for (sample, candidate) in search.frames(stepped) {
+        let time = sample.seconds;
+        if !candidate {
+            state
+                .render([0.0; 4], Some(sample))
+                .with_context(|| format!("failed to step keyframe search at {time}s"))?;
+            continue;
+        }
+        let (width, height, pixels) = state
+            .render_rgba8([0.0; 4], Some(sample))
+            .with_context(|| format!("failed to render keyframe candidate at {time}s"))?;
+        let score = FrameScore::of_rgba8(width, height, &pixels);
+        tracing::debug!(
+            time,
+            ?score,
+            total = score.total(),
+            "scored keyframe candidate"
+        );
+        if best.is_none_or(|(_, best_score)| score.total() > best_score) {
+            best = Some((time, score.total()));
+        }
+    }
+    let (time, score) = best.unwrap_or((0.0, 0.0));
+    tracing::info!(
+        shader = %config.shader_source.display(),
+        time,
+        score = %format_args!("{score:.3}"),
+        "selected still keyframe"
+    );
+    if let Some(cache) = cache.as_ref() {
+        cache.store_still_time(key, time);
+    }
+    Ok(time)
+}
+
+/// Hashes the search parameters, the preview size, the pack sources next to the
+/// entry shader and every pass source and channel input, so editing the shader,
+/// swapping an input or changing the search invalidates a stored keyframe.
+///
+/// Sources are hashed by content; media inputs by size and modification time.
+fn search_key(config: &RendererConfig, search: &KeyframeSearch, preview_size: (u32, u32)) -> u64 {
+    let mut hasher = key_hasher();
+    hash_path(&mut hasher, &config.shader_source);
+    hasher.write_u32(search.window.to_bits());
+    hasher.write_u32(search.candidates);
+    hasher.write_u32(preview_size.0);
+    hasher.write_u32(preview_size.1);
+
+    let mut sources: Vec<_> = config
+        .shader_source
+        .parent()
+        .map(list_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext, "glsl" | "wgsl" | "frag" | "toml"))
+        })
+        .collect();
+    let mut inputs = Vec::new();
+    collect_inputs(&config.channel_bindings, &mut sources, &mut inputs);
+    sources.sort();
+    sources.dedup();
+    for path in sources {
+        hash_path(&mut hasher, &path);
+        hasher.write(&std::fs::read(&path).unwrap_or_default());
+    }
+    inputs.sort();
+    inputs.dedup();
+    for path in inputs {
+        let files = if path.is_dir() {
+            list_dir(&path)
+        } else {
+            vec![path]
+        };
+        for file in files {
+            hash_path(&mut hasher, &file);
+            let metadata = std::fs::metadata(&file).ok();
+            hasher.write_u64(metadata.as_ref().map_or(0, |meta| meta.len()));
+            let modified = metadata
+                .and_then(|meta| meta.modified().ok())
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok());
+            hasher.write_u128(modified.map_or(0, |since| since.as_nanos()));
+        }
+    }
+    hasher.finish()
+}
+
+/// Adds the pass sources and channel input paths of `bindings` and its passes.
+fn collect_inputs(
+    bindings: &ChannelBindings,
+    sources: &mut Vec<PathBuf>,
+    inputs: &mut Vec<PathBuf>,
+) {
+    for source in bindings.slots().iter().flatten() {
+        match source {
+            ChannelSource::Texture { path }
+            | ChannelSource::Volume { path }
+            | ChannelSource::Animation { path, .. }
+            | ChannelSource::Audio { path } => inputs.push(path.clone()),
+            ChannelSource::Cubemap { directory } => inputs.push(directory.clone()),
+            _ => {}
+        }
+    }
+    for pass in bindings
+        .buffer_passes()
+        .iter()
+        .chain(bindings.cubemap_pass())
+        .chain(bindings.sound_pass())
+    {
+        sources.push(pass.source.clone());
+        collect_inputs(&pass.channels, sources, inputs);
+    }
+}
+
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn hash_path(hasher: &mut impl Hasher, path: &Path) {
+    hasher.write(path.as_os_str().as_encoded_bytes());
+    hasher.write_u8(0xff);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 3]) -> Vec<u8> {
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let [r, g, b] = pixel(x, y);
+                [r, g, b, 255]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flat_frames_score_below_detailed_ones() {
+        let black = FrameScore::of_rgba8(8, 8, &frame(8, 8, |_, _| [0, 0, 0]));
+        assert_eq!(black.total(), 0.0);
+
+        let grey = FrameScore::of_rgba8(8, 8, &frame(8, 8, |_, _| [128, 128, 128]));
+        let checker = FrameScore::of_rgba8(
+            8,
+            8,
+            &frame(
+                8,
+                8,
+                |x, y| if (x + y) % 2 == 0 { [0; 3] } else { [255; 3] },
+            ),
+        );
+        let colour = FrameScore::of_rgba8(
+            8,
+            8,
+            &frame(8, 8, |x, _| if x < 4 { [255, 0, 0] } else { [0, 0, 255] }),
+        );
+        assert!(checker.edge_density > 0.7 && checker.luminance_spread > 0.45);
+        assert!(colour.colourfulness > grey.colourfulness);
+        assert!(grey.total() < checker.total() && grey.total() < colour.total());
+    }
+
+    #[test]
+    fn search_skips_zero_and_keeps_the_aspect_ratio() {
+        let search = KeyframeSearch {
+            window: 10.0,
+            candidates: 4,
+            preview_extent: 256,
+        };
+        assert_eq!(search.times().collect::<Vec<_>>(), [2.5, 5.0, 7.5, 10.0]);
+        assert_eq!(search.preview_size((1920, 1080)), (256, 144));
+        assert_eq!(search.preview_size((1080, 1920)), (144, 256));
+    }
+
+    #[test]
+    fn feedback_shaders_step_every_frame_up_to_the_last_candidate() {
+        let search = KeyframeSearch {
+            window: 0.5,
+            candidates: 2,
+            preview_extent: 64,
+        };
+        let direct = search.frames(false);
+        assert_eq!(direct.len(), 2);
+        assert_eq!(direct[1].0.frame_index, 30);
+        assert!(direct.iter().all(|(_, candidate)| *candidate));
+
+        let stepped = search.frames(true);
+        assert_eq!(stepped.len(), 31);
+        let scored: Vec<_> = stepped
+            .iter()
+            .filter(|(_, candidate)| *candidate)
+            .map(|(sample, _)| sample.frame_index)
+            .collect();
+        assert_eq!(scored, [15, 30]);
+        assert!((stepped[15].0.seconds - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn search_key_follows_channel_inputs() {
+        let temp = tempfile::tempdir().unwrap();
+        let image = temp.path().join("image.glsl");
+        let texture = temp.path().join("textures").join("noise.png");
+        std::fs::create_dir_all(texture.parent().unwrap()).unwrap();
+        std::fs::write(&image, "void mainImage(out vec4 c, in vec2 p) {}\n").unwrap();
+        std::fs::write(&texture, b"one").unwrap();
+
+        let mut config = RendererConfig {
+            shader_source: image,
+            ..RendererConfig::default()
+        };
+        config
+            .channel_bindings
+            .set_texture(0, texture.clone())
+            .unwrap();
+        let search = KeyframeSearch::default();
+        let before = search_key(&config, &search, (256, 144));
+        assert_eq!(before, search_key(&config, &search, (256, 144)));
+
+        std::fs::write(&texture, b"longer").unwrap();
+        assert_ne!(before, search_key(&config, &search, (256, 144)));
+    }
+}
@@ -25,32 +25,59 @@
 //!
 //! Roles
 //!
-//! - `Renderer` — thin coordinator that selects preview vs wallpaper and delegates run.
+//! - `Renderer` — thin coordinator that selects preview vs wallpaper and delegates run;
+//!   one-shot exports without a visible window render on a headless `GpuState` instead.
 //! - `window`   — preview event loop, input handling, still/export capture.
 //! - `wallpaper`— Wayland layer surfaces per output, frame pacing, multi-surface swaps.
 //! - `gpu`      — wgpu orchestration: device/surface, pipelines, uniforms, channels.
 //! - `compile`  — wraps ShaderToy fragment code and compiles GLSL.
+//! - `preprocess`— reads pass sources and expands GLSL `#include`s with `#line` markers.
+//! - `diagnostic`— maps shaderc/naga errors back to pack files with source excerpts.
+//! - `export`   — headless still, numbered sequence and animated GIF/APNG loop exports.
+//! - `keyframe` — scores headless candidate frames to pick `--still-time auto` timestamps.
+//! - `shader_cache`— persists compiled SPIR-V and pipeline cache data between runs.
+//! - `check`    — compiles every pass of a shader headlessly for `wax11 check`.
+//! - `reflect`  — naga reflection of which time/input uniforms and channels a pass reads.
+//! - `audio`    — decodes audio inputs into ShaderToy's FFT/waveform texture layout.
+//! - `animation`— decodes GIF/APNG/image-sequence channels and picks frames by time.
+//! - `volume`   — decodes ShaderToy `.bin` volumes for 3D texture channels.
 //! - `runtime`  — render policy, time sources, fill method, frame scheduling helpers.
 //!
 //! The fragment shaders are wrapped at runtime (uniform block + macros) to align with
 //! ShaderToy semantics (`iTime`, `iMouse`, `iChannel*`), then compiled to a
 //! `wgpu::ShaderModule` (via naga or shaderc) before drawing.
 
+mod animation;
+mod audio;
+mod check;
 mod compile;
+mod diagnostic;
+mod export;
 mod gpu;
+mod keyframe;
+mod preprocess;
+mod reflect;
 mod runtime;
+mod shader_cache;
 mod types;
+mod volume;
 mod wallpaper;
 mod window;
 
+pub use check::{check_shader, PassCheck};
+pub use diagnostic::{ShaderCompileError, ShaderDiagnostic, ShaderFallbackError, SourceLocation};
+pub use keyframe::{select_still_time, KeyframeSearch};
 pub use runtime::{
-    time_source_for_policy, BoxedTimeSource, ExportFormat, FillMethod, FixedTimeSource,
-    RenderPolicy, RuntimeOptions, SystemTimeSource, TimeSample, TimeSource,
+    time_source_for_policy, BoxedTimeSource, ExportFormat, ExportSequence, FillMethod,
+    FixedTimeSource, ProgressiveRefine, RefinePass, RenderPolicy, RuntimeOptions, SystemTimeSource,
+    TimeSample, TimeSource,
 };
 pub use types::{
-    AdapterProfile, Antialiasing, ChannelBindings, ChannelSource, ChannelTextureKind,
-    ColorSpaceMode, CrossfadeCurve, GpuMemoryMode, GpuPowerPreference, RenderMode, RendererConfig,
-    ShaderCompiler, SurfaceAlpha, VsyncMode, CUBEMAP_FACE_STEMS,
+    AdapterProfile, AnimationLoop, Antialiasing, BufferPass, ChannelBindings, ChannelFilter,
+    ChannelPlayback, ChannelSampler, ChannelSource, ChannelTextureKind, ChannelWrap,
+    ColorSpaceMode, CrossfadeCurve, DynamicScale, GpuMemoryMode, GpuPowerPreference, LiveAudio,
+    LiveAudioInput, PcmFormat, RenderMode, RendererConfig, ShaderCheckConfig, ShaderCompiler,
+    ShaderLanguage, SoundExportConfig, SurfaceAlpha, UpscaleFilter, VsyncMode, CUBEMAP_FACE_STEMS,
 };
 pub use wallpaper::{
     OutputId, SurfaceId, SurfaceInfo, SurfaceSelector, SwapRequest, WallpaperRuntime,
@@ -83,18 +110,18 @@ impl Renderer {
     }
 
     /// Launches the renderer in either wallpaper or windowed mode.
+    ///
+    /// One-shot exports without a visible window, and every frame sequence,
+    /// skip both and render headlessly.
     pub fn run(&mut self) -> Result<()> {
+        if let RenderPolicy::Export { sequence, .. } = &self.config.policy {
+            if sequence.is_some() || (self.config.exit_on_export && !self.config.show_window) {
+                return self.run_export_once();
+            }
+        }
         match self.config.mode {
             RenderMode::Wallpaper => self.run_wallpaper(),
-            RenderMode::Windowed => {
-                if matches!(self.config.policy, RenderPolicy::Export { .. })
-                    && self.config.exit_on_export
-                {
-                    self.run_export_once()
-                } else {
-                    self.run_window_preview()
-                }
-            }
+            RenderMode::Windowed => self.run_window_preview(),
         }
     }
 
@@ -136,12 +163,10 @@ impl Renderer {
                         }
                         WindowEvent::MouseInput {
                             state: button_state,
-                            button,
+                            button: MouseButton::Left,
                             ..
                         } => {
-                            if button == MouseButton::Left {
-                                state.handle_mouse_button(button_state);
-                            }
+                            state.handle_mouse_button(button_state);
                         }
                         WindowEvent::Resized(new_size) => {
                             let target_size = if !self.config.show_window
@@ -164,7 +189,7 @@ impl Renderer {
                         }
                         WindowEvent::RedrawRequested => {
                             let sample = policy_driver.sample();
-                            let render_result = state.render_frame(sample);
+                            let render_result = state.render_frame(sample, policy_driver.refine_mut());
                             match render_result {
                                 Ok(RenderFrameStatus::Presented) => {
                                     policy_driver.mark_rendered();
@@ -225,29 +250,16 @@ impl Renderer {
             .map_err(|err| anyhow!("event loop error: {err}"))
     }
 
+    /// Renders the export on a surfaceless device, so no Wayland or X11
+    /// connection is needed.
     fn run_export_once(&self) -> Result<()> {
-        let event_loop = EventLoop::new().context("failed to initialize event loop")?;
-        let window_size = PhysicalSize::new(self.config.surface_size.0, self.config.surface_size.1);
-        let builder = WindowBuilder::new()
-            .with_title("wax11 shader Export")
-            .with_inner_size(window_size)
-            .with_visible(self.config.show_window);
-        let window = builder
-            .build(&event_loop)
-            .context("failed to create export window")?;
-        let window = Arc::new(window);
-
-        let mut state = WindowState::new(window.clone(), &self.config)?;
-        let mut policy_driver = RenderPolicyDriver::new(self.config.policy.clone())?;
-        let sample = policy_driver.sample();
-        match state.render_frame(sample)? {
-            RenderFrameStatus::Captured(_) => {
-                policy_driver.mark_rendered();
-                Ok(())
-            }
-            RenderFrameStatus::Presented => Err(anyhow!(
-                "export policy expected a captured frame but received a presentation"
-            )),
-        }
+        export::run(&self.config)
     }
 }
+
+/// Renders a ShaderToy sound pass offline and writes it to a 16-bit stereo WAV file.
+///
+/// Runs on a surfaceless device, so no window or compositor is required.
+pub fn export_sound(config: &SoundExportConfig) -> Result<()> {
+    gpu::export_sound(config)
+}
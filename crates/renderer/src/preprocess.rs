@@ -0,0 +1,221 @@
+//! `#include` expansion for GLSL pass sources.
+//!
+//! Packs share noise/palette helpers through `#include "file.glsl"`. Includes
+//! resolve against the including file's directory, then the pass file's
+//! directory (the pack), then the shared library roots (`<shader root>/lib`)
+//! supplied by the caller. Each included file is wrapped in `#line` markers so
+//! compiler diagnostics keep pointing at the original file and line:
+//!
+//! ```text
+//!   image.glsl                        expanded
+//!   1  #include "noise.glsl"   ──▶    #line 1 1        (noise.glsl, source 1)
+//!   2  void mainImage(…)              …noise.glsl…
+//!                                     #line 2 0        (back in image.glsl)
+//!                                     void mainImage(…)
+//! ```
+//!
+//! The second number of each marker indexes [`PassSource::files`]. A file is
+//! inlined at most once per pass, so diamond includes need no guards; a file
+//! that includes itself (directly or through others) is rejected as a cycle.
+//! WGSL passes are loaded verbatim since WGSL has no preprocessor.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::types::ShaderLanguage;
+
+/// Pass source text ready for `compile::compile_fragment_shader`.
+#[derive(Debug, Clone)]
+pub(crate) struct PassSource {
+    pub code: String,
+    pub language: ShaderLanguage,
+    /// Files referenced by the `#line <line> <index>` markers; index 0 is the pass file.
+    pub files: Vec<PathBuf>,
+}
+
+/// Reads a pass source and, for GLSL, expands its `#include` directives.
+pub(crate) fn load_pass_source(
+    path: &Path,
+    language: ShaderLanguage,
+    include_dirs: &[PathBuf],
+) -> Result<PassSource> {
+    if language == ShaderLanguage::Wgsl {
+        let code = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read shader at {}", path.display()))?;
+        return Ok(PassSource {
+            code,
+            language,
+            files: vec![path.to_path_buf()],
+        });
+    }
+
+    let entry_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut expander = Expander {
+        entry_dir,
+        include_dirs,
+        files: Vec::new(),
+        stack: Vec::new(),
+        seen: HashSet::new(),
+        code: String::new(),
+    };
+    let canonical = canonical(path)?;
+    expander.expand(path, canonical)?;
+    Ok(PassSource {
+        code: expander.code,
+        language,
+        files: expander.files,
+    })
+}
+
+struct Expander<'a> {
+    entry_dir: PathBuf,
+    include_dirs: &'a [PathBuf],
+    files: Vec<PathBuf>,
+    /// Canonical paths of the files currently being expanded, outermost first.
+    stack: Vec<PathBuf>,
+    seen: HashSet<PathBuf>,
+    code: String,
+}
+
+impl Expander<'_> {
+    fn expand(&mut self, path: &Path, canonical_path: PathBuf) -> Result<()> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read shader at {}", path.display()))?;
+        let index = self.files.len();
+        self.files.push(path.to_path_buf());
+        self.stack.push(canonical_path.clone());
+        self.seen.insert(canonical_path);
+
+        for (number, line) in text.lines().enumerate() {
+            let Some(target) = parse_include(line) else {
+                self.code.push_str(line);
+                self.code.push('\n');
+                continue;
+            };
+            let location = || format!("{}:{}", path.display(), number + 1);
+            let target = target.with_context(location)?;
+            let resolved = self.resolve(path, target).with_context(location)?;
+            let resolved_canonical = canonical(&resolved)?;
+
+            if let Some(start) = self.stack.iter().position(|p| *p == resolved_canonical) {
+                let chain: Vec<String> = self.stack[start..]
+                    .iter()
+                    .chain(std::iter::once(&resolved_canonical))
+                    .map(|p| p.display().to_string())
+                    .collect();
+                bail!("include cycle at {}: {}", location(), chain.join(" -> "));
+            }
+            if self.seen.contains(&resolved_canonical) {
+                // Already inlined; keep the line so numbering stays intact.
+                self.code.push('\n');
+                continue;
+            }
+
+            let _ = writeln!(self.code, "#line 1 {}", self.files.len());
+            self.expand(&resolved, resolved_canonical)?;
+            let _ = writeln!(self.code, "#line {} {}", number + 2, index);
+        }
+
+        self.stack.pop();
+        Ok(())
+    }
+
+    fn resolve(&self, including: &Path, target: &str) -> Result<PathBuf> {
+        let local = including.parent().unwrap_or(Path::new(""));
+        let mut searched = Vec::new();
+        for dir in std::iter::once(local)
+            .chain(std::iter::once(self.entry_dir.as_path()))
+            .chain(self.include_dirs.iter().map(PathBuf::as_path))
+        {
+            let candidate = dir.join(target);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+            if !searched.contains(&dir) {
+                searched.push(dir);
+            }
+        }
+        let searched: Vec<String> = searched.iter().map(|d| d.display().to_string()).collect();
+        Err(anyhow!(
+            "cannot find #include \"{target}\" (searched {})",
+            searched.join(", ")
+        ))
+    }
+}
+
+/// Returns the quoted target of an `#include "…"` line, or `None` for other lines.
+fn parse_include(line: &str) -> Option<Result<&str>> {
+    let directive = line.trim_start().strip_prefix('#')?.trim_start();
+    let rest = directive.strip_prefix("include")?;
+    let rest = rest.trim();
+    let target = rest
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .filter(|target| !target.is_empty());
+    Some(target.ok_or_else(|| anyhow!("expected #include \"file\", found `{}`", line.trim())))
+}
+
+fn canonical(path: &Path) -> Result<PathBuf> {
+    path.canonicalize()
+        .with_context(|| format!("failed to resolve {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_local_and_library_includes_with_line_markers() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let pack = root.join("pack");
+        let lib = root.join("lib");
+        std::fs::create_dir_all(&pack).unwrap();
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::write(
+            pack.join("image.glsl"),
+            "#include \"common.glsl\"\n#include \"noise.glsl\"\nvoid mainImage() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            pack.join("common.glsl"),
+            "#include \"noise.glsl\"\nfloat common;\n",
+        )
+        .unwrap();
+        std::fs::write(lib.join("noise.glsl"), "float noise;\n").unwrap();
+
+        let source = load_pass_source(
+            &pack.join("image.glsl"),
+            ShaderLanguage::Glsl,
+            std::slice::from_ref(&lib),
+        )
+        .expect("expand includes");
+
+        assert_eq!(source.files.len(), 3);
+        assert!(source.files[1].ends_with("common.glsl"));
+        assert!(source.files[2].ends_with("lib/noise.glsl"));
+        assert_eq!(
+            source.code,
+            "#line 1 1\n#line 1 2\nfloat noise;\n#line 2 1\nfloat common;\n#line 2 0\n\nvoid mainImage() {}\n"
+        );
+    }
+
+    #[test]
+    fn rejects_include_cycles_and_missing_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("a.glsl"), "#include \"b.glsl\"\n").unwrap();
+        std::fs::write(root.join("b.glsl"), "#include \"a.glsl\"\n").unwrap();
+        std::fs::write(root.join("c.glsl"), "#include \"missing.glsl\"\n").unwrap();
+
+        let cycle = load_pass_source(&root.join("a.glsl"), ShaderLanguage::Glsl, &[]).unwrap_err();
+        let missing =
+            load_pass_source(&root.join("c.glsl"), ShaderLanguage::Glsl, &[]).unwrap_err();
+
+        assert!(format!("{cycle:#}").contains("include cycle"));
+        assert!(format!("{missing:#}").contains("cannot find #include \"missing.glsl\""));
+    }
+}
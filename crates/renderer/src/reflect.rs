@@ -0,0 +1,198 @@
+//! Static analysis of compiled fragment modules.
+//!
+//! Many wallpapers are gradients or patterns that never change, yet the
+//! animate policy redraws them at full frame rate. After compiling a pass we
+//! walk the naga module (the GLSL/WGSL parse, or shaderc's SPIR-V read back
+//! through naga) and record which frame-varying inputs the code touches:
+//!
+//! ```text
+//!   naga::Module
+//!     │  every function + entry point
+//!     ▼
+//!   AccessIndex(ShaderParams [or a Load of it], member) ──▶ time / frame / mouse / date
+//!   GlobalVariable(@group(1) binding)                  ──▶ channels[binding / 2]
+//!     ▼
+//!   ShaderUsage::is_time_independent()
+//! ```
+//!
+//! The image, buffer and cubemap epilogues only read resolution and fill
+//! uniforms, so analysing the whole wrapped module gives the user code's usage.
+
+use std::collections::HashMap;
+
+use wgpu::naga::valid::{Capabilities, ValidationFlags, Validator};
+use wgpu::naga::{self, Expression, GlobalVariable, Handle};
+
+use crate::types::CHANNEL_COUNT;
+
+/// `ShaderParams` member indices; the GLSL and WGSL preludes share this order.
+const MEMBER_TIME_MIRROR: u32 = 1;
+const MEMBER_TIME: u32 = 2;
+const MEMBER_TIME_DELTA: u32 = 3;
+const MEMBER_FRAME: u32 = 4;
+const MEMBER_MOUSE: u32 = 6;
+const MEMBER_DATE: u32 = 7;
+const MEMBER_CHANNEL_TIME: u32 = 11;
+
+/// Frame-varying inputs a compiled pass reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ShaderUsage {
+    /// `iTime`, `iTimeDelta` or `iChannelTime`.
+    pub time: bool,
+    pub frame: bool,
+    pub mouse: bool,
+    pub date: bool,
+    /// Channels the pass samples or queries.
+    pub channels: [bool; CHANNEL_COUNT],
+}
+
+impl ShaderUsage {
+    /// Walks every function of `module`. Code that uses the uniform block as a
+    /// whole (rather than member by member) is assumed to read everything.
+    ///
+    /// Only expressions the validator sees referenced count: naga's GLSL
+    /// frontend materialises every global in every function, used or not.
+    pub(crate) fn from_module(module: &naga::Module) -> Option<Self> {
+        let info = Validator::new(ValidationFlags::empty(), Capabilities::all())
+            .validate(module)
+            .map_err(|err| tracing::debug!(error = %err.into_inner(), "shader reflection failed"))
+            .ok()?;
+        let params = find_global(module, |group, binding| group == 0 && binding == 0);
+        let mut usage = Self::default();
+
+        let functions = module
+            .functions
+            .iter()
+            .map(|(handle, function)| (function, &info[handle]))
+            .chain(
+                module
+                    .entry_points
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| (&entry.function, info.get_entry_point(index))),
+            );
+        for (function, function_info) in functions {
+            let used = |handle: Handle<Expression>| function_info[handle].ref_count > 0;
+            // The params pointer and loads of it, with how many of their
+            // references are member accesses (or further loads).
+            let mut roots = HashMap::new();
+            for (handle, expression) in function.expressions.iter() {
+                match *expression {
+                    Expression::GlobalVariable(var) => {
+                        if Some(var) == params {
+                            roots.insert(handle, 0);
+                        }
+                        if let Some(channel) = channel_of(module, var).filter(|_| used(handle)) {
+                            usage.channels[channel] = true;
+                        }
+                    }
+                    Expression::Load { pointer } if roots.contains_key(&pointer) => {
+                        *roots.get_mut(&pointer).unwrap() += 1;
+                        roots.insert(handle, 0);
+                    }
+                    Expression::AccessIndex { base, index } if roots.contains_key(&base) => {
+                        *roots.get_mut(&base).unwrap() += 1;
+                        if used(handle) {
+                            usage.mark_member(index);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if roots
+                .iter()
+                .any(|(&handle, &members)| function_info[handle].ref_count > members)
+            {
+                usage.mark_everything();
+            }
+        }
+        Some(usage)
+    }
+
+    /// Reflects shaderc output by reading the SPIR-V back through naga.
+    pub(crate) fn from_spirv(words: &[u32]) -> Option<Self> {
+        use naga::front::spv::{Frontend, Options};
+
+        let module = Frontend::new(words.iter().copied(), &Options::default())
+            .parse()
+            .map_err(|err| tracing::debug!(error = %err, "SPIR-V reflection failed"))
+            .ok()?;
+        Self::from_module(&module)
+    }
+
+    /// True when one rendered frame stays valid until the surface or shader changes.
+    pub(crate) fn is_time_independent(&self) -> bool {
+        !(self.time || self.frame || self.mouse || self.date || self.channels.contains(&true))
+    }
+
+    fn mark_member(&mut self, index: u32) {
+        match index {
+            MEMBER_TIME_MIRROR | MEMBER_TIME | MEMBER_TIME_DELTA | MEMBER_CHANNEL_TIME => {
+                self.time = true;
+            }
+            MEMBER_FRAME => self.frame = true,
+            MEMBER_MOUSE => self.mouse = true,
+            MEMBER_DATE => self.date = true,
+            _ => {}
+        }
+    }
+
+    fn mark_everything(&mut self) {
+        self.time = true;
+        self.frame = true;
+        self.mouse = true;
+        self.date = true;
+    }
+}
+
+fn find_global(
+    module: &naga::Module,
+    matches: impl Fn(u32, u32) -> bool,
+) -> Option<Handle<GlobalVariable>> {
+    module.global_variables.iter().find_map(|(handle, var)| {
+        var.binding
+            .as_ref()
+            .filter(|binding| matches(binding.group, binding.binding))
+            .map(|_| handle)
+    })
+}
+
+/// Channel index of a `@group(1)` texture or sampler global.
+fn channel_of(module: &naga::Module, var: Handle<GlobalVariable>) -> Option<usize> {
+    let binding = module.global_variables[var].binding.as_ref()?;
+    let channel = (binding.group == 1).then_some(binding.binding as usize / 2)?;
+    (channel < CHANNEL_COUNT).then_some(channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage_of_wgsl(body: &str) -> ShaderUsage {
+        let source = format!(
+            "struct ShaderParams {{ resolution: vec3<f32>, time_mirror: f32, time: f32, \
+             time_delta: f32, frame: i32, cube_face: f32, mouse: vec4<f32>, date: vec4<f32> }}\n\
+             @group(0) @binding(0) var<uniform> params: ShaderParams;\n\
+             @group(1) @binding(2) var channel1: texture_2d<f32>;\n\
+             @group(1) @binding(3) var channel1_sampler: sampler;\n\
+             @fragment fn main(@builtin(position) p: vec4<f32>) -> @location(0) vec4<f32> {{ {body} }}"
+        );
+        ShaderUsage::from_module(&naga::front::wgsl::parse_str(&source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn detects_time_mouse_and_channel_reads() {
+        let gradient = usage_of_wgsl("return vec4<f32>(p.xy / params.resolution.xy, 0.0, 1.0);");
+        assert!(gradient.is_time_independent());
+
+        let animated = usage_of_wgsl("return vec4<f32>(sin(params.time));");
+        assert!(animated.time && !animated.is_time_independent());
+
+        let interactive = usage_of_wgsl("return params.mouse;");
+        assert!(interactive.mouse && !interactive.time);
+
+        let textured = usage_of_wgsl("return textureSample(channel1, channel1_sampler, p.xy);");
+        assert_eq!(textured.channels, [false, true, false, false]);
+        assert!(!textured.is_time_independent());
+    }
+}
@@ -41,7 +41,7 @@
 //! - `window::RenderPolicyDriver` wraps `FrameScheduler` + `TimeSource` for previews.
 //! - `wallpaper::SurfaceState` maintains its own `TimeSource` and uses simple pacing.
 //!
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
@@ -68,6 +68,15 @@ pub enum RenderPolicy {
         /// Specific timestamp to evaluate the shader at (seconds).
         time: Option<f32>,
     },
+    /// Render a still that refines over several frames: a fast low-scale pass
+    /// is presented first, then higher scales, then jittered full-resolution
+    /// samples are averaged until the final frame, after which rendering stops.
+    Progressive {
+        /// Specific timestamp to evaluate the shader at (seconds).
+        time: Option<f32>,
+        /// Refinement schedule.
+        refine: ProgressiveRefine,
+    },
     /// Render a frame and write the result to disk.
     Export {
         /// Specific timestamp to evaluate the shader at (seconds).
@@ -76,6 +85,10 @@ pub enum RenderPolicy {
         path: PathBuf,
         /// Output format the user requested.
         format: ExportFormat,
+        /// Resolution multiplier over the surface size for headless exports (1.0 = native).
+        scale: f32,
+        /// Render several frames at a fixed timestep instead of one still.
+        sequence: Option<ExportSequence>,
     },
 }
 
@@ -110,12 +123,97 @@ mod tests {
         assert!(!sched.ready_for_frame(now));
     }
 
+    #[test]
+    fn progressive_refines_scales_then_samples() {
+        let mut refine = ProgressiveRefine::new(0.25, 3);
+        let mut passes = Vec::new();
+        while let Some(pass) = refine.current() {
+            passes.push(pass);
+            refine.advance();
+        }
+        assert_eq!(
+            passes,
+            [
+                RefinePass::Scaled(0.25),
+                RefinePass::Scaled(0.5),
+                RefinePass::Sample { index: 0, count: 3 },
+                RefinePass::Sample { index: 1, count: 3 },
+                RefinePass::Sample { index: 2, count: 3 },
+            ]
+        );
+        refine.reset();
+        assert_eq!(refine.current(), Some(RefinePass::Scaled(0.25)));
+        refine.finish();
+        assert!(refine.is_finished());
+    }
+
+    #[test]
+    fn progressive_parks_after_the_final_pass() {
+        let mut sched = FrameScheduler::new(RenderPolicy::Progressive {
+            time: None,
+            refine: ProgressiveRefine::new(0.5, 2),
+        });
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(sched.ready_for_frame(now));
+            sched.refine_mut().unwrap().advance();
+            sched.mark_rendered();
+        }
+        assert!(!sched.ready_for_frame(now));
+        sched.reset();
+        assert!(sched.ready_for_frame(now));
+    }
+
+    #[test]
+    fn stepped_time_source_is_deterministic() {
+        let mut source = FixedTimeSource::stepped(2.0, 0.5);
+        let samples: Vec<_> = (0..3).map(|_| source.sample()).collect();
+        assert_eq!(
+            samples,
+            [
+                TimeSample::new(2.0, 0),
+                TimeSample::new(2.5, 1),
+                TimeSample::new(3.0, 2),
+            ]
+        );
+        source.reset();
+        assert_eq!(source.sample(), TimeSample::new(2.0, 0));
+
+        let mut still = FixedTimeSource::new(1.0);
+        still.sample();
+        assert_eq!(still.sample(), TimeSample::new(1.0, 0));
+    }
+
+    #[test]
+    fn sequence_frames_are_numbered_before_the_extension() {
+        let sequence = ExportSequence {
+            frames: 120,
+            step: 1.0 / 60.0,
+            crossfade: 0,
+        };
+        let path = Path::new("renders/loop.exr");
+        assert_eq!(
+            sequence.frame_path(path, 7),
+            PathBuf::from("renders/loop-0007.exr")
+        );
+        let long = ExportSequence {
+            frames: 100_000,
+            ..sequence
+        };
+        assert_eq!(
+            long.frame_path(path, 42),
+            PathBuf::from("renders/loop-00042.exr")
+        );
+    }
+
     #[test]
     fn export_renders_once() {
         let mut sched = FrameScheduler::new(RenderPolicy::Export {
             time: None,
             path: PathBuf::from("out.png"),
             format: ExportFormat::Png,
+            scale: 1.0,
+            sequence: None,
         });
         let now = Instant::now();
         assert!(sched.ready_for_frame(now));
@@ -124,6 +222,24 @@ mod tests {
     }
 }
 
+impl RenderPolicy {
+    /// Whether the policy ends on a single final frame instead of animating.
+    pub fn is_still(&self) -> bool {
+        !matches!(self, RenderPolicy::Animate { .. })
+    }
+
+    /// Sets the timestamp still, progressive and export policies evaluate; animated
+    /// policies are left unchanged.
+    pub fn set_time(&mut self, seconds: f32) {
+        match self {
+            RenderPolicy::Animate { .. } => {}
+            RenderPolicy::Still { time }
+            | RenderPolicy::Progressive { time, .. }
+            | RenderPolicy::Export { time, .. } => *time = Some(seconds),
+        }
+    }
+}
+
 impl Default for RenderPolicy {
     fn default() -> Self {
         Self::Animate {
@@ -136,14 +252,146 @@ impl Default for RenderPolicy {
 /// File formats supported by the still/export pipeline.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportFormat {
+    /// 8-bit RGB, encoded like the on-screen surface.
     Png,
+    /// Half-float linear-light RGBA from a floating-point target, keeping values above 1.0.
     Exr,
+    /// Looping animated GIF of an export sequence, palette-quantised per frame.
+    Gif,
+    /// Looping animated PNG of an export sequence, in full 8-bit colour.
+    Apng,
 }
 
-/// Spatial mapping from shader coordinates onto the wallpaper surface.
+impl ExportFormat {
+    /// Whether the format holds a whole sequence in one file rather than one frame per file.
+    pub fn is_animated(self) -> bool {
+        matches!(self, ExportFormat::Gif | ExportFormat::Apng)
+    }
+}
+
+/// Fixed-timestep frame sequence for [`RenderPolicy::Export`].
+///
+/// Frame `n` is evaluated at `time + n * step` with `iFrame = n`, so the output
+/// does not depend on how fast frames render.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportSequence {
+    /// Number of frames to write.
+    pub frames: u32,
+    /// Seconds between consecutive frames.
+    pub step: f32,
+    /// For animated formats, how many extra frames past the end are rendered and
+    /// crossfaded over the first ones so the loop has no visible seam.
+    pub crossfade: u32,
+}
+
+impl ExportSequence {
+    /// Numbers `path` for frame `index`: `loop.png` becomes `loop-0007.png`,
+    /// zero-padded to at least four digits.
+    pub fn frame_path(&self, path: &Path, index: u32) -> PathBuf {
+        let width = self.frames.saturating_sub(1).to_string().len().max(4);
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut name = format!("{stem}-{index:0width$}");
+        if let Some(extension) = path.extension() {
+            name.push('.');
+            name.push_str(&extension.to_string_lossy());
+        }
+        path.with_file_name(name)
+    }
+}
+
+/// One refinement pass of a [`RenderPolicy::Progressive`] still.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefinePass {
+    /// Render the whole frame at this fraction of the output resolution.
+    Scaled(f32),
+    /// Full-resolution sample `index` of `count`, jittered within the pixel and
+    /// averaged with the samples before it.
+    Sample { index: u32, count: u32 },
+}
+
+/// Pass schedule for a [`RenderPolicy::Progressive`] still.
+///
+/// Scales double from `start_scale` until full resolution, then `samples`
+/// full-resolution passes are averaged:
+///
+/// ```text
+/// start 0.25, samples 4:  0.25 ─▶ 0.5 ─▶ sample 0 ─▶ 1 ─▶ 2 ─▶ 3 ─▶ done
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressiveRefine {
+    /// Render scale of the first, fastest pass (0.0-1.0].
+    pub start_scale: f32,
+    /// Full-resolution samples averaged at the end; 1 disables supersampling.
+    pub samples: u32,
+    pass: u32,
+}
+
+impl ProgressiveRefine {
+    /// Builds a schedule positioned at its first pass.
+    pub fn new(start_scale: f32, samples: u32) -> Self {
+        Self {
+            start_scale: start_scale.clamp(f32::EPSILON, 1.0),
+            samples: samples.max(1),
+            pass: 0,
+        }
+    }
+
+    /// Number of reduced-scale passes before full resolution.
+    fn scaled_passes(&self) -> u32 {
+        let mut passes = 0;
+        let mut scale = self.start_scale;
+        while scale < 1.0 {
+            passes += 1;
+            scale *= 2.0;
+        }
+        passes
+    }
+
+    /// The pass to render next, or `None` once the final frame was presented.
+    pub fn current(&self) -> Option<RefinePass> {
+        let scaled = self.scaled_passes();
+        if self.pass < scaled {
+            return Some(RefinePass::Scaled(
+                self.start_scale * 2f32.powi(self.pass as i32),
+            ));
+        }
+        let index = self.pass - scaled;
+        (index < self.samples).then_some(RefinePass::Sample {
+            index,
+            count: self.samples,
+        })
+    }
+
+    /// Moves to the next pass after the current one was presented.
+    pub fn advance(&mut self) {
+        if self.current().is_some() {
+            self.pass += 1;
+        }
+    }
+
+    /// Skips the remaining passes.
+    pub fn finish(&mut self) {
+        self.pass = self.scaled_passes() + self.samples;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// Restarts from the first pass, e.g. after a shader swap or resize.
+    pub fn reset(&mut self) {
+        self.pass = 0;
+    }
+}
+
+/// Spatial mapping from shader coordinates onto the wallpaper surface.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum FillMethod {
     /// Stretch shader output to fill the surface.
+    #[default]
     Stretch,
     /// Center the shader at a fixed content resolution, letterboxing otherwise.
     Center {
@@ -160,12 +408,6 @@ pub enum FillMethod {
     },
 }
 
-impl Default for FillMethod {
-    fn default() -> Self {
-        Self::Stretch
-    }
-}
-
 /// Options that fine-tune renderer quality and run-time behaviour.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RuntimeOptions {
@@ -264,16 +506,32 @@ impl TimeSource for SystemTimeSource {
     }
 }
 
-/// Time source that always reports a fixed timestamp.
+/// Time source that reports a fixed timestamp, or advances by a fixed step per
+/// sample for frame-sequence exports.
 #[derive(Debug, Clone, Copy)]
 pub struct FixedTimeSource {
     time: f32,
+    step: Option<f32>,
+    frame: u64,
 }
 
 impl FixedTimeSource {
     /// Constructs a fixed time source that always returns the provided time.
     pub fn new(time: f32) -> Self {
-        Self { time }
+        Self {
+            time,
+            step: None,
+            frame: 0,
+        }
+    }
+
+    /// Constructs a source whose `n`th sample is `start + n * step` at frame `n`.
+    pub fn stepped(start: f32, step: f32) -> Self {
+        Self {
+            time: start,
+            step: Some(step),
+            frame: 0,
+        }
     }
 
     /// Accesses the fixed timestamp without advancing the frame counter.
@@ -283,10 +541,19 @@ impl FixedTimeSource {
 }
 
 impl TimeSource for FixedTimeSource {
-    fn reset(&mut self) {}
+    fn reset(&mut self) {
+        self.frame = 0;
+    }
 
     fn sample(&mut self) -> TimeSample {
-        TimeSample::new(self.time, 0)
+        let Some(step) = self.step else {
+            return TimeSample::new(self.time, 0);
+        };
+        // Multiply rather than accumulate so long sequences do not drift.
+        let seconds = f64::from(self.time) + f64::from(step) * self.frame as f64;
+        let sample = TimeSample::new(seconds as f32, self.frame);
+        self.frame = self.frame.saturating_add(1);
+        sample
     }
 }
 
@@ -297,7 +564,17 @@ pub type BoxedTimeSource = Box<dyn TimeSource + Send>;
 pub fn time_source_for_policy(policy: &RenderPolicy) -> Result<BoxedTimeSource> {
     match policy {
         RenderPolicy::Animate { .. } => Ok(Box::new(SystemTimeSource::new())),
-        RenderPolicy::Still { time } => Ok(Box::new(FixedTimeSource::new(time.unwrap_or(0.0)))),
+        RenderPolicy::Still { time } | RenderPolicy::Progressive { time, .. } => {
+            Ok(Box::new(FixedTimeSource::new(time.unwrap_or(0.0))))
+        }
+        RenderPolicy::Export {
+            time,
+            sequence: Some(sequence),
+            ..
+        } => Ok(Box::new(FixedTimeSource::stepped(
+            time.unwrap_or(0.0),
+            sequence.step,
+        ))),
         RenderPolicy::Export { time, .. } => {
             Ok(Box::new(FixedTimeSource::new(time.unwrap_or(0.0))))
         }
@@ -354,17 +631,25 @@ impl FrameScheduler {
     pub fn reset(&mut self) {
         self.rendered_once = false;
         self.next_frame_due = self.target_interval.map(|_| Instant::now());
+        if let Some(refine) = self.refine_mut() {
+            refine.reset();
+        }
     }
 
-    /// Marks that a frame has been presented; still/export modes will stop scheduling afterwards.
+    /// Marks that a frame has been presented; still/export modes will stop scheduling
+    /// afterwards, progressive stills once their last refinement pass was presented.
     pub fn mark_rendered(&mut self) {
-        if matches!(
-            self.policy,
-            RenderPolicy::Still { .. } | RenderPolicy::Export { .. }
-        ) {
+        if self
+            .refine_mut()
+            .is_some_and(|refine| !refine.is_finished())
+        {
+            return;
+        }
+        if self.policy.is_still() {
             self.rendered_once = true;
             let mode = match self.policy {
                 RenderPolicy::Export { .. } => "export",
+                RenderPolicy::Progressive { .. } => "progressive",
                 _ => "still",
             };
             debug!(mode, "scheduler parked after single-frame {mode} policy");
@@ -386,7 +671,9 @@ impl FrameScheduler {
                 }
                 None => true,
             },
-            RenderPolicy::Still { .. } | RenderPolicy::Export { .. } => !self.rendered_once,
+            RenderPolicy::Still { .. }
+            | RenderPolicy::Progressive { .. }
+            | RenderPolicy::Export { .. } => !self.rendered_once,
         }
     }
 
@@ -402,4 +689,12 @@ impl FrameScheduler {
     pub fn policy(&self) -> &RenderPolicy {
         &self.policy
     }
+
+    /// Refinement schedule of a progressive policy, positioned at the pass to render next.
+    pub fn refine_mut(&mut self) -> Option<&mut ProgressiveRefine> {
+        match &mut self.policy {
+            RenderPolicy::Progressive { refine, .. } => Some(refine),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,183 @@
+//! On-disk cache for compiled shaders and the driver pipeline cache.
+//!
+//! Compiling ShaderToy GLSL through shaderc dominates cold starts and playlist
+//! swaps, so the SPIR-V it produces is kept under `<cache dir>/shaders`. Where
+//! the adapter supports `wgpu::Features::PIPELINE_CACHE`, the driver's pipeline
+//! cache blob is stored alongside it.
+//!
+//! ```text
+//!   <cache dir>/shaders/
+//!     <prelude fingerprint>/          one directory per prelude revision
+//!       <entry key>.spv               SPIR-V for one wrapped shader
+//!       pipeline-<adapter key>.bin    wgpu::PipelineCache data
+//!       still-<keyframe key>.txt      timestamp picked by `--still-time auto`
+//! ```
+//!
+//! Entry keys hash the wrapped source, compiler, stage, channel layout and
+//! surface color space. The directory name hashes the prelude and footers (see
+//! `compile::prelude_fingerprint`), so editing them, or upgrading wax11, starts a
+//! fresh directory and the stale ones are removed when the cache is opened.
+//! Keys come from [`key_hasher`] rather than `DefaultHasher`, whose algorithm
+//! may change between Rust releases and would orphan the cache on a toolchain
+//! bump. Cache failures are never fatal: a bad entry simply means compiling again.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use twox_hash::XxHash64;
+
+use crate::compile::prelude_fingerprint;
+
+/// First word of every SPIR-V module.
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+/// Seeds every key hash; bump it when the key layout changes.
+const KEY_VERSION: u64 = 1;
+
+/// Hasher for names of files and directories in the cache: xxHash64 with a
+/// fixed seed, so keys are identical across builds and toolchains.
+pub(crate) fn key_hasher() -> XxHash64 {
+    XxHash64::with_seed(KEY_VERSION)
+}
+
+/// Handle to the cache directory for the current prelude revision.
+#[derive(Debug, Clone)]
+pub(crate) struct ShaderCache {
+    dir: PathBuf,
+}
+
+impl ShaderCache {
+    /// Opens (creating if needed) the cache under `root` and prunes directories
+    /// left behind by other prelude revisions.
+    pub(crate) fn open(root: &Path) -> Result<Self> {
+        let revision = format!("{:016x}", prelude_fingerprint());
+        let dir = root.join(&revision);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create shader cache at {}", dir.display()))?;
+
+        if let Ok(entries) = std::fs::read_dir(root) {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                let stale = name != revision
+                    && name.len() == revision.len()
+                    && name.chars().all(|c| c.is_ascii_hexdigit());
+                if stale && entry.path().is_dir() {
+                    tracing::debug!(path = %entry.path().display(), "removing stale shader cache");
+                    let _ = std::fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+        Ok(Self { dir })
+    }
+
+    /// Cached SPIR-V for `key`, if present and well-formed.
+    pub(crate) fn load_spirv(&self, key: u64) -> Option<Vec<u32>> {
+        let bytes = std::fs::read(self.spirv_path(key)).ok()?;
+        if bytes.len() % 4 != 0 {
+            return None;
+        }
+        let words: Vec<u32> = bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        (words.first() == Some(&SPIRV_MAGIC)).then_some(words)
+    }
+
+    pub(crate) fn store_spirv(&self, key: u64, words: &[u32]) {
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        self.write(&self.spirv_path(key), &bytes);
+    }
+
+    /// Stored `wgpu::PipelineCache` data for the adapter identified by `adapter_key`.
+    pub(crate) fn load_pipeline_data(&self, adapter_key: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.pipeline_path(adapter_key)).ok()
+    }
+
+    pub(crate) fn store_pipeline_data(&self, adapter_key: &str, data: &[u8]) {
+        self.write(&self.pipeline_path(adapter_key), data);
+    }
+
+    /// Keyframe timestamp (seconds) previously selected for `key`.
+    pub(crate) fn load_still_time(&self, key: u64) -> Option<f32> {
+        let text = std::fs::read_to_string(self.still_time_path(key)).ok()?;
+        text.trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+    }
+
+    pub(crate) fn store_still_time(&self, key: u64, seconds: f32) {
+        self.write(
+            &self.still_time_path(key),
+            format!("{seconds}\n").as_bytes(),
+        );
+    }
+
+    fn spirv_path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{key:016x}.spv"))
+    }
+
+    fn pipeline_path(&self, adapter_key: &str) -> PathBuf {
+        self.dir.join(format!("pipeline-{adapter_key}.bin"))
+    }
+
+    fn still_time_path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("still-{key:016x}.txt"))
+    }
+
+    /// Writes through a temporary file so concurrent builds never see partial entries.
+    fn write(&self, path: &Path, bytes: &[u8]) {
+        let temp = path.with_extension(format!("tmp{}", std::process::id()));
+        let result = std::fs::write(&temp, bytes).and_then(|()| std::fs::rename(&temp, path));
+        if let Err(err) = result {
+            tracing::debug!(path = %path.display(), error = %err, "failed to write shader cache entry");
+            let _ = std::fs::remove_file(&temp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+
+    use super::*;
+
+    #[test]
+    fn key_hasher_is_pinned() {
+        let mut hasher = key_hasher();
+        hasher.write(b"wax11");
+        assert_eq!(hasher.finish(), 0xba1c_75cd_0a11_dbee);
+    }
+
+    #[test]
+    fn round_trips_spirv_and_prunes_stale_revisions() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let stale = root.join("00000000deadbeef");
+        std::fs::create_dir_all(&stale).unwrap();
+        std::fs::create_dir_all(root.join("keep-me")).unwrap();
+
+        let cache = ShaderCache::open(root).expect("open cache");
+        assert!(!stale.exists());
+        assert!(root.join("keep-me").exists());
+
+        let words = [SPIRV_MAGIC, 0x0001_0000, 7, 42];
+        cache.store_spirv(1, &words);
+        assert_eq!(cache.load_spirv(1).as_deref(), Some(&words[..]));
+        assert_eq!(cache.load_spirv(2), None);
+
+        std::fs::write(cache.spirv_path(3), b"not spirv").unwrap();
+        assert_eq!(cache.load_spirv(3), None);
+
+        cache.store_still_time(4, 12.5);
+        assert_eq!(cache.load_still_time(4), Some(12.5));
+        assert_eq!(cache.load_still_time(5), None);
+
+        cache.store_pipeline_data("adapter", b"blob");
+        assert_eq!(
+            cache.load_pipeline_data("adapter").as_deref(),
+            Some(&b"blob"[..])
+        );
+    }
+}
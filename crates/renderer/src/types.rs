@@ -36,13 +36,18 @@
 //!
 //! - `RendererConfig` — immutable run configuration; consumed by `window`/`wallpaper`.
 //! - `ChannelBindings` + `ChannelSource` + `CHANNEL_COUNT` — ShaderToy inputs.
+//! - `BufferPass` — offscreen "Buffer A–D" passes carried alongside the image bindings.
+//! - `ChannelSampler` + `ChannelFilter` + `ChannelWrap` — per-channel ShaderToy sampler settings.
+//! - `ChannelPlayback` + `AnimationLoop` — frame rate and looping for animated channels.
 //! - `ChannelTextureKind` + `CUBEMAP_FACE_STEMS` — texture dimensionality and discovery.
+//! - `ShaderLanguage` — GLSL (ShaderToy) or native WGSL pass sources.
 //! - `RenderMode`, `SurfaceAlpha`, `Antialiasing`, `ShaderCompiler`, `ColorSpaceMode` —
 //!   rendering and colour handling policies.
 //! - `GpuPowerPreference`, `GpuMemoryMode` — adapter/device usage hints.
 //!
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Result;
 
@@ -87,16 +92,181 @@ pub const CHANNEL_COUNT: usize = 4;
 /// Describes how a ShaderToy channel should be populated.
 #[derive(Clone, Debug)]
 pub enum ChannelSource {
-    Texture { path: PathBuf },
-    Cubemap { directory: PathBuf },
+    Texture {
+        path: PathBuf,
+    },
+    Cubemap {
+        directory: PathBuf,
+    },
+    /// ShaderToy `.bin` volume sampled as a 3D texture.
+    Volume {
+        path: PathBuf,
+    },
+    /// Animated GIF/APNG file or numbered image-sequence directory advancing with shader time.
+    Animation {
+        path: PathBuf,
+        playback: ChannelPlayback,
+    },
     Keyboard,
+    /// Decoded audio file exposed as ShaderToy's 512×2 FFT/waveform texture.
+    Audio {
+        path: PathBuf,
+    },
+    /// Live interleaved PCM read from stdin or a named pipe, analysed like an audio file.
+    LiveAudio(LiveAudio),
+    /// Output of an offscreen buffer pass, looked up by name in [`ChannelBindings::buffer_passes`].
+    Buffer {
+        name: String,
+    },
+    /// Output of the "Cube A" pass registered via [`ChannelBindings::set_cubemap_pass`].
+    CubemapPass {
+        name: String,
+    },
+    /// Samples generated by the "Sound" pass registered via
+    /// [`ChannelBindings::set_sound_pass`], exposed as an audio channel.
+    SoundPass {
+        name: String,
+    },
 }
 
-/// Enumerates the texture dimensionality requirements for a channel.
+/// Where a live audio channel reads its PCM stream from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LiveAudioInput {
+    /// The process' standard input, e.g. `parec | wax11 ...`.
+    Stdin,
+    /// A named pipe (FIFO); reopened whenever the writer goes away.
+    Fifo(PathBuf),
+}
+
+/// Sample encoding of a live PCM stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PcmFormat {
+    /// Signed 16-bit little-endian (the `parec`/`pw-cat` default).
+    #[default]
+    S16Le,
+    /// 32-bit little-endian float.
+    F32Le,
+}
+
+impl PcmFormat {
+    /// Size of one sample of one channel, in bytes.
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            PcmFormat::S16Le => 2,
+            PcmFormat::F32Le => 4,
+        }
+    }
+}
+
+/// Live PCM stream feeding a channel with ShaderToy's `mic`/`musicstream` texture layout.
+///
+/// Samples are interleaved across `channels` and downmixed to mono before analysis.
+/// `sample_rate` is reported to shaders through `iSampleRate`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LiveAudio {
+    pub input: LiveAudioInput,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub format: PcmFormat,
+}
+
+/// Offscreen ShaderToy pass ("Buffer A–D" or "Cube A") rendered ahead of the image pass.
+///
+/// Buffer passes render into double-buffered float targets sized to the shader's
+/// logical resolution; the cubemap pass renders all six faces of a fixed-size cube
+/// target. A pass that binds its own name reads the previous frame, which is how
+/// ShaderToy feedback effects accumulate state.
+#[derive(Clone, Debug)]
+pub struct BufferPass {
+    /// Name other passes use to reference this buffer.
+    pub name: String,
+    /// Source path for the pass, written in `channels.language()`.
+    pub source: PathBuf,
+    /// Channel inputs of this pass. Buffer references resolve against the owning
+    /// bindings' pass list; buffer passes declared here are ignored.
+    pub channels: ChannelBindings,
+}
+
+/// Texture filtering requested for a channel (ShaderToy's sampler `filter`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChannelFilter {
+    Nearest,
+    #[default]
+    Linear,
+    /// Trilinear filtering; image textures and cubemaps get a full mip chain on upload.
+    Mipmap,
+}
+
+/// Texture addressing outside `[0, 1]` for a channel (ShaderToy's sampler `wrap`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChannelWrap {
+    #[default]
+    Clamp,
+    Repeat,
+}
+
+/// Per-channel sampler settings mirroring ShaderToy's `sampler` block.
+///
+/// `filter` and `wrap` apply to every channel kind; `vflip` and `srgb` only affect
+/// image-backed textures and cubemaps.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChannelSampler {
+    pub filter: ChannelFilter,
+    pub wrap: ChannelWrap,
+    /// Flip image rows on upload so `uv.y = 0` samples the bottom of the picture.
+    pub vflip: bool,
+    /// Decode texels as sRGB; `None` follows the renderer's colour space.
+    pub srgb: Option<bool>,
+}
+
+impl Default for ChannelSampler {
+    fn default() -> Self {
+        Self {
+            filter: ChannelFilter::Linear,
+            wrap: ChannelWrap::Clamp,
+            vflip: true,
+            srgb: None,
+        }
+    }
+}
+
+/// What an animated channel does once it reaches its last frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationLoop {
+    /// Start over from the first frame.
+    #[default]
+    Repeat,
+    /// Hold the last frame.
+    Once,
+    /// Play backwards to the first frame, then forwards again.
+    PingPong,
+}
+
+/// Playback settings for animated texture channels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChannelPlayback {
+    /// Fixed frame rate; `None` uses the file's frame delays (30 fps for sequences).
+    pub fps: Option<f32>,
+    pub looping: AnimationLoop,
+}
+
+/// Enumerates the texture dimensionality requirements for a channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ChannelTextureKind {
     Texture2d,
     Cubemap,
+    Texture3d,
+}
+
+impl ChannelTextureKind {
+    /// Human-readable name used in channel diagnostics.
+    pub(crate) fn describe(self) -> &'static str {
+        match self {
+            ChannelTextureKind::Texture2d => "2D texture",
+            ChannelTextureKind::Cubemap => "cubemap",
+            ChannelTextureKind::Texture3d => "3D volume",
+        }
+    }
 }
 
 /// Expected face stems for cubemap resources stored on disk.
@@ -106,6 +276,11 @@ pub const CUBEMAP_FACE_STEMS: [&str; 6] = ["posx", "negx", "posy", "negy", "posz
 #[derive(Clone, Debug)]
 pub struct ChannelBindings {
     sources: [Option<ChannelSource>; CHANNEL_COUNT],
+    samplers: [ChannelSampler; CHANNEL_COUNT],
+    buffers: Vec<BufferPass>,
+    cubemap_pass: Option<Box<BufferPass>>,
+    sound_pass: Option<Box<BufferPass>>,
+    language: ShaderLanguage,
 }
 
 impl ChannelBindings {
@@ -116,43 +291,159 @@ impl ChannelBindings {
 
     /// Associates a texture path with the given channel.
     pub fn set_texture(&mut self, channel: usize, path: PathBuf) -> Result<()> {
-        if channel >= CHANNEL_COUNT {
-            anyhow::bail!(
-                "channel {} exceeds supported ShaderToy channel count ({})",
-                channel,
-                CHANNEL_COUNT
-            );
-        }
+        Self::check_channel(channel)?;
         self.sources[channel] = Some(ChannelSource::Texture { path });
         Ok(())
     }
 
     /// Associates a cubemap directory with the given channel.
     pub fn set_cubemap(&mut self, channel: usize, directory: PathBuf) -> Result<()> {
-        if channel >= CHANNEL_COUNT {
-            anyhow::bail!(
-                "channel {} exceeds supported ShaderToy channel count ({})",
-                channel,
-                CHANNEL_COUNT
-            );
-        }
+        Self::check_channel(channel)?;
         self.sources[channel] = Some(ChannelSource::Cubemap { directory });
         Ok(())
     }
 
+    /// Associates a ShaderToy `.bin` volume with the given channel.
+    pub fn set_volume(&mut self, channel: usize, path: PathBuf) -> Result<()> {
+        Self::check_channel(channel)?;
+        self.sources[channel] = Some(ChannelSource::Volume { path });
+        Ok(())
+    }
+
+    /// Associates an animated GIF/APNG or image-sequence directory with the given channel.
+    pub fn set_animation(
+        &mut self,
+        channel: usize,
+        path: PathBuf,
+        playback: ChannelPlayback,
+    ) -> Result<()> {
+        Self::check_channel(channel)?;
+        if playback.fps.is_some_and(|fps| fps.is_nan() || fps <= 0.0) {
+            anyhow::bail!("animation fps for channel {} must be positive", channel);
+        }
+        self.sources[channel] = Some(ChannelSource::Animation { path, playback });
+        Ok(())
+    }
+
     /// Marks the given channel as a virtual keyboard texture.
     pub fn set_keyboard(&mut self, channel: usize) -> Result<()> {
-        if channel >= CHANNEL_COUNT {
+        Self::check_channel(channel)?;
+        self.sources[channel] = Some(ChannelSource::Keyboard);
+        Ok(())
+    }
+
+    /// Associates an audio file (WAV/OGG/MP3) with the given channel.
+    pub fn set_audio(&mut self, channel: usize, path: PathBuf) -> Result<()> {
+        Self::check_channel(channel)?;
+        self.sources[channel] = Some(ChannelSource::Audio { path });
+        Ok(())
+    }
+
+    /// Feeds the given channel from a live PCM stream.
+    pub fn set_live_audio(&mut self, channel: usize, live: LiveAudio) -> Result<()> {
+        Self::check_channel(channel)?;
+        if live.sample_rate == 0 || live.channels == 0 {
             anyhow::bail!(
-                "channel {} exceeds supported ShaderToy channel count ({})",
-                channel,
-                CHANNEL_COUNT
+                "live audio for channel {} needs a non-zero sample rate and channel count",
+                channel
             );
         }
-        self.sources[channel] = Some(ChannelSource::Keyboard);
+        self.sources[channel] = Some(ChannelSource::LiveAudio(live));
+        Ok(())
+    }
+
+    /// Binds the output of the named buffer pass to the given channel.
+    pub fn set_buffer(&mut self, channel: usize, name: impl Into<String>) -> Result<()> {
+        Self::check_channel(channel)?;
+        self.sources[channel] = Some(ChannelSource::Buffer { name: name.into() });
         Ok(())
     }
 
+    /// Binds the output of the named cubemap pass to the given channel.
+    pub fn set_cubemap_buffer(&mut self, channel: usize, name: impl Into<String>) -> Result<()> {
+        Self::check_channel(channel)?;
+        self.sources[channel] = Some(ChannelSource::CubemapPass { name: name.into() });
+        Ok(())
+    }
+
+    /// Feeds the given channel with the audio generated by the named sound pass.
+    pub fn set_sound_buffer(&mut self, channel: usize, name: impl Into<String>) -> Result<()> {
+        Self::check_channel(channel)?;
+        self.sources[channel] = Some(ChannelSource::SoundPass { name: name.into() });
+        Ok(())
+    }
+
+    /// Registers an offscreen buffer pass. Passes render in registration order.
+    pub fn add_buffer_pass(&mut self, name: impl Into<String>, source: PathBuf, channels: Self) {
+        self.buffers.push(BufferPass {
+            name: name.into(),
+            source,
+            channels,
+        });
+    }
+
+    /// Buffer passes rendered before the image pass, in render order.
+    pub fn buffer_passes(&self) -> &[BufferPass] {
+        &self.buffers
+    }
+
+    /// Registers the cubemap pass, replacing any earlier one (ShaderToy allows a single "Cube A").
+    pub fn set_cubemap_pass(&mut self, name: impl Into<String>, source: PathBuf, channels: Self) {
+        self.cubemap_pass = Some(Box::new(BufferPass {
+            name: name.into(),
+            source,
+            channels,
+        }));
+    }
+
+    /// Cubemap pass rendered after the buffer passes, if any.
+    pub fn cubemap_pass(&self) -> Option<&BufferPass> {
+        self.cubemap_pass.as_deref()
+    }
+
+    /// Registers the sound pass, replacing any earlier one (ShaderToy allows a single "Sound").
+    ///
+    /// The pass is only evaluated when a channel reads it or when exporting audio.
+    pub fn set_sound_pass(&mut self, name: impl Into<String>, source: PathBuf, channels: Self) {
+        self.sound_pass = Some(Box::new(BufferPass {
+            name: name.into(),
+            source,
+            channels,
+        }));
+    }
+
+    /// Sound pass producing `mainSound` samples, if any.
+    pub fn sound_pass(&self) -> Option<&BufferPass> {
+        self.sound_pass.as_deref()
+    }
+
+    /// Source bound to `channel`, if any.
+    pub fn channel(&self, channel: usize) -> Option<&ChannelSource> {
+        self.sources.get(channel).and_then(Option::as_ref)
+    }
+
+    /// Overrides the sampler settings used for `channel`.
+    pub fn set_sampler(&mut self, channel: usize, sampler: ChannelSampler) -> Result<()> {
+        Self::check_channel(channel)?;
+        self.samplers[channel] = sampler;
+        Ok(())
+    }
+
+    /// Sampler settings for `channel` (defaults when never overridden).
+    pub fn sampler(&self, channel: usize) -> ChannelSampler {
+        self.samplers.get(channel).copied().unwrap_or_default()
+    }
+
+    /// Sets the source language of the pass these bindings belong to.
+    pub fn set_language(&mut self, language: ShaderLanguage) {
+        self.language = language;
+    }
+
+    /// Source language of the pass these bindings belong to.
+    pub fn language(&self) -> ShaderLanguage {
+        self.language
+    }
+
     /// Exposes the underlying channel slots for GPU resource creation.
     pub(crate) fn slots(&self) -> &[Option<ChannelSource>; CHANNEL_COUNT] {
         &self.sources
@@ -162,22 +453,55 @@ impl ChannelBindings {
     pub fn layout_signature(&self) -> [ChannelTextureKind; CHANNEL_COUNT] {
         let mut kinds = [ChannelTextureKind::Texture2d; CHANNEL_COUNT];
         for (index, source) in self.sources.iter().enumerate() {
-            if matches!(source, Some(ChannelSource::Cubemap { .. })) {
-                kinds[index] = ChannelTextureKind::Cubemap;
+            match source {
+                Some(ChannelSource::Cubemap { .. } | ChannelSource::CubemapPass { .. }) => {
+                    kinds[index] = ChannelTextureKind::Cubemap;
+                }
+                Some(ChannelSource::Volume { .. }) => kinds[index] = ChannelTextureKind::Texture3d,
+                _ => {}
             }
         }
         kinds
     }
+
+    /// Rejects channel indices beyond ShaderToy's `iChannel0..3`.
+    fn check_channel(channel: usize) -> Result<()> {
+        if channel >= CHANNEL_COUNT {
+            anyhow::bail!(
+                "channel {} exceeds supported ShaderToy channel count ({})",
+                channel,
+                CHANNEL_COUNT
+            );
+        }
+        Ok(())
+    }
 }
 
 impl Default for ChannelBindings {
     fn default() -> Self {
         Self {
             sources: std::array::from_fn(|_| None),
+            samplers: [ChannelSampler::default(); CHANNEL_COUNT],
+            buffers: Vec::new(),
+            cubemap_pass: None,
+            sound_pass: None,
+            language: ShaderLanguage::default(),
         }
     }
 }
 
+/// Language a pass source is written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ShaderLanguage {
+    /// ShaderToy GLSL, wrapped by `compile::wrap_shadertoy_fragment` and compiled
+    /// with the selected [`ShaderCompiler`].
+    #[default]
+    Glsl,
+    /// Native WGSL written against the prelude in `compile::wgsl_prelude`; handed
+    /// to naga's WGSL frontend and never touches the GLSL toolchain.
+    Wgsl,
+}
+
 /// Shader compilation backend requested by the caller.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShaderCompiler {
@@ -185,6 +509,20 @@ pub enum ShaderCompiler {
     Shaderc,
     /// Hand GLSL to naga's built-in frontend.
     NagaGlsl,
+    /// Try the preferred backend, fall back to the other one on failure, and
+    /// remember per shader which backend succeeded.
+    Auto,
+}
+
+impl ShaderCompiler {
+    /// Backend `Auto` tries first: shaderc when it is compiled in.
+    pub fn preferred() -> Self {
+        if cfg!(feature = "shaderc") {
+            ShaderCompiler::Shaderc
+        } else {
+            ShaderCompiler::NagaGlsl
+        }
+    }
 }
 
 impl Hash for ShaderCompiler {
@@ -192,6 +530,7 @@ impl Hash for ShaderCompiler {
         let value = match self {
             ShaderCompiler::Shaderc => 0u8,
             ShaderCompiler::NagaGlsl => 1u8,
+            ShaderCompiler::Auto => 2u8,
         };
         value.hash(state);
     }
@@ -199,11 +538,7 @@ impl Hash for ShaderCompiler {
 
 impl Default for ShaderCompiler {
     fn default() -> Self {
-        if cfg!(feature = "shaderc") {
-            ShaderCompiler::Shaderc
-        } else {
-            ShaderCompiler::NagaGlsl
-        }
+        ShaderCompiler::preferred()
     }
 }
 
@@ -212,6 +547,7 @@ impl std::fmt::Display for ShaderCompiler {
         match self {
             ShaderCompiler::Shaderc => f.write_str("shaderc"),
             ShaderCompiler::NagaGlsl => f.write_str("naga"),
+            ShaderCompiler::Auto => f.write_str("auto"),
         }
     }
 }
@@ -242,9 +578,10 @@ pub enum RenderMode {
 }
 
 /// Declares how the compositor should treat the swapchain alpha channel.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SurfaceAlpha {
     /// Frames fully cover the wallpaper surface without transparency.
+    #[default]
     Opaque,
     /// Frames may contain transparency and should be blended by the compositor.
     Transparent,
@@ -262,16 +599,38 @@ pub enum CrossfadeCurve {
     EaseInOut,
 }
 
-impl Default for SurfaceAlpha {
-    fn default() -> Self {
-        Self::Opaque
-    }
+/// Filter used to stretch a `render_scale` below 1.0 back to the output size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpscaleFilter {
+    /// Blocky pixel replication; the cheapest option.
+    Nearest,
+    /// Bilinear interpolation (default).
+    #[default]
+    Bilinear,
+    /// Bilinear followed by contrast-adaptive sharpening, in the spirit of FSR.
+    Sharpen,
+}
+
+/// Bounds and frame budget for dynamic resolution scaling.
+///
+/// The renderer starts at `max_scale` and moves the render scale within the
+/// bounds to hold `frame_budget`, upscaling to the output with the configured
+/// `UpscaleFilter`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DynamicScale {
+    /// Lowest render scale the governor may pick.
+    pub min_scale: f32,
+    /// Highest render scale, used for the first frames.
+    pub max_scale: f32,
+    /// Frame time to hold.
+    pub frame_budget: Duration,
 }
 
 /// Anti-aliasing policy for the render pipeline.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Antialiasing {
     /// Pick the highest sample count supported by the surface format.
+    #[default]
     Auto,
     /// Disable MSAA and render directly into the swapchain.
     Off,
@@ -279,46 +638,31 @@ pub enum Antialiasing {
     Samples(u32),
 }
 
-impl Default for Antialiasing {
-    fn default() -> Self {
-        Self::Auto
-    }
-}
-
 /// GPU power preference for adapter selection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum GpuPowerPreference {
     /// Low power mode, friendly to other applications (default).
+    #[default]
     Low,
     /// High performance mode, maximum GPU priority.
     High,
 }
 
-impl Default for GpuPowerPreference {
-    fn default() -> Self {
-        Self::Low
-    }
-}
-
 /// GPU memory allocation mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum GpuMemoryMode {
     /// Balanced memory usage, friendly to other applications (default).
+    #[default]
     Balanced,
     /// Performance mode, maximum memory allocation priority.
     Performance,
 }
 
-impl Default for GpuMemoryMode {
-    fn default() -> Self {
-        Self::Balanced
-    }
-}
-
 /// VSync behavior control for testing stutter mitigation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum VsyncMode {
     /// VSync always enabled (default, prevents tearing).
+    #[default]
     Never,
     /// VSync disabled only during shader crossfades.
     Crossfade,
@@ -326,12 +670,6 @@ pub enum VsyncMode {
     Always,
 }
 
-impl Default for VsyncMode {
-    fn default() -> Self {
-        Self::Never
-    }
-}
-
 /// Immutable configuration passed to the renderer at start-up.
 ///
 /// `RendererConfig` mirrors CLI flags and tells the renderer which shader file
@@ -357,10 +695,18 @@ pub struct RendererConfig {
     pub surface_alpha: SurfaceAlpha,
     /// Shader compiler that should be used for wrapped GLSL.
     pub shader_compiler: ShaderCompiler,
+    /// Shared library directories searched for GLSL `#include`s after the pack itself.
+    pub shader_include_dirs: Vec<PathBuf>,
+    /// Directory for compiled shaders and pipeline cache data; `None` disables caching.
+    pub shader_cache_dir: Option<PathBuf>,
     /// Desired color handling for swapchain/textures.
     pub color_space: ColorSpaceMode,
     /// Supersampling factor applied before presenting to the surface (1.0 = native).
     pub render_scale: f32,
+    /// Filter that stretches frames rendered below `render_scale` 1.0 to the surface.
+    pub upscale_filter: UpscaleFilter,
+    /// Adjust the render scale at run time to hold a frame budget; overrides `render_scale`.
+    pub dynamic_scale: Option<DynamicScale>,
     /// How shader coordinates map to the wallpaper surface.
     pub fill_method: FillMethod,
     /// Maximum FPS allowed while the surface is occluded (if adaptive throttling enabled).
@@ -396,8 +742,12 @@ impl Default for RendererConfig {
             antialiasing: Antialiasing::default(),
             surface_alpha: SurfaceAlpha::Opaque,
             shader_compiler: ShaderCompiler::default(),
+            shader_include_dirs: Vec::new(),
+            shader_cache_dir: None,
             color_space: ColorSpaceMode::default(),
             render_scale: 1.0,
+            upscale_filter: UpscaleFilter::default(),
+            dynamic_scale: None,
             fill_method: FillMethod::default(),
             max_fps_occluded: None,
             show_window: true,
@@ -411,3 +761,40 @@ impl Default for RendererConfig {
         }
     }
 }
+
+/// Request to evaluate a ShaderToy "Sound" pass offline and write it to a WAV file.
+#[derive(Clone, Debug)]
+pub struct SoundExportConfig {
+    /// Sound pass to evaluate (see [`ChannelBindings::sound_pass`]).
+    pub pass: BufferPass,
+    /// Destination `.wav` path (16-bit stereo PCM).
+    pub path: PathBuf,
+    /// Length of audio to render.
+    pub duration: Duration,
+    /// Samples per second exposed through `iSampleRate`.
+    pub sample_rate: u32,
+    /// Shader compiler that should be used for wrapped GLSL.
+    pub shader_compiler: ShaderCompiler,
+    /// Shared library directories searched for GLSL `#include`s after the pack itself.
+    pub shader_include_dirs: Vec<PathBuf>,
+    /// Directory for compiled shaders; `None` disables caching.
+    pub shader_cache_dir: Option<PathBuf>,
+    /// Colour handling for texture channels read by the sound pass.
+    pub color_space: ColorSpaceMode,
+    /// GPU power preference for adapter selection.
+    pub gpu_power: GpuPowerPreference,
+}
+
+/// Request to compile every pass of a shader without a window or GPU, as
+/// `wax11 check` does.
+#[derive(Clone, Debug)]
+pub struct ShaderCheckConfig {
+    /// Entry (image) pass source.
+    pub shader_source: PathBuf,
+    /// Channel bindings of the entry pass, carrying the buffer, cubemap and sound passes.
+    pub channel_bindings: ChannelBindings,
+    /// Shared library directories searched for GLSL `#include`s after the pack itself.
+    pub shader_include_dirs: Vec<PathBuf>,
+    /// Backends every GLSL pass is compiled with; WGSL passes always use naga.
+    pub compilers: Vec<ShaderCompiler>,
+}
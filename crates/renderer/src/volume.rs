@@ -0,0 +1,126 @@
+//! Decoder for ShaderToy's `.bin` volume textures (the grey/RGBA noise volumes).
+//!
+//! The site ships volumes as a small little-endian header followed by tightly
+//! packed texels, x fastest then y then z:
+//!
+//! ```text
+//! offset  size  field
+//!      0     4  signature "BIN\0"
+//!      4     4  width  (u32)
+//!      8     4  height (u32)
+//!     12     4  depth  (u32)
+//!     16     1  channels (1–4)
+//!     17     1  layout (unused, always 0)
+//!     18     2  format (u16, 0 = 8-bit unsigned)
+//!     20     …  texels
+//! ```
+//!
+//! Texels are widened to RGBA8 the way WebGL samples narrower formats: missing
+//! colour channels read as zero and missing alpha as one.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+const HEADER_LEN: usize = 20;
+const SIGNATURE: &[u8; 4] = b"BIN\0";
+const FORMAT_U8: u16 = 0;
+
+/// Decoded volume ready for upload as an `Rgba8Unorm` 3D texture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Volume {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Reads and decodes a ShaderToy `.bin` volume file.
+pub(crate) fn load_volume(path: &Path) -> Result<Volume> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read volume {}", path.display()))?;
+    parse_volume(&bytes).with_context(|| format!("failed to decode volume {}", path.display()))
+}
+
+fn parse_volume(bytes: &[u8]) -> Result<Volume> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != SIGNATURE {
+        bail!("missing ShaderToy BIN volume header");
+    }
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4-byte slice"))
+    };
+    let (width, height, depth) = (read_u32(4), read_u32(8), read_u32(12));
+    let channels = bytes[16] as usize;
+    let format = u16::from_le_bytes([bytes[18], bytes[19]]);
+
+    if !(1..=4).contains(&channels) {
+        bail!("unsupported volume channel count {channels}");
+    }
+    if format != FORMAT_U8 {
+        bail!("unsupported volume sample format {format} (only 8-bit volumes are supported)");
+    }
+    if width == 0 || height == 0 || depth == 0 {
+        bail!("volume has zero extent ({width}x{height}x{depth})");
+    }
+
+    let texels = width as usize * height as usize * depth as usize;
+    let payload = &bytes[HEADER_LEN..];
+    if payload.len() < texels * channels {
+        bail!(
+            "volume payload holds {} bytes, expected {} for {width}x{height}x{depth}x{channels}",
+            payload.len(),
+            texels * channels
+        );
+    }
+
+    let mut rgba = Vec::with_capacity(texels * 4);
+    for texel in payload.chunks_exact(channels).take(texels) {
+        let mut out = [0, 0, 0, u8::MAX];
+        out[..channels].copy_from_slice(texel);
+        rgba.extend_from_slice(&out);
+    }
+
+    Ok(Volume {
+        width,
+        height,
+        depth,
+        rgba,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(width: u32, height: u32, depth: u32, channels: u8, texels: &[u8]) -> Vec<u8> {
+        let mut bytes = SIGNATURE.to_vec();
+        for value in [width, height, depth] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[channels, 0]);
+        bytes.extend_from_slice(&FORMAT_U8.to_le_bytes());
+        bytes.extend_from_slice(texels);
+        bytes
+    }
+
+    #[test]
+    fn widens_grey_volumes_to_rgba() {
+        let bytes = encode(2, 1, 2, 1, &[10, 20, 30, 40]);
+        let volume = parse_volume(&bytes).expect("valid volume");
+        assert_eq!((volume.width, volume.height, volume.depth), (2, 1, 2));
+        assert_eq!(
+            volume.rgba,
+            [10, 0, 0, 255, 20, 0, 0, 255, 30, 0, 0, 255, 40, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn keeps_rgba_volumes_and_rejects_truncated_payloads() {
+        let bytes = encode(1, 1, 1, 4, &[1, 2, 3, 4]);
+        assert_eq!(parse_volume(&bytes).unwrap().rgba, [1, 2, 3, 4]);
+
+        let truncated = encode(2, 2, 2, 4, &[0; 8]);
+        assert!(parse_volume(&truncated).is_err());
+        assert!(parse_volume(b"PNG\0").is_err());
+    }
+}
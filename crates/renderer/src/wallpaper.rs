@@ -67,8 +67,8 @@ use winit::dpi::PhysicalSize;
 use crate::gpu::{FileExportTarget, GpuState, RenderExportError};
 use crate::runtime::{time_source_for_policy, BoxedTimeSource, FillMethod, RenderPolicy};
 use crate::types::{
-    AdapterProfile, Antialiasing, ChannelBindings, ColorSpaceMode, GpuMemoryMode,
-    GpuPowerPreference, RendererConfig, ShaderCompiler, SurfaceAlpha, VsyncMode,
+    AdapterProfile, Antialiasing, ChannelBindings, ColorSpaceMode, DynamicScale, GpuMemoryMode,
+    GpuPowerPreference, RendererConfig, ShaderCompiler, SurfaceAlpha, UpscaleFilter, VsyncMode,
 };
 
 const SOFTWARE_FPS_CAP: f32 = 15.0;
@@ -310,8 +310,12 @@ struct WallpaperManager {
     target_fps: Option<f32>,
     color_space: ColorSpaceMode,
     shader_compiler: ShaderCompiler,
+    shader_include_dirs: Vec<PathBuf>,
+    shader_cache_dir: Option<PathBuf>,
     should_exit: bool,
     render_scale: f32,
+    upscale_filter: UpscaleFilter,
+    dynamic_scale: Option<DynamicScale>,
     fill_method: FillMethod,
     software_hint_emitted: bool,
     base_policy: RenderPolicy,
@@ -346,8 +350,12 @@ impl WallpaperManager {
             target_fps: config.target_fps,
             color_space: config.color_space,
             shader_compiler: config.shader_compiler,
+            shader_include_dirs: config.shader_include_dirs.clone(),
+            shader_cache_dir: config.shader_cache_dir.clone(),
             should_exit: false,
             render_scale: config.render_scale,
+            upscale_filter: config.upscale_filter,
+            dynamic_scale: config.dynamic_scale,
             fill_method: config.fill_method,
             software_hint_emitted: false,
             base_policy: config.policy.clone(),
@@ -486,7 +494,11 @@ impl WallpaperManager {
             self.surface_alpha,
             self.color_space,
             self.shader_compiler,
+            self.shader_include_dirs.clone(),
+            self.shader_cache_dir.clone(),
             self.render_scale,
+            self.upscale_filter,
+            self.dynamic_scale,
             self.fill_method,
             self.base_policy.clone(),
             self.gpu_power,
@@ -876,7 +888,11 @@ struct SurfaceState {
     surface_alpha: SurfaceAlpha,
     color_space: ColorSpaceMode,
     shader_compiler: ShaderCompiler,
+    shader_include_dirs: Vec<PathBuf>,
+    shader_cache_dir: Option<PathBuf>,
     render_scale: f32,
+    upscale_filter: UpscaleFilter,
+    dynamic_scale: Option<DynamicScale>,
     fill_method: FillMethod,
     requested_target_fps: Option<f32>,
     software_cap_applied: bool,
@@ -901,7 +917,11 @@ impl SurfaceState {
         surface_alpha: SurfaceAlpha,
         color_space: ColorSpaceMode,
         shader_compiler: ShaderCompiler,
+        shader_include_dirs: Vec<PathBuf>,
+        shader_cache_dir: Option<PathBuf>,
         render_scale: f32,
+        upscale_filter: UpscaleFilter,
+        dynamic_scale: Option<DynamicScale>,
         fill_method: FillMethod,
         policy: RenderPolicy,
         gpu_power: GpuPowerPreference,
@@ -925,7 +945,11 @@ impl SurfaceState {
             surface_alpha,
             color_space,
             shader_compiler,
+            shader_include_dirs,
+            shader_cache_dir,
             render_scale,
+            upscale_filter,
+            dynamic_scale,
             fill_method,
             requested_target_fps: target_fps,
             software_cap_applied: false,
@@ -960,7 +984,11 @@ impl SurfaceState {
             self.antialiasing,
             self.color_space,
             self.shader_compiler,
+            &self.shader_include_dirs,
+            self.shader_cache_dir.as_deref(),
             self.render_scale,
+            self.upscale_filter,
+            self.dynamic_scale,
             self.fill_method,
             self.gpu_power,
             self.gpu_memory,
@@ -1048,6 +1076,9 @@ impl SurfaceState {
     fn reset_render_state(&mut self) {
         self.rendered_once = false;
         self.pacer.reset();
+        if let RenderPolicy::Progressive { refine, .. } = &mut self.policy {
+            refine.reset();
+        }
     }
 
     fn set_policy(&mut self, policy: RenderPolicy) -> Result<()> {
@@ -1069,20 +1100,43 @@ impl SurfaceState {
         self.rendered_once
     }
 
-    fn should_render(&mut self) -> bool {
+    /// Whether one final frame is enough: always for still, progressive and
+    /// export policies, and for animated shaders that reflection found time-independent.
+    fn renders_once(&self) -> bool {
         match self.policy {
-            RenderPolicy::Still { .. } | RenderPolicy::Export { .. } => !self.rendered_once,
-            _ => self.pacer.should_render(),
+            RenderPolicy::Still { .. }
+            | RenderPolicy::Progressive { .. }
+            | RenderPolicy::Export { .. } => true,
+            _ => self
+                .gpu
+                .as_ref()
+                .is_some_and(|gpu| gpu.is_time_independent()),
+        }
+    }
+
+    fn should_render(&mut self) -> bool {
+        if self.renders_once() {
+            !self.rendered_once
+        } else {
+            self.pacer.should_render()
         }
     }
 
     fn mark_rendered(&mut self) -> bool {
-        if matches!(
-            self.policy,
-            RenderPolicy::Still { .. } | RenderPolicy::Export { .. }
-        ) {
+        if let RenderPolicy::Progressive { refine, .. } = &self.policy {
+            if !refine.is_finished() {
+                return false;
+            }
+        }
+        if self.renders_once() {
             let first_render = !self.rendered_once;
             self.rendered_once = true;
+            if first_render && matches!(self.policy, RenderPolicy::Animate { .. }) {
+                tracing::info!(
+                    shader = %self.shader_source.display(),
+                    "shader reads no time, input or channel uniforms; pausing frame callbacks"
+                );
+            }
             first_render
         } else {
             false
@@ -1092,14 +1146,18 @@ impl SurfaceState {
     fn render(&mut self) -> Result<(), SurfaceError> {
         if let Some(gpu) = self.gpu.as_mut() {
             let sample = self.time_source.sample();
-            let export_result = match &self.policy {
+            let export_result = match &mut self.policy {
                 RenderPolicy::Export { path, format, .. } => {
                     let target = FileExportTarget {
                         path: path.clone(),
-                        _format: *format,
+                        format: *format,
                     };
                     Some(gpu.render_export([0.0; 4], Some(sample), &target))
                 }
+                RenderPolicy::Progressive { refine, .. } => {
+                    gpu.render_refine([0.0; 4], Some(sample), refine)?;
+                    None
+                }
                 _ => {
                     gpu.render([0.0; 4], Some(sample))?;
                     None
@@ -1110,10 +1168,11 @@ impl SurfaceState {
                 match result {
                     Ok(_) => {}
                     Err(RenderExportError::Surface(surface_err)) => return Err(surface_err),
-                    Err(RenderExportError::Unsupported) => {
-                        tracing::warn!(
-                            "still-frame export is currently unavailable in the simplified renderer"
-                        );
+                    Err(err @ RenderExportError::Unsupported) => {
+                        tracing::warn!(error = %err, "still-frame export skipped");
+                    }
+                    Err(err @ RenderExportError::Capture(_)) => {
+                        tracing::error!(error = %err, "failed to export still frame");
                     }
                 }
             }
@@ -1154,11 +1213,7 @@ impl SurfaceState {
         if self.gpu.is_none() {
             return;
         }
-        if matches!(
-            self.policy,
-            RenderPolicy::Still { .. } | RenderPolicy::Export { .. }
-        ) && self.rendered_once
-        {
+        if self.renders_once() && self.rendered_once {
             self.pacer.is_frame_scheduled = false;
             return;
         }
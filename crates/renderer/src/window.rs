@@ -10,7 +10,7 @@
 //! ```text
 //! WindowRuntime (thread)
 //!   ├─ EventLoop<WindowCommand>
-//!   │    ├─ redraw → WindowState::render_frame(TimeSample)
+//!   │    ├─ redraw → WindowState::render_frame(TimeSample, refine pass)
 //!   │    ├─ resize → GpuState::resize
 //!   │    └─ input  → Mouse/Keyboard → keyboard texture
 //!   ├─ RenderPolicyDriver (cadence + TimeSource)
@@ -50,11 +50,12 @@ use tracing::{debug, error};
 
 use crate::gpu::{FileExportTarget, GpuState, RenderExportError};
 use crate::runtime::{
-    time_source_for_policy, BoxedTimeSource, FillMethod, FrameScheduler, RenderPolicy, TimeSample,
+    time_source_for_policy, BoxedTimeSource, FillMethod, FrameScheduler, ProgressiveRefine,
+    RenderPolicy, TimeSample,
 };
 use crate::types::{
-    AdapterProfile, Antialiasing, ChannelBindings, ColorSpaceMode, GpuMemoryMode,
-    GpuPowerPreference, RendererConfig, ShaderCompiler, SurfaceAlpha, VsyncMode,
+    AdapterProfile, Antialiasing, ChannelBindings, ColorSpaceMode, DynamicScale, GpuMemoryMode,
+    GpuPowerPreference, RendererConfig, ShaderCompiler, SurfaceAlpha, UpscaleFilter, VsyncMode,
 };
 use crate::wallpaper::SwapRequest;
 
@@ -68,8 +69,12 @@ pub(crate) struct WindowState {
     keyboard: KeyboardState,
     antialiasing: Antialiasing,
     shader_compiler: ShaderCompiler,
+    shader_include_dirs: Vec<PathBuf>,
+    shader_cache_dir: Option<PathBuf>,
     color_space: ColorSpaceMode,
     render_scale: f32,
+    upscale_filter: UpscaleFilter,
+    dynamic_scale: Option<DynamicScale>,
     fill_method: FillMethod,
     frame_sink: FrameSinkDriver,
     surface_alpha: SurfaceAlpha,
@@ -109,7 +114,11 @@ impl WindowState {
             config.antialiasing,
             config.color_space,
             config.shader_compiler,
+            &config.shader_include_dirs,
+            config.shader_cache_dir.as_deref(),
             config.render_scale,
+            config.upscale_filter,
+            config.dynamic_scale,
             config.fill_method,
             config.gpu_power,
             config.gpu_memory,
@@ -122,7 +131,7 @@ impl WindowState {
             RenderPolicy::Export { path, format, .. } => FrameSinkDriver::Export(FileExportState {
                 target: FileExportTarget {
                     path: path.clone(),
-                    _format: *format,
+                    format: *format,
                 },
                 captured: false,
             }),
@@ -136,8 +145,12 @@ impl WindowState {
             keyboard: KeyboardState::default(),
             antialiasing: config.antialiasing,
             shader_compiler: config.shader_compiler,
+            shader_include_dirs: config.shader_include_dirs.clone(),
+            shader_cache_dir: config.shader_cache_dir.clone(),
             color_space: config.color_space,
             render_scale: config.render_scale,
+            upscale_filter: config.upscale_filter,
+            dynamic_scale: config.dynamic_scale,
             fill_method: config.fill_method,
             frame_sink,
             surface_alpha: config.surface_alpha,
@@ -172,20 +185,24 @@ impl WindowState {
         }
     }
 
+    /// Renders one frame; progressive stills render the pass `refine` points at.
     pub(crate) fn render_frame(
         &mut self,
         time_sample: TimeSample,
+        refine: Option<&mut ProgressiveRefine>,
     ) -> Result<RenderFrameStatus, RenderExportError> {
         self.sync_keyboard(false);
         let mouse_uniform = self.mouse.as_uniform(self.size().height.max(1) as f32);
         let result = match &mut self.frame_sink {
-            FrameSinkDriver::Surface => self
-                .gpu
-                .as_mut()
-                .expect("gpu initialized")
-                .render(mouse_uniform, Some(time_sample))
+            FrameSinkDriver::Surface => {
+                let gpu = self.gpu.as_mut().expect("gpu initialized");
+                match refine {
+                    Some(refine) => gpu.render_refine(mouse_uniform, Some(time_sample), refine),
+                    None => gpu.render(mouse_uniform, Some(time_sample)),
+                }
                 .map(|_| RenderFrameStatus::Presented)
-                .map_err(RenderExportError::Surface),
+                .map_err(RenderExportError::Surface)
+            }
             FrameSinkDriver::Export(state) => {
                 if state.captured {
                     Ok(RenderFrameStatus::Captured(state.target.path.clone()))
@@ -249,7 +266,11 @@ impl WindowState {
                 antialiasing,
                 self.color_space,
                 self.shader_compiler,
+                &self.shader_include_dirs,
+                self.shader_cache_dir.as_deref(),
                 self.render_scale,
+                self.upscale_filter,
+                self.dynamic_scale,
                 self.fill_method,
                 self.gpu_power,
                 self.gpu_memory,
@@ -335,6 +356,10 @@ impl RenderPolicyDriver {
         self.time_source.sample()
     }
 
+    pub(crate) fn refine_mut(&mut self) -> Option<&mut ProgressiveRefine> {
+        self.scheduler.refine_mut()
+    }
+
     pub(crate) fn update_policy(&mut self, policy: RenderPolicy) -> Result<()> {
         let previous = self.scheduler.policy().clone();
         let preserve_time = matches!(
@@ -646,12 +671,10 @@ fn run_window_thread(
                     }
                     WindowEvent::MouseInput {
                         state: button_state,
-                        button,
+                        button: winit::event::MouseButton::Left,
                         ..
                     } => {
-                        if button == winit::event::MouseButton::Left {
-                            state.handle_mouse_button(button_state);
-                        }
+                        state.handle_mouse_button(button_state);
                     }
                     WindowEvent::Resized(new_size) => {
                         let target_size = if !config.show_window
@@ -670,7 +693,8 @@ fn run_window_thread(
                         let _ = inner_size_writer.request_inner_size(state.size());
                     }
                     WindowEvent::RedrawRequested => {
-                        match state.render_frame(policy_driver.sample()) {
+                        let sample = policy_driver.sample();
+                        match state.render_frame(sample, policy_driver.refine_mut()) {
                             Ok(RenderFrameStatus::Presented) => {
                                 policy_driver.mark_rendered();
                             }
@@ -24,7 +24,7 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use multiconfig::{AntialiasSetting, MultiConfig, PlaylistMode};
+use multiconfig::{AntialiasSetting, LiveAudioRoute, MultiConfig, PlaylistMode, StillTime};
 use rand::prelude::*;
 
 #[derive(Debug, thiserror::Error)]
@@ -51,7 +51,8 @@ pub struct ScheduledItem {
     pub refresh_once: bool,
     pub crossfade: Duration,
     pub mode: multiconfig::PlaylistItemMode,
-    pub still_time: Option<Duration>,
+    pub still_time: Option<StillTime>,
+    pub live_audio: Option<LiveAudioRoute>,
 }
 
 #[derive(Debug, Clone)]
@@ -168,6 +169,7 @@ impl PlaylistRuntime {
                 refresh_once: item.refresh_once,
                 mode: item.mode.unwrap_or(multiconfig::PlaylistItemMode::Animate),
                 still_time: item.still_time,
+                live_audio: item.live_audio.clone(),
             })
             .collect();
         Self {
@@ -186,7 +188,8 @@ struct RuntimeItem {
     antialias: Option<AntialiasSetting>,
     refresh_once: bool,
     mode: multiconfig::PlaylistItemMode,
-    still_time: Option<Duration>,
+    still_time: Option<StillTime>,
+    live_audio: Option<LiveAudioRoute>,
 }
 
 struct TargetState {
@@ -250,6 +253,7 @@ impl TargetState {
             crossfade: self.playlist.crossfade,
             mode: item.mode,
             still_time: item.still_time,
+            live_audio: item.live_audio.clone(),
         }
     }
 }
@@ -439,7 +443,10 @@ still_time = "2s"
             change.item.mode,
             multiconfig::PlaylistItemMode::Still
         ));
-        assert_eq!(change.item.still_time, Some(Duration::from_secs(2)));
+        assert_eq!(
+            change.item.still_time,
+            Some(StillTime::At(Duration::from_secs(2)))
+        );
     }
 
     #[test]
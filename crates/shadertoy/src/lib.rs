@@ -31,7 +31,8 @@ mod repository;
 
 pub use handle::{normalize_shadertoy_reference, parse_shader_handle};
 pub use manifest::{
-    ColorSpace, InputSource, PassInput, PassKind, ShaderPackManifest, ShaderPass, SurfaceAlpha,
+    ColorSpace, InputSampler, InputSource, LoopMode, PassInput, PassKind, PassLanguage, PcmFormat,
+    SamplerFilter, SamplerWrap, ShaderPackManifest, ShaderPass, StillAt, SurfaceAlpha,
 };
 pub use pack::{ensure_glsl_sources, resolve_entry_source, LocalPack, PackError};
 pub use path::PathResolver;
@@ -86,7 +87,7 @@ mod tests {
     fn parses_local_path() {
         assert!(matches!(
             ShaderHandle::from_input("packs/demo"),
-            ShaderHandle::LocalPack(path) if path == PathBuf::from("packs/demo")
+            ShaderHandle::LocalPack(path) if path == Path::new("packs/demo")
         ));
     }
 }
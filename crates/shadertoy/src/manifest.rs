@@ -11,8 +11,15 @@
 //! - `ShaderPass` stores per-pass source paths, kind, and declared inputs.
 //! - `PassKind`, `SurfaceAlpha`, and `ColorSpace` encode renderer-relevant
 //!   options with serde defaults that tolerate sparse manifests.
+//! - `StillAt` is the pack's preferred still timestamp: seconds, or `"auto"`
+//!   to have the renderer search for an interesting frame.
 //! - `PassInput` pairs a channel index with an `InputSource` describing textures,
-//!   buffers, cubemaps, audio, or keyboard expectations.
+//!   buffers, cubemaps, volumes, animations, audio files, live PCM streams, or keyboard expectations.
+//! - `InputSampler` (with `SamplerFilter`/`SamplerWrap`) records ShaderToy's
+//!   per-channel filter, wrap, vflip, and sRGB options; absent fields keep the
+//!   renderer defaults.
+//! - `LoopMode` selects repeat/once/ping-pong playback for animation inputs.
+//! - `PcmFormat` names the sample encoding of live inputs (`s16le` or `f32le`).
 //! - `InputSource` enumerates manifest-supported resources exactly as `remote`
 //!   emits them and `pack` resolves them on disk.
 //!
@@ -22,7 +29,8 @@
 //!   cache loaders can surface misconfigurations without panicking.
 use std::path::PathBuf;
 
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ShaderPackManifest {
@@ -37,6 +45,8 @@ pub struct ShaderPackManifest {
     pub description: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub still_at: Option<StillAt>,
     #[serde(default)]
     pub passes: Vec<ShaderPass>,
 }
@@ -51,6 +61,8 @@ pub struct ShaderPass {
     #[serde(default = "default_pass_kind")]
     pub kind: PassKind,
     pub source: PathBuf,
+    #[serde(default, skip_serializing_if = "PassLanguage::is_glsl")]
+    pub language: PassLanguage,
     #[serde(default)]
     pub inputs: Vec<PassInput>,
 }
@@ -59,45 +71,103 @@ fn default_pass_kind() -> PassKind {
     PassKind::Image
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum PassKind {
+    #[default]
     Image,
     Buffer,
     Sound,
     Cubemap,
 }
 
-impl Default for PassKind {
-    fn default() -> Self {
-        Self::Image
+/// Language of a pass source: ShaderToy GLSL, or native WGSL written against
+/// the renderer's WGSL prelude.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PassLanguage {
+    #[default]
+    Glsl,
+    Wgsl,
+}
+
+impl PassLanguage {
+    fn is_glsl(&self) -> bool {
+        *self == PassLanguage::Glsl
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum SurfaceAlpha {
+    #[default]
     Opaque,
     Transparent,
 }
 
-impl Default for SurfaceAlpha {
-    fn default() -> Self {
-        Self::Opaque
-    }
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ColorSpace {
+    #[default]
     Auto,
     Gamma,
     Linear,
 }
 
-impl Default for ColorSpace {
-    fn default() -> Self {
-        Self::Auto
+/// Timestamp stills of the pack are rendered at when `--still-time` is not given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StillAt {
+    /// Fixed time in seconds (`still_at = 12.5`).
+    Seconds(f32),
+    /// Search for the most interesting frame (`still_at = "auto"`).
+    Auto,
+}
+
+impl Serialize for StillAt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            StillAt::Seconds(seconds) => serializer.serialize_f32(*seconds),
+            StillAt::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StillAt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StillAtVisitor;
+
+        impl de::Visitor<'_> for StillAtVisitor {
+            type Value = StillAt;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("seconds or \"auto\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<StillAt, E> {
+                let value = value.trim();
+                if value.eq_ignore_ascii_case("auto") {
+                    return Ok(StillAt::Auto);
+                }
+                value
+                    .parse()
+                    .map(StillAt::Seconds)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<StillAt, E> {
+                Ok(StillAt::Seconds(value as f32))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<StillAt, E> {
+                Ok(StillAt::Seconds(value as f32))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<StillAt, E> {
+                Ok(StillAt::Seconds(value as f32))
+            }
+        }
+
+        deserializer.deserialize_any(StillAtVisitor)
     }
 }
 
@@ -106,16 +176,105 @@ pub struct PassInput {
     pub channel: u8,
     #[serde(flatten)]
     pub source: InputSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampler: Option<InputSampler>,
+}
+
+/// Per-channel sampler options; unset fields fall back to the renderer defaults
+/// (linear filtering, clamped, vertically flipped, sRGB following the surface).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InputSampler {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SamplerFilter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrap: Option<SamplerWrap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vflip: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub srgb: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SamplerFilter {
+    Nearest,
+    Linear,
+    Mipmap,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SamplerWrap {
+    Clamp,
+    Repeat,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum InputSource {
-    Buffer { name: String },
-    Texture { path: PathBuf },
-    Cubemap { directory: PathBuf },
-    Audio { path: PathBuf },
+    Buffer {
+        name: String,
+    },
+    Texture {
+        path: PathBuf,
+    },
+    Cubemap {
+        directory: PathBuf,
+    },
+    /// ShaderToy `.bin` volume sampled as a 3D texture.
+    Volume {
+        path: PathBuf,
+    },
+    /// Animated GIF/APNG file or numbered image-sequence directory.
+    Animation {
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fps: Option<f32>,
+        #[serde(default, rename = "loop")]
+        looping: LoopMode,
+    },
+    Audio {
+        path: PathBuf,
+    },
     Keyboard,
+    /// Live interleaved PCM from stdin (no `fifo`) or a named pipe.
+    Live {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fifo: Option<PathBuf>,
+        #[serde(default = "default_live_sample_rate")]
+        sample_rate: u32,
+        #[serde(default = "default_live_channels")]
+        channels: u16,
+        #[serde(default)]
+        format: PcmFormat,
+    },
+}
+
+fn default_live_sample_rate() -> u32 {
+    44_100
+}
+
+fn default_live_channels() -> u16 {
+    2
+}
+
+/// What an animation input does after its last frame.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LoopMode {
+    #[default]
+    Repeat,
+    Once,
+    Pingpong,
+}
+
+/// Sample encoding of a live PCM input.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PcmFormat {
+    #[default]
+    S16le,
+    F32le,
 }
 
 impl ShaderPackManifest {
@@ -127,6 +286,11 @@ impl ShaderPackManifest {
         if self.passes.iter().all(|pass| pass.name != self.entry) {
             issues.push(format!("entry pass '{}' not found", self.entry));
         }
+        if let Some(StillAt::Seconds(seconds)) = self.still_at {
+            if !seconds.is_finite() || seconds < 0.0 {
+                issues.push(format!("still_at {seconds} must be a non-negative time"));
+            }
+        }
         for pass in &self.passes {
             for input in &pass.inputs {
                 if input.channel > 3 {
@@ -135,16 +299,67 @@ impl ShaderPackManifest {
                         pass.name, input.channel
                     ));
                 }
-                if let InputSource::Buffer { name } = &input.source {
-                    if self.passes.iter().all(|candidate| &candidate.name != name) {
+                match &input.source {
+                    InputSource::Buffer { name }
+                        if self.passes.iter().all(|candidate| &candidate.name != name) =>
+                    {
                         issues.push(format!(
                             "pass '{}' references buffer '{}' which is undefined",
                             pass.name, name
                         ));
                     }
+                    InputSource::Animation { fps: Some(fps), .. }
+                        if fps.is_nan() || *fps <= 0.0 =>
+                    {
+                        issues.push(format!(
+                            "pass '{}' animation on channel {} needs a positive fps",
+                            pass.name, input.channel
+                        ));
+                    }
+                    InputSource::Live {
+                        sample_rate,
+                        channels,
+                        ..
+                    } if *sample_rate == 0 || *channels == 0 => {
+                        issues.push(format!(
+                            "pass '{}' live input on channel {} needs a non-zero sample_rate and channels",
+                            pass.name, input.channel
+                        ));
+                    }
+                    _ => {}
                 }
             }
         }
         issues
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(still_at: &str) -> Result<ShaderPackManifest, toml::de::Error> {
+        toml::from_str(&format!(
+            "{still_at}\n[[passes]]\nname = \"image\"\nsource = \"image.glsl\"\n"
+        ))
+    }
+
+    #[test]
+    fn still_at_accepts_seconds_and_auto() {
+        assert_eq!(parse("").unwrap().still_at, None);
+        assert_eq!(
+            parse("still_at = 12.5").unwrap().still_at,
+            Some(StillAt::Seconds(12.5))
+        );
+        assert_eq!(
+            parse("still_at = 3").unwrap().still_at,
+            Some(StillAt::Seconds(3.0))
+        );
+        assert_eq!(
+            parse("still_at = \"auto\"").unwrap().still_at,
+            Some(StillAt::Auto)
+        );
+        assert!(parse("still_at = \"later\"").is_err());
+        assert!(!parse("still_at = -1.0").unwrap().validate().is_empty());
+    }
+}
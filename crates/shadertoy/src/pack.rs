@@ -85,11 +85,13 @@ impl LocalPack {
 
     pub fn asset_path(&self, source: &InputSource) -> Option<PathBuf> {
         match source {
-            InputSource::Texture { path } | InputSource::Audio { path } => {
-                Some(self.root.join(path))
-            }
+            InputSource::Texture { path }
+            | InputSource::Volume { path }
+            | InputSource::Animation { path, .. }
+            | InputSource::Audio { path } => Some(self.root.join(path)),
             InputSource::Cubemap { directory } => Some(self.root.join(directory)),
-            InputSource::Keyboard | InputSource::Buffer { .. } => None,
+            // Live inputs read stdin or a system FIFO, not a pack asset.
+            InputSource::Keyboard | InputSource::Buffer { .. } | InputSource::Live { .. } => None,
         }
     }
 
@@ -130,7 +132,8 @@ pub fn ensure_glsl_sources(pack: &LocalPack) -> Result<Vec<PathBuf>, PackError>
 mod tests {
     use super::*;
     use crate::manifest::{
-        ColorSpace, InputSource, PassInput, PassKind, ShaderPackManifest, ShaderPass, SurfaceAlpha,
+        ColorSpace, InputSource, PassInput, PassKind, PassLanguage, PcmFormat, ShaderPackManifest,
+        ShaderPass, SurfaceAlpha,
     };
 
     fn write_pack(dir: &Path, manifest: &ShaderPackManifest, extra_files: &[(&str, &str)]) {
@@ -153,15 +156,18 @@ mod tests {
             color_space: ColorSpace::Auto,
             description: None,
             tags: vec![],
+            still_at: None,
             passes: vec![ShaderPass {
                 name: "image".into(),
                 kind: PassKind::Image,
                 source: PathBuf::from("image.glsl"),
+                language: PassLanguage::Glsl,
                 inputs: vec![PassInput {
                     channel: 0,
                     source: InputSource::Texture {
                         path: PathBuf::from("textures/tex0.png"),
                     },
+                    sampler: None,
                 }],
             }],
         }
@@ -196,4 +202,53 @@ mod tests {
         let err = ensure_glsl_sources(&pack).unwrap_err();
         assert!(matches!(err, PackError::ManifestValidation(_)));
     }
+
+    #[test]
+    fn loads_live_audio_inputs_with_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(
+            temp.path().join("shader.toml"),
+            r#"
+[[passes]]
+name = "image"
+source = "image.glsl"
+
+[[passes.inputs]]
+channel = 0
+type = "live"
+
+[[passes.inputs]]
+channel = 1
+type = "live"
+fifo = "/tmp/wax11.fifo"
+sample_rate = 48000
+channels = 1
+format = "f32le"
+"#,
+        )
+        .unwrap();
+        fs::write(temp.path().join("image.glsl"), "// shader").unwrap();
+
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+        let inputs = &pack.manifest().passes[0].inputs;
+        assert!(matches!(
+            &inputs[0].source,
+            InputSource::Live {
+                fifo: None,
+                sample_rate: 44_100,
+                channels: 2,
+                format: PcmFormat::S16le,
+            }
+        ));
+        assert!(matches!(
+            &inputs[1].source,
+            InputSource::Live {
+                fifo: Some(path),
+                sample_rate: 48_000,
+                channels: 1,
+                format: PcmFormat::F32le,
+            } if path == Path::new("/tmp/wax11.fifo")
+        ));
+        assert!(pack.asset_path(&inputs[0].source).is_none());
+    }
 }
@@ -10,8 +10,9 @@
 //!   blocking client.
 //! - `ShadertoyClient` wraps `reqwest` to fetch shader JSON and supporting media
 //!   before handing results to caching logic.
-//! - `ShaderPayload`, `ShaderInfo`, `RenderPass`, `RenderInput`, and
-//!   `RenderOutput` mirror the API schema that `materialize_shader` ingests.
+//! - `ShaderPayload`, `ShaderInfo`, `RenderPass`, `RenderInput`,
+//!   `RenderSampler`, and `RenderOutput` mirror the API schema that
+//!   `materialize_shader` ingests.
 //!
 //! Functions:
 //!
@@ -20,7 +21,7 @@
 //! - `materialize_shader` converts payloads into manifest + GLSL files, calling
 //!   back into the provided asset fetcher so tests can stub downloads.
 //! - Internal helpers (`build_cache_plan`, `maybe_unpack_cubemap_archive`,
-//!   `map_pass_kind`, `sanitize_label`, `derive_filename`, etc.) keep asset names
+//!   `map_pass_kind`, `map_sampler`, `sanitize_label`, `derive_filename`, etc.) keep asset names
 //!   stable and the generated manifest valid for `LocalPack::load`.
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -35,7 +36,8 @@ use tracing::debug;
 use zip::ZipArchive;
 
 use crate::manifest::{
-    ColorSpace, InputSource, PassInput, PassKind, ShaderPackManifest, ShaderPass, SurfaceAlpha,
+    ColorSpace, InputSampler, InputSource, PassInput, PassKind, PassLanguage, SamplerFilter,
+    SamplerWrap, ShaderPackManifest, ShaderPass, SurfaceAlpha,
 };
 use crate::pack::LocalPack;
 
@@ -202,6 +204,40 @@ pub struct RenderInput {
     pub src: Option<String>,
     #[serde(rename = "ctype")]
     pub channel_type: String,
+    #[serde(default)]
+    pub sampler: Option<RenderSampler>,
+}
+
+/// Sampler options as the API reports them; flags arrive as `"true"`/`"false"` strings.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RenderSampler {
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub wrap: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub vflip: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub srgb: Option<String>,
+}
+
+/// Accepts both the API's string flags and plain JSON booleans.
+fn deserialize_flag<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Text(String),
+    }
+    Ok(
+        Option::<Flag>::deserialize(deserializer)?.map(|flag| match flag {
+            Flag::Bool(value) => value.to_string(),
+            Flag::Text(text) => text,
+        }),
+    )
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -235,6 +271,7 @@ struct CachePlan {
 enum AssetKind {
     Texture,
     Cubemap,
+    Volume,
     Audio,
 }
 
@@ -331,6 +368,7 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
                     channel
                 );
             }
+            let sampler = input.sampler.as_ref().and_then(map_sampler);
             let ctype = input.channel_type.to_ascii_lowercase();
             match ctype.as_str() {
                 "buffer" => {
@@ -352,6 +390,7 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
                     inputs.push(PassInput {
                         channel,
                         source: InputSource::Buffer { name: buffer_name },
+                        sampler,
                     });
                 }
                 "texture" => {
@@ -373,6 +412,19 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
                     inputs.push(PassInput {
                         channel,
                         source: InputSource::Texture { path: dest_rel },
+                        sampler,
+                    });
+                }
+                "cubemap" if input.id.is_some_and(|id| output_to_pass.contains_key(&id)) => {
+                    // Cubemap inputs whose id matches a pass output read the "Cube A" pass.
+                    let cube_pass = input
+                        .id
+                        .and_then(|id| output_to_pass.get(&id).cloned())
+                        .expect("guarded by match arm");
+                    inputs.push(PassInput {
+                        channel,
+                        source: InputSource::Buffer { name: cube_pass },
+                        sampler,
                     });
                 }
                 "cubemap" => {
@@ -402,12 +454,36 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
                         source: InputSource::Cubemap {
                             directory: dest_dir,
                         },
+                        sampler,
+                    });
+                }
+                "volume" => {
+                    let src = input.src.as_ref().ok_or_else(|| {
+                        anyhow!("volume input missing src in pass '{}'", pass.name)
+                    })?;
+                    let filename = derive_filename(src, &format!("{pass_name}_ch{channel}"), "bin");
+                    let dest_rel = PathBuf::from("volumes").join(&filename);
+                    if !assets
+                        .iter()
+                        .any(|asset: &AssetArtifact| asset.destination_rel == dest_rel)
+                    {
+                        assets.push(AssetArtifact {
+                            url: src.clone(),
+                            destination_rel: dest_rel.clone(),
+                            kind: AssetKind::Volume,
+                        });
+                    }
+                    inputs.push(PassInput {
+                        channel,
+                        source: InputSource::Volume { path: dest_rel },
+                        sampler,
                     });
                 }
                 "keyboard" => {
                     inputs.push(PassInput {
                         channel,
                         source: InputSource::Keyboard,
+                        sampler: None,
                     });
                 }
                 "music" | "sound" | "musicstream" => {
@@ -429,6 +505,7 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
                     inputs.push(PassInput {
                         channel,
                         source: InputSource::Audio { path: dest_rel },
+                        sampler,
                     });
                 }
                 other => {
@@ -451,6 +528,7 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
             name: pass_name.clone(),
             kind,
             source: source_rel.clone(),
+            language: PassLanguage::Glsl,
             inputs,
         });
         // Prepend Common code to the pass source (if any was present)
@@ -475,6 +553,7 @@ fn build_cache_plan(payload: &ShaderPayload) -> Result<CachePlan> {
         color_space: ColorSpace::Auto,
         description: payload.info.description.clone(),
         tags: payload.info.tags.clone(),
+        still_at: None,
         passes: manifest_passes,
     };
 
@@ -531,6 +610,50 @@ fn maybe_unpack_cubemap_archive(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Translates API sampler options, dropping values the renderer does not understand.
+fn map_sampler(sampler: &RenderSampler) -> Option<InputSampler> {
+    let filter =
+        sampler
+            .filter
+            .as_deref()
+            .and_then(|filter| match filter.to_ascii_lowercase().as_str() {
+                "nearest" => Some(SamplerFilter::Nearest),
+                "linear" => Some(SamplerFilter::Linear),
+                "mipmap" => Some(SamplerFilter::Mipmap),
+                other => {
+                    debug!(filter = %other, "ignoring unknown sampler filter");
+                    None
+                }
+            });
+    let wrap = sampler
+        .wrap
+        .as_deref()
+        .and_then(|wrap| match wrap.to_ascii_lowercase().as_str() {
+            "clamp" => Some(SamplerWrap::Clamp),
+            "repeat" => Some(SamplerWrap::Repeat),
+            other => {
+                debug!(wrap = %other, "ignoring unknown sampler wrap");
+                None
+            }
+        });
+    let flag = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(|value| match value.to_ascii_lowercase().as_str() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            })
+    };
+    let mapped = InputSampler {
+        filter,
+        wrap,
+        vflip: flag(&sampler.vflip),
+        srgb: flag(&sampler.srgb),
+    };
+    (mapped != InputSampler::default()).then_some(mapped)
+}
+
 fn map_pass_kind(kind: &str) -> Result<PassKind> {
     match kind.to_ascii_lowercase().as_str() {
         "image" => Ok(PassKind::Image),
@@ -697,6 +820,138 @@ mod tests {
             .any(|(url, dest)| url.contains("tex0.png") && dest.ends_with("textures/tex0.png")));
     }
 
+    #[test]
+    fn maps_cubemap_pass_inputs_to_pass_references() {
+        let json = r#"
+        {
+            "info": { "name": "Cube" },
+            "renderpass": [
+                {
+                    "name": "Cube A",
+                    "type": "cubemap",
+                    "code": "void mainCubemap(out vec4 c, in vec2 p, in vec3 o, in vec3 d) { c = vec4(d, 1.0); }",
+                    "outputs": [{ "id": 41, "channel": 0 }]
+                },
+                {
+                    "name": "Image",
+                    "type": "image",
+                    "code": "void mainImage(out vec4 c, in vec2 p) { c = vec4(1.0); }",
+                    "inputs": [
+                        { "channel": 0, "ctype": "cubemap", "id": 41, "src": "/media/previz/cubemap00.png" }
+                    ]
+                }
+            ]
+        }
+        "#;
+        let payload: ShaderPayload = serde_json::from_str(json).expect("valid payload");
+        let plan = build_cache_plan(&payload).expect("cache plan");
+
+        let cube = plan
+            .manifest
+            .passes
+            .iter()
+            .find(|pass| pass.name == "cube_a")
+            .expect("cube pass");
+        assert_eq!(cube.kind, PassKind::Cubemap);
+        let image = plan
+            .manifest
+            .passes
+            .iter()
+            .find(|pass| pass.name == "image")
+            .expect("image pass");
+        assert!(matches!(
+            image.inputs[0].source,
+            InputSource::Buffer { ref name } if name == "cube_a"
+        ));
+        assert!(plan.assets.is_empty());
+    }
+
+    #[test]
+    fn maps_volume_inputs_to_bin_assets() {
+        let json = r#"
+        {
+            "info": { "name": "Clouds" },
+            "renderpass": [
+                {
+                    "name": "Image",
+                    "type": "image",
+                    "code": "void mainImage(out vec4 c, in vec2 p) { c = vec4(1.0); }",
+                    "inputs": [
+                        { "channel": 0, "ctype": "volume", "src": "/media/a/27012b4e.bin" }
+                    ]
+                }
+            ]
+        }
+        "#;
+        let payload: ShaderPayload = serde_json::from_str(json).expect("valid payload");
+        let plan = build_cache_plan(&payload).expect("cache plan");
+
+        assert!(matches!(
+            plan.manifest.passes[0].inputs[0].source,
+            InputSource::Volume { ref path } if path == Path::new("volumes/27012b4e.bin")
+        ));
+        assert_eq!(plan.assets.len(), 1);
+        assert_eq!(plan.assets[0].kind, AssetKind::Volume);
+    }
+
+    #[test]
+    fn carries_sampler_settings_into_manifest() {
+        let json = r#"
+        {
+            "info": { "name": "Noise" },
+            "renderpass": [
+                {
+                    "name": "Image",
+                    "type": "image",
+                    "code": "void mainImage(out vec4 c, in vec2 p) { c = vec4(1.0); }",
+                    "inputs": [
+                        {
+                            "channel": 0, "ctype": "texture", "src": "/media/a/noise.png",
+                            "sampler": { "filter": "nearest", "wrap": "repeat", "vflip": "false", "srgb": "false", "internal": "byte" }
+                        },
+                        {
+                            "channel": 1, "ctype": "texture", "src": "/media/a/wood.jpg",
+                            "sampler": { "filter": "mipmap", "wrap": "clamp", "vflip": true }
+                        },
+                        { "channel": 2, "ctype": "keyboard" }
+                    ]
+                }
+            ]
+        }
+        "#;
+        let payload: ShaderPayload = serde_json::from_str(json).expect("valid payload");
+        let plan = build_cache_plan(&payload).expect("cache plan");
+        let image = &plan.manifest.passes[0];
+
+        assert_eq!(
+            image.inputs[0].sampler,
+            Some(InputSampler {
+                filter: Some(SamplerFilter::Nearest),
+                wrap: Some(SamplerWrap::Repeat),
+                vflip: Some(false),
+                srgb: Some(false),
+            })
+        );
+        assert_eq!(
+            image.inputs[1].sampler,
+            Some(InputSampler {
+                filter: Some(SamplerFilter::Mipmap),
+                wrap: Some(SamplerWrap::Clamp),
+                vflip: Some(true),
+                srgb: None,
+            })
+        );
+        assert_eq!(image.inputs[2].sampler, None);
+
+        let toml = toml::to_string(&plan.manifest).expect("serialize manifest");
+        let reparsed: ShaderPackManifest = toml::from_str(&toml).expect("parse manifest");
+        assert_eq!(
+            reparsed.passes[0].inputs[0].sampler,
+            image.inputs[0].sampler
+        );
+        assert_eq!(reparsed.passes[0].inputs[2].sampler, None);
+    }
+
     #[test]
     fn sanitize_label_handles_edge_cases() {
         assert_eq!(sanitize_label("Buffer A"), Some("buffer_a".to_string()));
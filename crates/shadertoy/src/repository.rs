@@ -209,7 +209,8 @@ pub fn load_entry_shader(source: &ShaderSource) -> Result<PathBuf> {
 mod tests {
     use super::*;
     use crate::manifest::{
-        ColorSpace, InputSource, PassInput, PassKind, ShaderPackManifest, ShaderPass, SurfaceAlpha,
+        ColorSpace, InputSource, PassInput, PassKind, PassLanguage, ShaderPackManifest, ShaderPass,
+        SurfaceAlpha,
     };
     use crate::{
         materialize_shader, RenderInput, RenderOutput, RenderPass, ShaderHandle, ShaderInfo,
@@ -224,15 +225,18 @@ mod tests {
             color_space: ColorSpace::Auto,
             description: None,
             tags: vec![],
+            still_at: None,
             passes: vec![ShaderPass {
                 name: "image".into(),
                 kind: PassKind::Image,
                 source: PathBuf::from("image.glsl"),
+                language: PassLanguage::Glsl,
                 inputs: vec![PassInput {
                     channel: 0,
                     source: InputSource::Texture {
                         path: PathBuf::from("textures/tex0.png"),
                     },
+                    sampler: None,
                 }],
             }],
         };
@@ -287,6 +291,7 @@ mod tests {
                     channel: 0,
                     src: Some("/media/tex0.png".into()),
                     channel_type: "texture".into(),
+                    sampler: None,
                 }],
                 outputs: vec![RenderOutput { id: 9, channel: 0 }],
             }],
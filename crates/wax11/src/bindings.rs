@@ -13,7 +13,14 @@
 //!
 //! Functions:
 //!
-//! - `channel_bindings_from_pack` walks manifest inputs into concrete resources.
+//! - `channel_bindings_from_pack` walks manifest inputs into concrete resources, attaching
+//!   every `PassKind::Buffer` pass (with its own inputs) as an offscreen buffer pass,
+//!   the first `PassKind::Cubemap` pass as the cube render target, and the first
+//!   `PassKind::Sound` pass as the sound generator.
+//! - `live_audio_input` and `map_manifest_pcm_format` translate live PCM settings shared
+//!   by manifests and playlist `live_audio` routes.
+//! - `map_manifest_loop` translates animation loop modes for animated channels.
+//! - `map_manifest_sampler` layers manifest sampler options over the renderer defaults.
 //! - `find_cubemap_face` probes pack directories for cubemap face files.
 //! - `map_manifest_*` helpers resolve color/alpha preferences with CLI overrides through
 //!   `resolve_color_space`.
@@ -23,13 +30,18 @@
 //! - Relative asset paths are resolved under the pack root; absolute paths are used as-is.
 //! - Cubemap discovery matches face stems case-insensitively by filename stem.
 
+use std::fmt;
 use std::path::{Path, PathBuf};
 
 use renderer::{
-    ChannelBindings, ColorSpaceMode, SurfaceAlpha as RendererSurfaceAlpha, CUBEMAP_FACE_STEMS,
+    AnimationLoop, ChannelBindings, ChannelFilter, ChannelPlayback, ChannelSampler, ChannelWrap,
+    ColorSpaceMode, LiveAudio, LiveAudioInput, PcmFormat, ShaderLanguage,
+    SurfaceAlpha as RendererSurfaceAlpha, CUBEMAP_FACE_STEMS,
 };
 use shadertoy::{
-    ColorSpace as ManifestColorSpace, InputSource, LocalPack, SurfaceAlpha as ManifestSurfaceAlpha,
+    ColorSpace as ManifestColorSpace, InputSampler, InputSource, LocalPack, LoopMode, PassKind,
+    PassLanguage, PcmFormat as ManifestPcmFormat, SamplerFilter, SamplerWrap, ShaderPass,
+    SurfaceAlpha as ManifestSurfaceAlpha,
 };
 use tracing::{instrument, warn};
 
@@ -80,7 +92,14 @@ impl ChannelBindingIssue {
                 pass = %self.pass,
                 channel = self.channel,
                 buffer = %name,
-                "buffer channels are not supported yet"
+                "buffer channel references a pass that is not a buffer, cubemap, or sound pass"
+            ),
+            ChannelBindingIssueKind::BufferAssignFailed { name, error } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                buffer = %name,
+                error = %error,
+                "failed to register buffer channel"
             ),
             ChannelBindingIssueKind::CubemapDirectoryMissing { directory } => warn!(
                 pass = %self.pass,
@@ -108,11 +127,104 @@ impl ChannelBindingIssue {
                 error = %error,
                 "failed to register cubemap channel"
             ),
-            ChannelBindingIssueKind::UnsupportedAudio { path } => warn!(
+            ChannelBindingIssueKind::VolumeMissing { path } => warn!(
                 pass = %self.pass,
                 channel = self.channel,
                 path = %path.display(),
-                "audio channels are not supported yet"
+                "channel volume file not found on disk"
+            ),
+            ChannelBindingIssueKind::VolumeAssignFailed { path, error } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                path = %path.display(),
+                error = %error,
+                "failed to register volume channel"
+            ),
+            ChannelBindingIssueKind::AnimationMissing { path } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                path = %path.display(),
+                "channel animation not found on disk"
+            ),
+            ChannelBindingIssueKind::AnimationAssignFailed { path, error } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                path = %path.display(),
+                error = %error,
+                "failed to register animated channel"
+            ),
+            ChannelBindingIssueKind::AudioMissing { path } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                path = %path.display(),
+                "channel audio file not found on disk"
+            ),
+            ChannelBindingIssueKind::AudioAssignFailed { path, error } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                path = %path.display(),
+                error = %error,
+                "failed to register audio channel"
+            ),
+            ChannelBindingIssueKind::LiveAudioAssignFailed { error } => warn!(
+                pass = %self.pass,
+                channel = self.channel,
+                error = %error,
+                "failed to register live audio channel"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ChannelBindingIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pass '{}' channel {}: ", self.pass, self.channel)?;
+        match &self.kind {
+            ChannelBindingIssueKind::TextureMissing { path } => {
+                write!(f, "texture not found at {}", path.display())
+            }
+            ChannelBindingIssueKind::VolumeMissing { path } => {
+                write!(f, "volume not found at {}", path.display())
+            }
+            ChannelBindingIssueKind::AnimationMissing { path } => {
+                write!(f, "animation not found at {}", path.display())
+            }
+            ChannelBindingIssueKind::AudioMissing { path } => {
+                write!(f, "audio file not found at {}", path.display())
+            }
+            ChannelBindingIssueKind::TextureAssignFailed { path, error }
+            | ChannelBindingIssueKind::VolumeAssignFailed { path, error }
+            | ChannelBindingIssueKind::AnimationAssignFailed { path, error }
+            | ChannelBindingIssueKind::AudioAssignFailed { path, error } => {
+                write!(f, "failed to register {}: {error}", path.display())
+            }
+            ChannelBindingIssueKind::KeyboardAssignFailed { error } => {
+                write!(f, "failed to register keyboard: {error}")
+            }
+            ChannelBindingIssueKind::LiveAudioAssignFailed { error } => {
+                write!(f, "failed to register live audio: {error}")
+            }
+            ChannelBindingIssueKind::UnsupportedBuffer { name } => {
+                write!(f, "'{name}' is not a buffer, cubemap, or sound pass")
+            }
+            ChannelBindingIssueKind::BufferAssignFailed { name, error } => {
+                write!(f, "failed to register buffer '{name}': {error}")
+            }
+            ChannelBindingIssueKind::CubemapDirectoryMissing { directory } => {
+                write!(f, "cubemap directory not found at {}", directory.display())
+            }
+            ChannelBindingIssueKind::CubemapNotDirectory { path } => {
+                write!(f, "cubemap path {} is not a directory", path.display())
+            }
+            ChannelBindingIssueKind::CubemapFaceMissing { directory, face } => write!(
+                f,
+                "cubemap face '{face}' missing in {}",
+                directory.display()
+            ),
+            ChannelBindingIssueKind::CubemapAssignFailed { directory, error } => write!(
+                f,
+                "failed to register cubemap {}: {error}",
+                directory.display()
             ),
         }
     }
@@ -124,11 +236,18 @@ pub enum ChannelBindingIssueKind {
     TextureAssignFailed { path: PathBuf, error: String },
     KeyboardAssignFailed { error: String },
     UnsupportedBuffer { name: String },
+    BufferAssignFailed { name: String, error: String },
     CubemapDirectoryMissing { directory: PathBuf },
     CubemapNotDirectory { path: PathBuf },
     CubemapFaceMissing { directory: PathBuf, face: String },
     CubemapAssignFailed { directory: PathBuf, error: String },
-    UnsupportedAudio { path: PathBuf },
+    VolumeMissing { path: PathBuf },
+    VolumeAssignFailed { path: PathBuf, error: String },
+    AnimationMissing { path: PathBuf },
+    AnimationAssignFailed { path: PathBuf, error: String },
+    AudioMissing { path: PathBuf },
+    AudioAssignFailed { path: PathBuf, error: String },
+    LiveAudioAssignFailed { error: String },
 }
 
 #[instrument(level = "debug", skip(pack))]
@@ -144,7 +263,62 @@ pub fn channel_bindings_from_pack(pack: &LocalPack) -> ChannelBindingReport {
         return ChannelBindingReport { bindings, issues };
     };
 
+    bind_pass_inputs(pack, pass, &mut bindings, &mut issues);
+
+    // Buffer passes render in manifest order, matching ShaderToy's A→D ordering.
+    for buffer in manifest
+        .passes
+        .iter()
+        .filter(|candidate| candidate.kind == PassKind::Buffer && &candidate.name != entry_name)
+    {
+        let mut channels = ChannelBindings::default();
+        bind_pass_inputs(pack, buffer, &mut channels, &mut issues);
+        bindings.add_buffer_pass(
+            buffer.name.clone(),
+            pack.root().join(&buffer.source),
+            channels,
+        );
+    }
+
+    if let Some(cube) = manifest
+        .passes
+        .iter()
+        .find(|candidate| candidate.kind == PassKind::Cubemap)
+    {
+        let mut channels = ChannelBindings::default();
+        bind_pass_inputs(pack, cube, &mut channels, &mut issues);
+        bindings.set_cubemap_pass(cube.name.clone(), pack.root().join(&cube.source), channels);
+    }
+
+    if let Some(sound) = manifest
+        .passes
+        .iter()
+        .find(|candidate| candidate.kind == PassKind::Sound)
+    {
+        let mut channels = ChannelBindings::default();
+        bind_pass_inputs(pack, sound, &mut channels, &mut issues);
+        bindings.set_sound_pass(
+            sound.name.clone(),
+            pack.root().join(&sound.source),
+            channels,
+        );
+    }
+
+    ChannelBindingReport { bindings, issues }
+}
+
+fn bind_pass_inputs(
+    pack: &LocalPack,
+    pass: &ShaderPass,
+    bindings: &mut ChannelBindings,
+    issues: &mut Vec<ChannelBindingIssue>,
+) {
+    bindings.set_language(map_manifest_language(pass.language));
     for input in &pass.inputs {
+        if let Some(sampler) = input.sampler {
+            // Out-of-range channels are reported by the source binding below.
+            let _ = bindings.set_sampler(input.channel as usize, map_manifest_sampler(sampler));
+        }
         match &input.source {
             InputSource::Texture { path } => {
                 let resolved = if path.is_absolute() {
@@ -185,11 +359,37 @@ pub fn channel_bindings_from_pack(pack: &LocalPack) -> ChannelBindingReport {
                 }
             }
             InputSource::Buffer { name } => {
-                issues.push(ChannelBindingIssue {
-                    pass: pass.name.clone(),
-                    channel: input.channel,
-                    kind: ChannelBindingIssueKind::UnsupportedBuffer { name: name.clone() },
-                });
+                let target_kind = pack
+                    .passes()
+                    .find(|candidate| &candidate.name == name)
+                    .map(|candidate| candidate.kind);
+                let assigned = match target_kind {
+                    Some(PassKind::Buffer) => bindings.set_buffer(input.channel as usize, name),
+                    Some(PassKind::Cubemap) => {
+                        bindings.set_cubemap_buffer(input.channel as usize, name)
+                    }
+                    Some(PassKind::Sound) => {
+                        bindings.set_sound_buffer(input.channel as usize, name)
+                    }
+                    _ => {
+                        issues.push(ChannelBindingIssue {
+                            pass: pass.name.clone(),
+                            channel: input.channel,
+                            kind: ChannelBindingIssueKind::UnsupportedBuffer { name: name.clone() },
+                        });
+                        continue;
+                    }
+                };
+                if let Err(err) = assigned {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::BufferAssignFailed {
+                            name: name.clone(),
+                            error: err.to_string(),
+                        },
+                    });
+                }
             }
             InputSource::Cubemap { directory } => {
                 let resolved = if directory.is_absolute() {
@@ -238,17 +438,160 @@ pub fn channel_bindings_from_pack(pack: &LocalPack) -> ChannelBindingReport {
                     });
                 }
             }
+            InputSource::Volume { path } => {
+                let resolved = if path.is_absolute() {
+                    path.clone()
+                } else {
+                    pack.root().join(path)
+                };
+                if !resolved.exists() {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::VolumeMissing {
+                            path: resolved.clone(),
+                        },
+                    });
+                }
+                if let Err(err) = bindings.set_volume(input.channel as usize, resolved.clone()) {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::VolumeAssignFailed {
+                            path: resolved,
+                            error: err.to_string(),
+                        },
+                    });
+                }
+            }
+            InputSource::Animation { path, fps, looping } => {
+                let resolved = if path.is_absolute() {
+                    path.clone()
+                } else {
+                    pack.root().join(path)
+                };
+                if !resolved.exists() {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::AnimationMissing {
+                            path: resolved.clone(),
+                        },
+                    });
+                }
+                let playback = ChannelPlayback {
+                    fps: *fps,
+                    looping: map_manifest_loop(*looping),
+                };
+                if let Err(err) =
+                    bindings.set_animation(input.channel as usize, resolved.clone(), playback)
+                {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::AnimationAssignFailed {
+                            path: resolved,
+                            error: err.to_string(),
+                        },
+                    });
+                }
+            }
             InputSource::Audio { path } => {
-                issues.push(ChannelBindingIssue {
-                    pass: pass.name.clone(),
-                    channel: input.channel,
-                    kind: ChannelBindingIssueKind::UnsupportedAudio { path: path.clone() },
-                });
+                let resolved = if path.is_absolute() {
+                    path.clone()
+                } else {
+                    pack.root().join(path)
+                };
+                if !resolved.exists() {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::AudioMissing {
+                            path: resolved.clone(),
+                        },
+                    });
+                }
+                if let Err(err) = bindings.set_audio(input.channel as usize, resolved.clone()) {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::AudioAssignFailed {
+                            path: resolved,
+                            error: err.to_string(),
+                        },
+                    });
+                }
+            }
+            InputSource::Live {
+                fifo,
+                sample_rate,
+                channels,
+                format,
+            } => {
+                let live = LiveAudio {
+                    input: live_audio_input(fifo.clone()),
+                    sample_rate: *sample_rate,
+                    channels: *channels,
+                    format: map_manifest_pcm_format(*format),
+                };
+                if let Err(err) = bindings.set_live_audio(input.channel as usize, live) {
+                    issues.push(ChannelBindingIssue {
+                        pass: pass.name.clone(),
+                        channel: input.channel,
+                        kind: ChannelBindingIssueKind::LiveAudioAssignFailed {
+                            error: err.to_string(),
+                        },
+                    });
+                }
             }
         }
     }
+}
 
-    ChannelBindingReport { bindings, issues }
+/// Stdin when no FIFO is configured, otherwise the named pipe.
+pub fn live_audio_input(fifo: Option<PathBuf>) -> LiveAudioInput {
+    fifo.map_or(LiveAudioInput::Stdin, LiveAudioInput::Fifo)
+}
+
+pub fn map_manifest_pcm_format(format: ManifestPcmFormat) -> PcmFormat {
+    match format {
+        ManifestPcmFormat::S16le => PcmFormat::S16Le,
+        ManifestPcmFormat::F32le => PcmFormat::F32Le,
+    }
+}
+
+pub fn map_manifest_language(language: PassLanguage) -> ShaderLanguage {
+    match language {
+        PassLanguage::Glsl => ShaderLanguage::Glsl,
+        PassLanguage::Wgsl => ShaderLanguage::Wgsl,
+    }
+}
+
+pub fn map_manifest_loop(looping: LoopMode) -> AnimationLoop {
+    match looping {
+        LoopMode::Repeat => AnimationLoop::Repeat,
+        LoopMode::Once => AnimationLoop::Once,
+        LoopMode::Pingpong => AnimationLoop::PingPong,
+    }
+}
+
+pub fn map_manifest_sampler(sampler: InputSampler) -> ChannelSampler {
+    let defaults = ChannelSampler::default();
+    ChannelSampler {
+        filter: sampler
+            .filter
+            .map_or(defaults.filter, |filter| match filter {
+                SamplerFilter::Nearest => ChannelFilter::Nearest,
+                SamplerFilter::Linear => ChannelFilter::Linear,
+                SamplerFilter::Mipmap => ChannelFilter::Mipmap,
+            }),
+        wrap: sampler.wrap.map_or(defaults.wrap, |wrap| match wrap {
+            SamplerWrap::Clamp => ChannelWrap::Clamp,
+            SamplerWrap::Repeat => ChannelWrap::Repeat,
+        }),
+        vflip: sampler.vflip.unwrap_or(defaults.vflip),
+        srgb: sampler.srgb.or(defaults.srgb),
+    }
 }
 
 fn find_cubemap_face(directory: &Path, face: &str) -> Option<PathBuf> {
@@ -309,7 +652,7 @@ pub fn resolve_color_space(cli: ColorSpaceMode, manifest: ColorSpaceMode) -> Col
 mod tests {
     use super::*;
     use shadertoy::{
-        ColorSpace as ManifestColor, InputSource, LocalPack, PassInput, PassKind,
+        ColorSpace as ManifestColor, InputSource, LocalPack, PassInput, PassKind, PassLanguage,
         ShaderPackManifest, ShaderPass, SurfaceAlpha as ManifestAlpha,
     };
     use std::fs;
@@ -334,10 +677,12 @@ mod tests {
             color_space: ManifestColor::Auto,
             description: None,
             tags: vec![],
+            still_at: None,
             passes: vec![ShaderPass {
                 name: "image".into(),
                 kind: PassKind::Image,
                 source: PathBuf::from("image.glsl"),
+                language: PassLanguage::Glsl,
                 inputs,
             }],
         }
@@ -392,6 +737,7 @@ mod tests {
             source: InputSource::Texture {
                 path: PathBuf::from("textures/tex0.png"),
             },
+            sampler: None,
         }];
         let manifest = demo_manifest_with_inputs(inputs);
         write_pack(temp.path(), &manifest, &[("image.glsl", "// shader")]);
@@ -404,6 +750,168 @@ mod tests {
             .any(|i| matches!(i.kind, ChannelBindingIssueKind::TextureMissing { ref path } if path.ends_with("textures/tex0.png"))));
     }
 
+    #[test]
+    fn binds_audio_channel_and_reports_missing_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let inputs = vec![
+            PassInput {
+                channel: 0,
+                source: InputSource::Audio {
+                    path: PathBuf::from("audio/track0.ogg"),
+                },
+                sampler: None,
+            },
+            PassInput {
+                channel: 1,
+                source: InputSource::Audio {
+                    path: PathBuf::from("audio/missing.mp3"),
+                },
+                sampler: None,
+            },
+        ];
+        let manifest = demo_manifest_with_inputs(inputs);
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("audio/track0.ogg", "")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(matches!(
+            report.bindings.channel(0),
+            Some(renderer::ChannelSource::Audio { path }) if path.ends_with("audio/track0.ogg")
+        ));
+        assert_eq!(report.issues.len(), 1);
+        assert!(matches!(
+            report.issues[0].kind,
+            ChannelBindingIssueKind::AudioMissing { ref path } if path.ends_with("audio/missing.mp3")
+        ));
+    }
+
+    #[test]
+    fn binds_live_audio_from_stdin_by_default() {
+        let temp = tempfile::tempdir().unwrap();
+        let inputs = vec![PassInput {
+            channel: 3,
+            source: InputSource::Live {
+                fifo: None,
+                sample_rate: 48_000,
+                channels: 2,
+                format: shadertoy::PcmFormat::S16le,
+            },
+            sampler: None,
+        }];
+        let manifest = demo_manifest_with_inputs(inputs);
+        write_pack(temp.path(), &manifest, &[("image.glsl", "// shader")]);
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        assert!(matches!(
+            report.bindings.channel(3),
+            Some(renderer::ChannelSource::LiveAudio(LiveAudio {
+                input: LiveAudioInput::Stdin,
+                sample_rate: 48_000,
+                channels: 2,
+                format: PcmFormat::S16Le,
+            }))
+        ));
+    }
+
+    #[test]
+    fn binds_volume_channel_as_3d_texture() {
+        let temp = tempfile::tempdir().unwrap();
+        let inputs = vec![PassInput {
+            channel: 2,
+            source: InputSource::Volume {
+                path: PathBuf::from("volumes/noise.bin"),
+            },
+            sampler: None,
+        }];
+        let manifest = demo_manifest_with_inputs(inputs);
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("volumes/noise.bin", "")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        assert_eq!(
+            report.bindings.layout_signature()[2],
+            renderer::ChannelTextureKind::Texture3d
+        );
+    }
+
+    #[test]
+    fn binds_animation_with_playback_settings() {
+        let temp = tempfile::tempdir().unwrap();
+        let inputs = vec![PassInput {
+            channel: 0,
+            source: InputSource::Animation {
+                path: PathBuf::from("frames"),
+                fps: Some(12.0),
+                looping: LoopMode::Pingpong,
+            },
+            sampler: None,
+        }];
+        let manifest = demo_manifest_with_inputs(inputs);
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("frames/0001.png", "")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        assert!(matches!(
+            report.bindings.channel(0),
+            Some(renderer::ChannelSource::Animation { path, playback })
+                if path.ends_with("frames")
+                    && *playback == ChannelPlayback { fps: Some(12.0), looping: AnimationLoop::PingPong }
+        ));
+    }
+
+    #[test]
+    fn applies_manifest_sampler_over_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        let inputs = vec![PassInput {
+            channel: 1,
+            source: InputSource::Texture {
+                path: PathBuf::from("textures/noise.png"),
+            },
+            sampler: Some(InputSampler {
+                filter: Some(SamplerFilter::Nearest),
+                wrap: Some(SamplerWrap::Repeat),
+                vflip: Some(false),
+                srgb: None,
+            }),
+        }];
+        let manifest = demo_manifest_with_inputs(inputs);
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("textures/noise.png", "")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        assert_eq!(
+            report.bindings.sampler(1),
+            ChannelSampler {
+                filter: ChannelFilter::Nearest,
+                wrap: ChannelWrap::Repeat,
+                vflip: false,
+                srgb: None,
+            }
+        );
+        assert_eq!(report.bindings.sampler(0), ChannelSampler::default());
+    }
+
     #[test]
     fn finds_cubemap_faces_case_insensitive() {
         let temp = tempfile::tempdir().unwrap();
@@ -429,6 +937,7 @@ mod tests {
             source: InputSource::Cubemap {
                 directory: PathBuf::from("cube"),
             },
+            sampler: None,
         }];
         let manifest = demo_manifest_with_inputs(inputs);
         write_pack(
@@ -444,4 +953,136 @@ mod tests {
             .iter()
             .any(|i| matches!(i.kind, ChannelBindingIssueKind::CubemapFaceMissing { .. })));
     }
+
+    #[test]
+    fn binds_buffer_passes_with_feedback() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut manifest = demo_manifest_with_inputs(vec![PassInput {
+            channel: 0,
+            source: InputSource::Buffer {
+                name: "buffer_a".into(),
+            },
+            sampler: None,
+        }]);
+        manifest.passes.push(ShaderPass {
+            name: "buffer_a".into(),
+            kind: PassKind::Buffer,
+            source: PathBuf::from("buffer_a.glsl"),
+            language: PassLanguage::Glsl,
+            inputs: vec![PassInput {
+                channel: 1,
+                source: InputSource::Buffer {
+                    name: "buffer_a".into(),
+                },
+                sampler: None,
+            }],
+        });
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("buffer_a.glsl", "// buffer")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        let buffers = report.bindings.buffer_passes();
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(buffers[0].name, "buffer_a");
+        assert!(buffers[0].source.ends_with("buffer_a.glsl"));
+        assert!(buffers[0].channels.buffer_passes().is_empty());
+    }
+
+    #[test]
+    fn carries_pass_languages_into_bindings() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut manifest = demo_manifest_with_inputs(vec![]);
+        manifest.passes.push(ShaderPass {
+            name: "buffer_a".into(),
+            kind: PassKind::Buffer,
+            source: PathBuf::from("buffer_a.wgsl"),
+            language: PassLanguage::Wgsl,
+            inputs: vec![],
+        });
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("buffer_a.wgsl", "// buffer")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert_eq!(report.bindings.language(), ShaderLanguage::Glsl);
+        let buffers = report.bindings.buffer_passes();
+        assert_eq!(buffers[0].channels.language(), ShaderLanguage::Wgsl);
+    }
+
+    #[test]
+    fn binds_cubemap_pass_as_cube_channel() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut manifest = demo_manifest_with_inputs(vec![PassInput {
+            channel: 2,
+            source: InputSource::Buffer {
+                name: "cube_a".into(),
+            },
+            sampler: None,
+        }]);
+        manifest.passes.push(ShaderPass {
+            name: "cube_a".into(),
+            kind: PassKind::Cubemap,
+            source: PathBuf::from("cube_a.glsl"),
+            language: PassLanguage::Glsl,
+            inputs: vec![],
+        });
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("cube_a.glsl", "// cube")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        assert!(report.bindings.buffer_passes().is_empty());
+        let cube = report.bindings.cubemap_pass().expect("cubemap pass bound");
+        assert!(cube.source.ends_with("cube_a.glsl"));
+        assert_eq!(
+            report.bindings.layout_signature()[2],
+            renderer::ChannelTextureKind::Cubemap
+        );
+    }
+
+    #[test]
+    fn binds_sound_pass_and_channels_reading_it() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut manifest = demo_manifest_with_inputs(vec![PassInput {
+            channel: 3,
+            source: InputSource::Buffer {
+                name: "sound".into(),
+            },
+            sampler: None,
+        }]);
+        manifest.passes.push(ShaderPass {
+            name: "sound".into(),
+            kind: PassKind::Sound,
+            source: PathBuf::from("sound.glsl"),
+            language: PassLanguage::Glsl,
+            inputs: vec![],
+        });
+        write_pack(
+            temp.path(),
+            &manifest,
+            &[("image.glsl", "// shader"), ("sound.glsl", "// sound")],
+        );
+        let pack = LocalPack::load(temp.path()).expect("load pack");
+
+        let report = channel_bindings_from_pack(&pack);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
+        let sound = report.bindings.sound_pass().expect("sound pass bound");
+        assert!(sound.source.ends_with("sound.glsl"));
+        assert!(matches!(
+            report.bindings.channel(3),
+            Some(renderer::ChannelSource::SoundPass { name }) if name == "sound"
+        ));
+    }
 }
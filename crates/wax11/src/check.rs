@@ -0,0 +1,293 @@
+//! Backs `wax11 check`, a CI gate for shader repositories: every pack under the shader
+//! search roots (or the given paths) is loaded, linted, and compiled with each available
+//! GLSL backend through `renderer::check_shader`, without opening a window or touching
+//! the GPU.
+//!
+//! Types:
+//!
+//! - `PackReport` and `PassReport` carry per-pack status and diagnostics for the table
+//!   and JSON outputs.
+//!
+//! Functions:
+//!
+//! - `run_check` resolves packs, prints the report, and fails when any pack failed.
+//! - `collect_packs` expands pack directories and search roots into pack paths.
+//! - `check_pack` runs manifest validation (`LocalPack::load` applies
+//!   `ShaderPackManifest::validate`), `ensure_glsl_sources`, `channel_bindings_from_pack`,
+//!   and the headless compile for one pack.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use renderer::{PassCheck, ShaderCheckConfig, ShaderCompileError, ShaderCompiler};
+use serde::Serialize;
+use shadertoy::{ensure_glsl_sources, resolve_entry_source, LocalPack, PackError};
+
+use crate::bindings::channel_bindings_from_pack;
+use crate::cli::{CheckCommand, CheckFormat};
+use crate::paths::AppPaths;
+
+#[derive(Debug, Serialize)]
+pub struct PackReport {
+    pub name: String,
+    pub path: PathBuf,
+    pub passed: bool,
+    /// Manifest, source, and channel binding problems.
+    pub errors: Vec<String>,
+    pub passes: Vec<PassReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PassReport {
+    pub pass: String,
+    pub compiler: &'static str,
+    pub passed: bool,
+    pub diagnostics: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl From<PassCheck> for PassReport {
+    fn from(check: PassCheck) -> Self {
+        let diagnostics = match &check.error {
+            Some(err) => match err.downcast_ref::<ShaderCompileError>() {
+                Some(compile) => compile
+                    .diagnostics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                None => vec![format!("{err:#}")],
+            },
+            None => Vec::new(),
+        };
+        Self {
+            passed: check.passed(),
+            pass: check.pass,
+            compiler: check.frontend,
+            diagnostics,
+            warnings: check.warnings,
+        }
+    }
+}
+
+pub fn run_check(command: CheckCommand) -> Result<()> {
+    let paths = AppPaths::discover()?;
+    let roots = if command.paths.is_empty() {
+        paths.shader_roots()
+    } else {
+        command.paths.clone()
+    };
+    let packs = collect_packs(&roots);
+    if packs.is_empty() {
+        anyhow::bail!("no shader packs found to check");
+    }
+
+    let compilers = if cfg!(feature = "shaderc") {
+        vec![ShaderCompiler::Shaderc, ShaderCompiler::NagaGlsl]
+    } else {
+        vec![ShaderCompiler::NagaGlsl]
+    };
+    let include_dirs = paths.shader_include_dirs();
+    let reports: Vec<PackReport> = packs
+        .iter()
+        .map(|pack| check_pack(pack, &include_dirs, &compilers))
+        .collect();
+
+    match command.format {
+        CheckFormat::Table => print_table(&reports),
+        CheckFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&reports).context("failed to encode check report")?
+        ),
+    }
+
+    let failed = reports.iter().filter(|report| !report.passed).count();
+    if failed > 0 {
+        anyhow::bail!(
+            "{failed} of {} shader packs failed the check",
+            reports.len()
+        );
+    }
+    Ok(())
+}
+
+/// Pack directories named directly, plus every pack one level below the others.
+/// Missing roots are skipped so unused search roots do not fail the check.
+pub fn collect_packs(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut packs = Vec::new();
+    for root in roots {
+        if root.join("shader.toml").is_file() {
+            packs.push(root.clone());
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(root) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join("shader.toml").is_file())
+            .collect();
+        found.sort();
+        packs.extend(found);
+    }
+    packs
+}
+
+pub fn check_pack(
+    path: &Path,
+    include_dirs: &[PathBuf],
+    compilers: &[ShaderCompiler],
+) -> PackReport {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let mut report = PackReport {
+        name,
+        path: path.to_path_buf(),
+        passed: false,
+        errors: Vec::new(),
+        passes: Vec::new(),
+    };
+
+    let pack = match LocalPack::load(path) {
+        Ok(pack) => pack,
+        Err(err) => {
+            report.errors = pack_errors(err);
+            return report;
+        }
+    };
+    if let Err(err) = ensure_glsl_sources(&pack) {
+        report.errors = pack_errors(err);
+        return report;
+    }
+
+    let channels = channel_bindings_from_pack(&pack);
+    report
+        .errors
+        .extend(channels.issues.iter().map(ToString::to_string));
+    let shader_source = match resolve_entry_source(&pack) {
+        Ok(source) => source,
+        Err(err) => {
+            report.errors.push(format!("{err:#}"));
+            return report;
+        }
+    };
+
+    report.passes = renderer::check_shader(&ShaderCheckConfig {
+        shader_source,
+        channel_bindings: channels.bindings,
+        shader_include_dirs: include_dirs.to_vec(),
+        compilers: compilers.to_vec(),
+    })
+    .into_iter()
+    .map(PassReport::from)
+    .collect();
+    report.passed = report.errors.is_empty() && report.passes.iter().all(|pass| pass.passed);
+    report
+}
+
+fn pack_errors(err: PackError) -> Vec<String> {
+    match err {
+        PackError::ManifestValidation(issues) => issues,
+        other => vec![other.to_string()],
+    }
+}
+
+fn print_table(reports: &[PackReport]) {
+    let width = reports
+        .iter()
+        .map(|report| report.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("PACK".len());
+    println!("{:<width$}  STATUS  CHECKS", "PACK");
+    for report in reports {
+        let status = if report.passed { "ok" } else { "FAIL" };
+        let checks = report
+            .passes
+            .iter()
+            .map(|pass| {
+                let mark = if pass.passed { "ok" } else { "failed" };
+                format!("{} [{}] {mark}", pass.pass, pass.compiler)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("{:<width$}  {status:<6}  {checks}", report.name);
+    }
+
+    for report in reports {
+        let has_details = !report.errors.is_empty()
+            || report
+                .passes
+                .iter()
+                .any(|pass| !pass.diagnostics.is_empty() || !pass.warnings.is_empty());
+        if !has_details {
+            continue;
+        }
+        println!();
+        println!("{} ({})", report.name, report.path.display());
+        for error in &report.errors {
+            println!("  error: {error}");
+        }
+        for pass in &report.passes {
+            for diagnostic in &pass.diagnostics {
+                println!("  {} [{}]:", pass.pass, pass.compiler);
+                for line in diagnostic.lines() {
+                    println!("    {line}");
+                }
+            }
+            for warning in &pass.warnings {
+                println!("  {} [{}]: warning: {warning}", pass.pass, pass.compiler);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_pack(root: &Path, name: &str, image: &str) -> PathBuf {
+        let pack = root.join(name);
+        std::fs::create_dir_all(&pack).unwrap();
+        std::fs::write(
+            pack.join("shader.toml"),
+            "name = \"test\"\nentry = \"image\"\n\n[[passes]]\nname = \"image\"\nkind = \"image\"\nsource = \"image.glsl\"\n",
+        )
+        .unwrap();
+        std::fs::write(pack.join("image.glsl"), image).unwrap();
+        pack
+    }
+
+    #[test]
+    fn reports_passing_and_failing_packs() {
+        let root = tempfile::tempdir().unwrap();
+        let good = write_pack(
+            root.path(),
+            "good",
+            "void mainImage(out vec4 c, in vec2 p) { c = vec4(p / iResolution.xy, 0.0, 1.0); }\n",
+        );
+        write_pack(
+            root.path(),
+            "broken",
+            "void mainImage(out vec4 c, in vec2 p) {\n    c = vec4(missing);\n}\n",
+        );
+        std::fs::create_dir_all(root.path().join("lib")).unwrap();
+
+        let packs = collect_packs(&[root.path().to_path_buf(), root.path().join("absent")]);
+        assert_eq!(packs.len(), 2);
+        assert_eq!(collect_packs(std::slice::from_ref(&good)), vec![good]);
+
+        let compilers = [ShaderCompiler::NagaGlsl];
+        let broken = check_pack(&packs[0], &[], &compilers);
+        let good = check_pack(&packs[1], &[], &compilers);
+        assert!(good.passed, "{good:?}");
+        assert!(!broken.passed);
+        let diagnostic = &broken.passes[0].diagnostics[0];
+        assert!(diagnostic.contains("image.glsl:2"), "{diagnostic}");
+
+        let json = serde_json::to_value(&broken).unwrap();
+        assert_eq!(json["passes"][0]["compiler"], "naga glsl");
+    }
+}
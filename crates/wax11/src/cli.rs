@@ -6,6 +6,7 @@
 //! Types:
 //!
 //! - `Cli`, `RunArgs`, and subcommand structs underpin the main entry point.
+//! - `CheckFormat` selects the `wax11 check` report format.
 //! - GPU tuning enums (`GpuPowerPreference`, `GpuMemoryMode`) expose resource policies.
 //!
 //! Functions:
@@ -15,15 +16,34 @@
 //!   results.
 
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use clap::{Parser, Subcommand};
 use renderer::{
     Antialiasing, ColorSpaceMode, CrossfadeCurve, ExportFormat, FillMethod, ShaderCompiler,
-    VsyncMode,
+    UpscaleFilter, VsyncMode,
 };
 
 use crate::handles::{LaunchHandleArg, PlaylistHandleArg};
 
+/// Frame step for `--export-sequence` when `--every` is omitted.
+pub const DEFAULT_SEQUENCE_STEP: f32 = 1.0 / 60.0;
+
+/// Frame time `--dynamic-scale` holds when neither `--frame-budget` nor `--fps` is set.
+pub const DEFAULT_FRAME_BUDGET: f32 = 1.0 / 60.0;
+
+/// Render scale of the first `--progressive` pass when `--progressive-start` is omitted.
+pub const DEFAULT_PROGRESSIVE_START: f32 = 0.25;
+
+/// Full-resolution samples `--progressive` averages when `--progressive-samples` is omitted.
+pub const DEFAULT_PROGRESSIVE_SAMPLES: u32 = 8;
+
+/// Frame rate for `.gif`/`.apng` loop exports when `--loop-fps` is omitted.
+pub const DEFAULT_LOOP_FPS: f32 = 30.0;
+
+/// Length of `.gif`/`.apng` loop exports when `--loop-duration` is omitted.
+pub const DEFAULT_LOOP_DURATION: Duration = Duration::from_secs(4);
+
 #[derive(Parser, Debug)]
 #[command(
     name = "wax11",
@@ -65,18 +85,74 @@ pub struct RunArgs {
     #[arg(long, value_name = "SECONDS|auto")]
     pub still_time: Option<String>,
 
-    /// Export a still frame to the provided PNG path then exit.
+    /// Refine the `--still` frame over several passes, starting with a fast low-scale one.
+    #[arg(long)]
+    pub progressive: bool,
+
+    /// Render scale of the first `--progressive` pass (0.1-1.0; default 0.25).
+    #[arg(long, value_name = "SCALE")]
+    pub progressive_start: Option<f32>,
+
+    /// Jittered full-resolution samples `--progressive` averages into the final frame (default 8).
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=256))]
+    pub progressive_samples: Option<u32>,
+
+    /// Export a still frame (`.png`, `.exr`) or an animated loop (`.gif`, `.apng`) then exit.
     #[arg(long, value_name = "PATH")]
     pub still_export: Option<PathBuf>,
 
+    /// Resolution multiplier for `--still-export` over the surface size (0.25-8.0).
+    #[arg(long, value_name = "FACTOR")]
+    pub export_scale: Option<f32>,
+
+    /// Write N numbered frames (`out-0000.png`, ...) to the `--still-export` path.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub export_sequence: Option<u32>,
+
+    /// Time between `--export-sequence` frames (`1/60`, `0.5`, or `40ms`; default `1/60`).
+    #[arg(long, value_name = "STEP", value_parser = parse_time_step)]
+    pub every: Option<f32>,
+
+    /// Frame rate of a `.gif`/`.apng` loop export (default 30).
+    #[arg(long, value_name = "FPS")]
+    pub loop_fps: Option<f32>,
+
+    /// Length of a `.gif`/`.apng` loop export (`4s`, `1500ms`, or plain seconds; default `4s`).
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub loop_duration: Option<Duration>,
+
+    /// Crossfade the frames after the loop into its first frames so it wraps seamlessly.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub loop_crossfade: Option<Duration>,
+
     /// Control whether the process exits automatically after a still export (`true` by default).
     #[arg(long, value_name = "BOOL")]
     pub still_exit: Option<bool>,
 
+    /// Render the shader's sound pass to the provided WAV path then exit.
+    #[arg(long, value_name = "PATH")]
+    pub export_audio: Option<PathBuf>,
+
+    /// Length of audio to render with `--export-audio` (`30s`, `2m`, or plain seconds).
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, default_value = "30s")]
+    pub duration: Duration,
+
     /// Supersampling factor to render at before presenting (0.25-1.0).
     #[arg(long, value_name = "SCALE")]
     pub render_scale: Option<f32>,
 
+    /// Filter that stretches a `--render-scale` below 1.0 to the output: `nearest`, `bilinear` (default), or `sharpen`.
+    #[arg(long, value_name = "FILTER", value_parser = parse_upscale_filter)]
+    pub upscale_filter: Option<UpscaleFilter>,
+
+    /// Adjust the render scale between MIN and MAX (e.g. `0.4-1.0`) to hold `--frame-budget`.
+    #[arg(long, value_name = "MIN-MAX", value_parser = parse_scale_range)]
+    pub dynamic_scale: Option<(f32, f32)>,
+
+    /// Frame time `--dynamic-scale` holds (`16ms`, `1/60`; defaults to `1/--fps`, else `1/60`).
+    #[arg(long, value_name = "DURATION", value_parser = parse_time_step)]
+    pub frame_budget: Option<f32>,
+
     /// How shader coordinates map to the surface (`stretch`, `center:WIDTHxHEIGHT`, `tile[:XxY]`).
     #[arg(long, value_name = "MODE", value_parser = parse_fill_method)]
     pub fill_method: Option<FillMethod>,
@@ -118,7 +194,8 @@ pub struct RunArgs {
     )]
     pub antialias: Antialiasing,
 
-    /// Shader compiler backend: `shaderc` (default) or `naga`.
+    /// Shader compiler backend: `shaderc` (default), `naga`, or `auto` (try the
+    /// preferred backend and fall back to the other when a shader fails).
     #[arg(
         long,
         value_name = "COMPILER",
@@ -202,6 +279,31 @@ pub enum GpuMemoryMode {
 pub enum Command {
     /// Manage bundled defaults (shader packs, playlists, paths).
     Defaults(DefaultsCommand),
+    /// Validate and compile every shader pack without opening a window.
+    Check(CheckCommand),
+}
+
+#[derive(Parser, Debug)]
+pub struct CheckCommand {
+    /// Packs or directories of packs to check (defaults to every shader search root).
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+
+    /// Report format: `table` (default) or `json`.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = parse_check_format,
+        default_value = "table"
+    )]
+    pub format: CheckFormat,
+}
+
+/// Output format of `wax11 check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckFormat {
+    Table,
+    Json,
 }
 
 #[derive(Parser, Debug)]
@@ -250,6 +352,14 @@ pub fn parse_antialias(value: &str) -> Result<Antialiasing, String> {
     }
 }
 
+pub fn parse_check_format(value: &str) -> Result<CheckFormat, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "table" | "text" => Ok(CheckFormat::Table),
+        "json" => Ok(CheckFormat::Json),
+        other => Err(format!("invalid check format '{other}'; use table or json")),
+    }
+}
+
 pub fn parse_shader_compiler(value: &str) -> Result<ShaderCompiler, String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -266,7 +376,8 @@ pub fn parse_shader_compiler(value: &str) -> Result<ShaderCompiler, String> {
             }
         }
         "naga" | "naga-glsl" => Ok(ShaderCompiler::NagaGlsl),
-        _ => Err("unknown shader compiler (expected shaderc or naga)".to_string()),
+        "auto" => Ok(ShaderCompiler::Auto),
+        _ => Err("unknown shader compiler (expected shaderc, naga, or auto)".to_string()),
     }
 }
 
@@ -304,6 +415,39 @@ pub fn parse_crossfade_curve(value: &str) -> Result<CrossfadeCurve, String> {
     }
 }
 
+pub fn parse_upscale_filter(value: &str) -> Result<UpscaleFilter, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "nearest" => Ok(UpscaleFilter::Nearest),
+        "bilinear" | "linear" => Ok(UpscaleFilter::Bilinear),
+        "sharpen" | "fsr" => Ok(UpscaleFilter::Sharpen),
+        "" => Err("upscale filter must not be empty".to_string()),
+        other => Err(format!(
+            "unknown upscale filter '{other}'; expected nearest, bilinear, or sharpen"
+        )),
+    }
+}
+
+/// Parses a `MIN-MAX` render scale range within 0.25-1.0.
+pub fn parse_scale_range(value: &str) -> Result<(f32, f32), String> {
+    let (min, max) = value
+        .trim()
+        .split_once('-')
+        .ok_or_else(|| format!("scale range '{value}' must look like MIN-MAX, e.g. 0.4-1.0"))?;
+    let parse = |part: &str| {
+        part.trim()
+            .parse::<f32>()
+            .map_err(|_| format!("invalid scale '{part}' in range '{value}'"))
+    };
+    let (min, max) = (parse(min)?, parse(max)?);
+    if !(0.25..=1.0).contains(&min) || !(0.25..=1.0).contains(&max) {
+        return Err("scale range bounds must be between 0.25 and 1.0".to_string());
+    }
+    if min > max {
+        return Err(format!("scale range '{value}' has MIN above MAX"));
+    }
+    Ok((min, max))
+}
+
 pub fn parse_export_format(path: &Path) -> Result<ExportFormat, String> {
     match path
         .extension()
@@ -312,14 +456,57 @@ pub fn parse_export_format(path: &Path) -> Result<ExportFormat, String> {
         .as_deref()
     {
         Some("png") => Ok(ExportFormat::Png),
-        Some("exr") => Err("EXR export is not implemented yet; use a .png path".to_string()),
-        None => Err("export path has no extension; expected .png".to_string()),
+        Some("exr") => Ok(ExportFormat::Exr),
+        Some("gif") => Ok(ExportFormat::Gif),
+        Some("apng") => Ok(ExportFormat::Apng),
+        None => Err("export path has no extension; expected .png, .exr, .gif or .apng".to_string()),
         Some(other) => Err(format!(
-            "unsupported export format '.{other}'; expected .png"
+            "unsupported export format '.{other}'; expected .png, .exr, .gif or .apng"
         )),
     }
 }
 
+/// Parses a frame step in seconds: a fraction (`1/60`), or anything `parse_duration` accepts.
+pub fn parse_time_step(value: &str) -> Result<f32, String> {
+    let trimmed = value.trim();
+    let seconds = match trimmed.split_once('/') {
+        Some((numerator, denominator)) => {
+            let parse = |part: &str| {
+                part.trim()
+                    .parse::<f32>()
+                    .map_err(|_| format!("invalid frame step '{trimmed}'; expected e.g. 1/60"))
+            };
+            parse(numerator)? / parse(denominator)?
+        }
+        None => parse_duration(trimmed)?.as_secs_f32(),
+    };
+    if seconds.is_finite() && seconds > 0.0 {
+        Ok(seconds)
+    } else {
+        Err(format!(
+            "frame step '{trimmed}' must be a positive duration"
+        ))
+    }
+}
+
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err("duration must not be empty".into());
+    }
+
+    let duration = match trimmed.parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Duration::from_secs_f64(seconds),
+        Ok(_) => return Err(format!("invalid duration '{trimmed}'")),
+        Err(_) => humantime::parse_duration(trimmed)
+            .map_err(|err| format!("invalid duration '{trimmed}': {err}"))?,
+    };
+    if duration.is_zero() {
+        return Err("duration must be greater than zero".into());
+    }
+    Ok(duration)
+}
+
 pub fn parse_fill_method(value: &str) -> Result<FillMethod, String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -474,4 +661,67 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn parses_durations_with_units_or_plain_seconds() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("1m 30s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("2.5").unwrap(), Duration::from_millis(2500));
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+
+    #[test]
+    fn parses_frame_steps_as_fractions_or_durations() {
+        assert_eq!(parse_time_step("1/60").unwrap(), 1.0 / 60.0);
+        assert_eq!(parse_time_step("0.5").unwrap(), 0.5);
+        assert_eq!(parse_time_step("40ms").unwrap(), 0.04);
+        assert!(parse_time_step("1/0").is_err());
+        assert!(parse_time_step("0").is_err());
+        assert!(parse_time_step("a/60").is_err());
+    }
+
+    #[test]
+    fn parses_export_formats_from_extensions() {
+        assert_eq!(
+            parse_export_format(Path::new("still.PNG")).unwrap(),
+            ExportFormat::Png
+        );
+        assert_eq!(
+            parse_export_format(Path::new("grade/still.exr")).unwrap(),
+            ExportFormat::Exr
+        );
+        assert_eq!(
+            parse_export_format(Path::new("loop.GIF")).unwrap(),
+            ExportFormat::Gif
+        );
+        assert_eq!(
+            parse_export_format(Path::new("loop.apng")).unwrap(),
+            ExportFormat::Apng
+        );
+        assert!(parse_export_format(Path::new("still.jpg")).is_err());
+        assert!(parse_export_format(Path::new("still")).is_err());
+    }
+
+    #[test]
+    fn parses_upscale_filters() {
+        assert_eq!(
+            parse_upscale_filter("Sharpen").unwrap(),
+            UpscaleFilter::Sharpen
+        );
+        assert_eq!(
+            parse_upscale_filter("nearest").unwrap(),
+            UpscaleFilter::Nearest
+        );
+        assert!(parse_upscale_filter("lanczos").is_err());
+    }
+
+    #[test]
+    fn parses_scale_ranges() {
+        assert_eq!(parse_scale_range("0.4-1.0").unwrap(), (0.4, 1.0));
+        assert_eq!(parse_scale_range(" 0.5 - 0.5 ").unwrap(), (0.5, 0.5));
+        assert!(parse_scale_range("1.0-0.4").is_err());
+        assert!(parse_scale_range("0.1-1.0").is_err());
+        assert!(parse_scale_range("0.5").is_err());
+    }
 }
@@ -14,7 +14,7 @@
 //!   formats, and manifest overlays.
 
 use anyhow::Context;
-use multiconfig::MultiConfig;
+use multiconfig::{MultiConfig, StillTime};
 use renderer::{Antialiasing, ColorSpaceMode, FillMethod, ShaderCompiler};
 use shadertoy::{PathResolver, ShaderHandle, ShaderRepository, ShaderSource, ShadertoyClient};
 use tracing::info;
@@ -63,7 +63,48 @@ fn dump_global_options(args: &RunArgs, paths: &AppPaths) {
             .map(|p| p.display().to_string())
             .unwrap_or_else(|| "none".to_string())
     );
+    if let Some(scale) = args.export_scale {
+        info!("  export-scale: {scale}");
+    }
+    if let Some(frames) = args.export_sequence {
+        info!(
+            "  export-sequence: {frames} frames every {}s",
+            args.every.unwrap_or(crate::cli::DEFAULT_SEQUENCE_STEP)
+        );
+    }
+    if let Some(fps) = args.loop_fps {
+        info!("  loop-fps: {fps}");
+    }
+    if let Some(duration) = args.loop_duration {
+        info!("  loop-duration: {duration:?}");
+    }
+    if let Some(fade) = args.loop_crossfade {
+        info!("  loop-crossfade: {fade:?}");
+    }
+    if let Some(path) = &args.export_audio {
+        info!("  export-audio: {} ({:?})", path.display(), args.duration);
+    }
+    if args.progressive {
+        info!(
+            "  progressive: from {} scale, {} samples",
+            args.progressive_start
+                .unwrap_or(crate::cli::DEFAULT_PROGRESSIVE_START),
+            args.progressive_samples
+                .unwrap_or(crate::cli::DEFAULT_PROGRESSIVE_SAMPLES)
+        );
+    }
     info!("  render-scale: {}", args.render_scale.unwrap_or(1.0));
+    if let Some((min, max)) = args.dynamic_scale {
+        info!(
+            "  dynamic-scale: {min}-{max} (budget {})",
+            args.frame_budget
+                .map(|seconds| format!("{:.1}ms", seconds * 1000.0))
+                .unwrap_or_else(|| "from fps".to_string())
+        );
+    }
+    if let Some(filter) = args.upscale_filter {
+        info!("  upscale-filter: {filter:?}");
+    }
     info!(
         "  fill-method: {}",
         format_fill_method(args.fill_method.as_ref())
@@ -94,6 +135,7 @@ fn dump_global_options(args: &RunArgs, paths: &AppPaths) {
         "  shadertoy-cache: {}",
         paths.shadertoy_cache_dir().display()
     );
+    info!("  shader-cache: {}", paths.shader_cache_dir().display());
     info!("  share: {}", paths.share_dir().display());
     info!(
         "  shader-roots: [{}]",
@@ -309,8 +351,25 @@ fn dump_playlist_config(
             if let Some(mode) = &item.mode {
                 info!("      [{}] mode: {}", i, format_item_mode(mode));
             }
-            if let Some(still_time) = item.still_time {
-                info!("      [{}] still-time: {}s", i, still_time.as_secs_f32());
+            match item.still_time {
+                Some(StillTime::At(time)) => {
+                    info!("      [{}] still-time: {}s", i, time.as_secs_f32());
+                }
+                Some(StillTime::Auto) => info!("      [{}] still-time: auto", i),
+                None => {}
+            }
+            if let Some(live) = &item.live_audio {
+                info!(
+                    "      [{}] live-audio: iChannel{} <- {} ({} Hz, {} ch, {:?})",
+                    i,
+                    live.channel,
+                    live.fifo
+                        .as_ref()
+                        .map_or_else(|| "stdin".to_string(), |path| path.display().to_string()),
+                    live.sample_rate,
+                    live.channels,
+                    live.format
+                );
             }
         }
     }
@@ -520,6 +579,7 @@ fn format_shader_compiler(compiler: &ShaderCompiler) -> String {
     match compiler {
         ShaderCompiler::Shaderc => "shaderc".to_string(),
         ShaderCompiler::NagaGlsl => "naga".to_string(),
+        ShaderCompiler::Auto => "auto".to_string(),
     }
 }
 
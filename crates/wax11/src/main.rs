@@ -1,6 +1,6 @@
 //! Entry point wiring that stitches together the CLI surface, filesystem bootstrap, and
 //! single- or multi-playlist runtime paths before delegating to `run.rs`, while exposing
-//! utility commands like `wax11 defaults where`.
+//! utility commands like `wax11 defaults where` and `wax11 check`.
 //!
 //! Types:
 //!
@@ -13,6 +13,7 @@
 
 mod bindings;
 mod bootstrap;
+mod check;
 mod cli;
 mod defaults;
 mod diagnostics;
@@ -32,6 +33,7 @@ fn main() -> Result<()> {
 
     match cli.command {
         Some(Command::Defaults(defaults_cmd)) => handle_defaults_command(defaults_cmd.action),
+        Some(Command::Check(check_cmd)) => check::run_check(check_cmd),
         None => run::run(cli.run),
     }
 }
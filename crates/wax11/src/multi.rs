@@ -8,6 +8,8 @@
 //! - `EngineOptions`, `ShaderCache`, and `PlaylistEngine` encapsulate scheduling state.
 //! - Helper structs like `EngineTarget`, `TargetResolver`, and Hyprland payloads track
 //!   compositor integration.
+//! - `Keyframe` tracks `auto` still keyframe searches, which run on worker threads
+//!   while the still is shown at its fallback time.
 //!
 //! Functions:
 //!
@@ -23,29 +25,34 @@ use std::io::{Read, Write};
 use std::net::Shutdown;
 use std::os::unix::net::UnixStream;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, TryRecvError};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{bail, Context, Result};
-use multiconfig::{AntialiasSetting, MultiConfig};
+use multiconfig::{AntialiasSetting, MultiConfig, StillTime};
 use renderer::{
-    Antialiasing, ChannelBindings, ColorSpaceMode, CrossfadeCurve, OutputId, RenderMode,
-    RenderPolicy, RendererConfig, SurfaceAlpha, SurfaceId, SurfaceInfo, SurfaceSelector,
-    SwapRequest, WallpaperRuntime, WindowRuntime,
+    Antialiasing, ChannelBindings, ColorSpaceMode, CrossfadeCurve, KeyframeSearch, LiveAudio,
+    OutputId, PcmFormat, RenderMode, RenderPolicy, RendererConfig, SurfaceAlpha, SurfaceId,
+    SurfaceInfo, SurfaceSelector, SwapRequest, WallpaperRuntime, WindowRuntime,
 };
 use scheduler::{ScheduledItem, Scheduler, TargetId};
 use serde::Deserialize;
-use shadertoy::{load_entry_shader, PathResolver, ShaderRepository, ShaderSource, ShadertoyClient};
+use shadertoy::{
+    load_entry_shader, PathResolver, ShaderRepository, ShaderSource, ShadertoyClient, StillAt,
+};
 use tracing::{debug, error, info, trace, warn};
 
 use crate::bindings::{
-    channel_bindings_from_pack, map_manifest_alpha, map_manifest_color, resolve_color_space,
+    channel_bindings_from_pack, live_audio_input, map_manifest_alpha, map_manifest_color,
+    resolve_color_space,
 };
 use crate::bootstrap::parse_surface_size;
 use crate::cli::RunArgs;
 use crate::handles::{EntryHandle, PlaylistHandle};
 use crate::paths::AppPaths;
 use crate::run::{
-    convert_gpu_memory, convert_gpu_power, resolve_render_scale, validate_occlusion_args,
+    convert_gpu_memory, convert_gpu_power, resolve_dynamic_scale, resolve_render_scale,
+    validate_occlusion_args,
 };
 
 const DEFAULT_PREWARM_MS: u64 = 100;
@@ -64,10 +71,28 @@ pub fn run_multi(
     info!(config = %config_path.display(), "loaded playlist configuration");
     debug!(roots = ?playlist_roots, "playlist search roots");
 
+    let include_dirs = paths.shader_include_dirs();
+    let shader_cache_dir = Some(paths.shader_cache_dir());
     if args.window {
-        run_window_multi(args, repo, client, config, resolver)
+        run_window_multi(
+            args,
+            repo,
+            client,
+            config,
+            resolver,
+            include_dirs,
+            shader_cache_dir,
+        )
     } else {
-        run_wallpaper_multi(args, repo, client, config, resolver)
+        run_wallpaper_multi(
+            args,
+            repo,
+            client,
+            config,
+            resolver,
+            include_dirs,
+            shader_cache_dir,
+        )
     }
 }
 
@@ -84,6 +109,8 @@ fn run_wallpaper_multi(
     client: Option<&ShadertoyClient>,
     config: MultiConfig,
     resolver: PathResolver,
+    include_dirs: Vec<PathBuf>,
+    shader_cache_dir: Option<PathBuf>,
 ) -> Result<()> {
     let requested_size = args
         .size
@@ -112,7 +139,11 @@ fn run_wallpaper_multi(
         surface_alpha: bootstrap.surface_alpha,
         color_space: bootstrap_color,
         shader_compiler: args.shader_compiler,
+        shader_include_dirs: include_dirs,
+        shader_cache_dir,
         render_scale,
+        upscale_filter: args.upscale_filter.unwrap_or_default(),
+        dynamic_scale: resolve_dynamic_scale(args)?,
         fill_method,
         max_fps_occluded: args.max_fps_occluded,
         show_window: true,
@@ -128,6 +159,7 @@ fn run_wallpaper_multi(
         vsync_mode: args.vsync_mode,
     };
 
+    let keyframe_config = renderer_config.clone();
     let runtime = WallpaperRuntime::spawn(renderer_config)?;
 
     let seed = seed_from_time();
@@ -139,6 +171,7 @@ fn run_wallpaper_multi(
         global_color_space: args.color_space,
         prewarm: Duration::from_millis(args.prewarm_ms.unwrap_or(DEFAULT_PREWARM_MS)),
         crossfade_curve: args.crossfade_curve.unwrap_or_default(),
+        keyframe_config: Some(keyframe_config),
     };
     let mut engine = PlaylistEngine::new(config, cache, seed, options);
 
@@ -151,6 +184,8 @@ fn run_window_multi(
     client: Option<&ShadertoyClient>,
     config: MultiConfig,
     resolver: PathResolver,
+    include_dirs: Vec<PathBuf>,
+    shader_cache_dir: Option<PathBuf>,
 ) -> Result<()> {
     if config.default_playlist().is_none() {
         bail!("window mode requires defaults.playlist to be set");
@@ -183,7 +218,11 @@ fn run_window_multi(
         surface_alpha: bootstrap.surface_alpha,
         color_space: bootstrap_color,
         shader_compiler: args.shader_compiler,
+        shader_include_dirs: include_dirs,
+        shader_cache_dir,
         render_scale,
+        upscale_filter: args.upscale_filter.unwrap_or_default(),
+        dynamic_scale: resolve_dynamic_scale(args)?,
         fill_method,
         max_fps_occluded: args.max_fps_occluded,
         show_window: true,
@@ -199,6 +238,7 @@ fn run_window_multi(
         vsync_mode: args.vsync_mode,
     };
 
+    let keyframe_config = renderer_config.clone();
     let runtime = WindowRuntime::spawn(renderer_config)?;
 
     let seed = seed_from_time();
@@ -210,6 +250,7 @@ fn run_window_multi(
         global_color_space: args.color_space,
         prewarm: Duration::from_millis(args.prewarm_ms.unwrap_or(DEFAULT_PREWARM_MS)),
         crossfade_curve: args.crossfade_curve.unwrap_or_default(),
+        keyframe_config: Some(keyframe_config),
     };
     let mut engine = PlaylistEngine::new(config, cache, seed, options);
 
@@ -325,6 +366,8 @@ struct EngineOptions {
     global_color_space: ColorSpaceMode,
     prewarm: Duration,
     crossfade_curve: CrossfadeCurve,
+    /// Template for `auto` still keyframe searches; `None` renders those stills at 0s.
+    keyframe_config: Option<RendererConfig>,
 }
 
 struct ShaderAssets {
@@ -332,6 +375,7 @@ struct ShaderAssets {
     channel_bindings: ChannelBindings,
     surface_alpha: SurfaceAlpha,
     color_space: ColorSpaceMode,
+    still_at: Option<StillAt>,
 }
 
 #[derive(Clone)]
@@ -340,6 +384,7 @@ struct CachedShader {
     channel_bindings: ChannelBindings,
     surface_alpha: SurfaceAlpha,
     color_space: ColorSpaceMode,
+    still_at: Option<StillAt>,
 }
 
 struct ShaderCache<'a> {
@@ -375,6 +420,7 @@ impl<'a> ShaderCache<'a> {
                     channel_bindings: cached.channel_bindings.clone(),
                     surface_alpha: cached.surface_alpha,
                     color_space: cached.color_space,
+                    still_at: cached.still_at,
                 });
             }
         }
@@ -387,16 +433,18 @@ impl<'a> ShaderCache<'a> {
             .repo
             .resolve(&shader_handle, self.client, refresh && !self.cache_only)
             .with_context(|| format!("failed to resolve shader '{handle}'"))?;
-        let (channel_report, surface_alpha, color_space) = match &source {
+        let (channel_report, surface_alpha, color_space, still_at) = match &source {
             ShaderSource::Local(pack) => (
                 channel_bindings_from_pack(pack),
                 map_manifest_alpha(pack.manifest().surface_alpha),
                 map_manifest_color(pack.manifest().color_space),
+                pack.manifest().still_at,
             ),
             ShaderSource::CachedRemote(remote) => (
                 channel_bindings_from_pack(&remote.pack),
                 map_manifest_alpha(remote.pack.manifest().surface_alpha),
                 map_manifest_color(remote.pack.manifest().color_space),
+                remote.pack.manifest().still_at,
             ),
         };
         if !channel_report.issues.is_empty() {
@@ -435,6 +483,7 @@ impl<'a> ShaderCache<'a> {
                 channel_bindings: channel_bindings.clone(),
                 surface_alpha,
                 color_space,
+                still_at,
             },
         );
         debug!(handle = %handle, "cached shader assets");
@@ -443,6 +492,7 @@ impl<'a> ShaderCache<'a> {
             channel_bindings,
             surface_alpha,
             color_space,
+            still_at,
         })
     }
 }
@@ -477,6 +527,21 @@ struct PlaylistEngine<'a> {
     targets: HashMap<TargetId, EngineTarget>,
     surface_map: HashMap<SurfaceId, TargetId>,
     workspace_crossfade: Duration,
+    /// `auto` keyframe searches, per entry shader.
+    keyframes: HashMap<PathBuf, Keyframe>,
+}
+
+/// State of the `auto` keyframe search for one entry shader.
+enum Keyframe {
+    Searching(mpsc::Receiver<Option<f32>>),
+    /// The chosen timestamp, or `None` when the search failed.
+    Ready(Option<f32>),
+}
+
+/// A still shown at its fallback time until its shader's keyframe search finishes.
+struct KeyframeWait {
+    shader_path: PathBuf,
+    action: SwapAction,
 }
 
 struct EngineTarget {
@@ -488,6 +553,7 @@ struct EngineTarget {
     playlist_len: usize,
     crossfade_override: Option<Duration>,
     last_size: Option<(u32, u32)>,
+    keyframe_wait: Option<KeyframeWait>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -510,6 +576,7 @@ impl<'a> PlaylistEngine<'a> {
             targets: HashMap::new(),
             surface_map: HashMap::new(),
             workspace_crossfade,
+            keyframes: HashMap::new(),
         }
     }
 
@@ -586,6 +653,7 @@ impl<'a> PlaylistEngine<'a> {
                                 playlist_len: resolved.playlist_len,
                                 crossfade_override: Some(Duration::ZERO),
                                 last_size: surface.size,
+                                keyframe_wait: None,
                             });
                             info!(
                                 target = %target_id.0,
@@ -688,7 +756,7 @@ impl<'a> PlaylistEngine<'a> {
         now: Instant,
         _hyprland: Option<&HyprlandSnapshot>,
     ) -> Result<Vec<SwapAction>> {
-        let mut actions = Vec::new();
+        let mut actions = self.keyframe_actions();
         for change in self.scheduler.tick(now) {
             let target_id = change.target.clone();
             if let Some(action) = self.activate_selection(target_id, change) {
@@ -766,9 +834,11 @@ impl<'a> PlaylistEngine<'a> {
                         "prepared swap action"
                     );
 
+                    let (still_time, searching) = self.still_time(&change.item, &assets);
                     let request = build_swap_request(
                         &change.item,
                         &assets,
+                        still_time,
                         self.options.global_fps,
                         self.options.global_antialias,
                         self.options.global_color_space,
@@ -776,12 +846,19 @@ impl<'a> PlaylistEngine<'a> {
                         self.options.crossfade_curve,
                         self.options.prewarm,
                     );
-                    return Some(SwapAction {
+                    let action = SwapAction {
                         target_display: display,
                         selector,
                         request,
                         handle,
-                    });
+                    };
+                    if let Some(target) = self.targets.get_mut(&target_id) {
+                        target.keyframe_wait = searching.then(|| KeyframeWait {
+                            shader_path: assets.shader_path.clone(),
+                            action: action.clone(),
+                        });
+                    }
+                    return Some(action);
                 }
                 Err(()) => {
                     attempts += 1;
@@ -805,6 +882,105 @@ impl<'a> PlaylistEngine<'a> {
         }
     }
 
+    /// Timestamp of a `still` item: its `still_time`, else the pack's `still_at`.
+    ///
+    /// `auto` starts a background keyframe search once per shader; until it
+    /// finishes the still uses the pack's `still_at` seconds (or 0s) and the
+    /// second value is true.
+    fn still_time(&mut self, item: &ScheduledItem, assets: &ShaderAssets) -> (Option<f32>, bool) {
+        if item.mode != multiconfig::PlaylistItemMode::Still {
+            return (None, false);
+        }
+        match (item.still_time, assets.still_at) {
+            (Some(StillTime::At(time)), _) => return (Some(time.as_secs_f32()), false),
+            (None, Some(StillAt::Seconds(seconds))) => return (Some(seconds), false),
+            (None, None) => return (None, false),
+            (Some(StillTime::Auto), _) | (None, Some(StillAt::Auto)) => {}
+        }
+        let fallback = match assets.still_at {
+            Some(StillAt::Seconds(seconds)) => Some(seconds),
+            _ => None,
+        };
+        match self.keyframe(item, assets) {
+            Some(Keyframe::Ready(time)) => (time.or(fallback), false),
+            Some(Keyframe::Searching(_)) => (fallback, true),
+            None => (fallback, false),
+        }
+    }
+
+    /// The keyframe search for `assets`, started on a worker thread if needed.
+    /// `None` when searches are disabled.
+    fn keyframe(&mut self, item: &ScheduledItem, assets: &ShaderAssets) -> Option<&Keyframe> {
+        if !self.keyframes.contains_key(&assets.shader_path) {
+            let mut config = self.options.keyframe_config.clone()?;
+            config.shader_source = assets.shader_path.clone();
+            config.channel_bindings = assets.channel_bindings.clone();
+            config.color_space =
+                resolve_color_space(self.options.global_color_space, assets.color_space);
+            let (sender, receiver) = mpsc::channel();
+            let handle = item.handle.clone();
+            let spawned = std::thread::Builder::new()
+                .name("wax11-keyframe".into())
+                .spawn(move || {
+                    let time = renderer::select_still_time(&config, &KeyframeSearch::default())
+                        .map_err(|err| {
+                            warn!(
+                                handle = %handle,
+                                error = %format!("{err:#}"),
+                                "keyframe search failed; keeping the fallback still time"
+                            );
+                        })
+                        .ok();
+                    let _ = sender.send(time);
+                });
+            let keyframe = match spawned {
+                Ok(_) => {
+                    debug!(handle = %item.handle, "searching for a still keyframe");
+                    Keyframe::Searching(receiver)
+                }
+                Err(err) => {
+                    warn!(handle = %item.handle, error = %err, "failed to start keyframe search");
+                    Keyframe::Ready(None)
+                }
+            };
+            self.keyframes.insert(assets.shader_path.clone(), keyframe);
+        }
+        self.keyframes.get(&assets.shader_path)
+    }
+
+    /// Collects finished keyframe searches and re-issues the stills waiting on
+    /// them at the chosen time.
+    fn keyframe_actions(&mut self) -> Vec<SwapAction> {
+        for keyframe in self.keyframes.values_mut() {
+            if let Keyframe::Searching(receiver) = keyframe {
+                match receiver.try_recv() {
+                    Ok(time) => *keyframe = Keyframe::Ready(time),
+                    Err(TryRecvError::Disconnected) => *keyframe = Keyframe::Ready(None),
+                    Err(TryRecvError::Empty) => {}
+                }
+            }
+        }
+        let mut actions = Vec::new();
+        for target in self.targets.values_mut() {
+            let Some(wait) = target.keyframe_wait.as_ref() else {
+                continue;
+            };
+            let time = match self.keyframes.get(&wait.shader_path) {
+                Some(Keyframe::Searching(_)) => continue,
+                Some(Keyframe::Ready(time)) => *time,
+                None => None,
+            };
+            let Some(mut action) = target.keyframe_wait.take().map(|wait| wait.action) else {
+                continue;
+            };
+            if let Some(time) = time {
+                action.request.policy = RenderPolicy::Still { time: Some(time) };
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
     fn should_refresh(&self, handle: &str, item: &ScheduledItem) -> bool {
         if self.options.cache_only {
             return false;
@@ -820,6 +996,7 @@ impl<'a> PlaylistEngine<'a> {
 fn build_swap_request(
     item: &ScheduledItem,
     assets: &ShaderAssets,
+    still_time: Option<f32>,
     global_fps: Option<f32>,
     global_antialias: Antialiasing,
     global_color: ColorSpaceMode,
@@ -842,13 +1019,28 @@ fn build_swap_request(
             target_fps,
             adaptive: false,
         },
-        multiconfig::PlaylistItemMode::Still => RenderPolicy::Still {
-            time: item.still_time.map(|d| d.as_secs_f32()),
-        },
+        multiconfig::PlaylistItemMode::Still => RenderPolicy::Still { time: still_time },
     };
+    let mut channel_bindings = assets.channel_bindings.clone();
+    if let Some(route) = &item.live_audio {
+        let live = LiveAudio {
+            input: live_audio_input(route.fifo.clone()),
+            sample_rate: route.sample_rate,
+            channels: route.channels,
+            format: map_pcm_format(route.format),
+        };
+        if let Err(err) = channel_bindings.set_live_audio(route.channel as usize, live) {
+            warn!(
+                handle = %item.handle,
+                channel = route.channel,
+                error = %err,
+                "failed to route live audio onto playlist item"
+            );
+        }
+    }
     SwapRequest {
         shader_source: assets.shader_path.clone(),
-        channel_bindings: assets.channel_bindings.clone(),
+        channel_bindings,
         crossfade,
         crossfade_curve,
         target_fps,
@@ -871,6 +1063,13 @@ fn map_antialias(setting: AntialiasSetting) -> Antialiasing {
     }
 }
 
+fn map_pcm_format(format: multiconfig::PcmFormat) -> PcmFormat {
+    match format {
+        multiconfig::PcmFormat::S16le => PcmFormat::S16Le,
+        multiconfig::PcmFormat::F32le => PcmFormat::F32Le,
+    }
+}
+
 fn describe_target(target_id: &TargetId, target: &EngineTarget) -> String {
     if let Some(output) = &target.output_name {
         format!("{} ({output})", target_id.0)
@@ -962,6 +1161,7 @@ impl<'a> TargetResolver<'a> {
     }
 }
 
+#[derive(Clone)]
 struct SwapAction {
     target_display: String,
     selector: SurfaceSelector,
@@ -1108,6 +1308,52 @@ handle = "demo"
         .unwrap()
     }
 
+    #[test]
+    fn swap_request_routes_live_audio_onto_channel() {
+        let item = ScheduledItem {
+            handle: "local/visualizer".into(),
+            duration: Duration::from_secs(60),
+            fps: None,
+            antialias: None,
+            refresh_once: false,
+            crossfade: Duration::from_secs(1),
+            mode: multiconfig::PlaylistItemMode::Animate,
+            still_time: None,
+            live_audio: Some(multiconfig::LiveAudioRoute {
+                channel: 1,
+                fifo: Some(PathBuf::from("/tmp/wax11.fifo")),
+                sample_rate: 48_000,
+                channels: 2,
+                format: multiconfig::PcmFormat::F32le,
+            }),
+        };
+        let assets = ShaderAssets {
+            shader_path: PathBuf::from("image.glsl"),
+            channel_bindings: ChannelBindings::new(),
+            surface_alpha: SurfaceAlpha::Opaque,
+            color_space: ColorSpaceMode::Auto,
+            still_at: None,
+        };
+
+        let request = build_swap_request(
+            &item,
+            &assets,
+            None,
+            None,
+            Antialiasing::Auto,
+            ColorSpaceMode::Auto,
+            Duration::ZERO,
+            CrossfadeCurve::Linear,
+            Duration::ZERO,
+        );
+        assert!(matches!(
+            request.channel_bindings.channel(1),
+            Some(renderer::ChannelSource::LiveAudio(live))
+                if live.sample_rate == 48_000 && live.format == PcmFormat::F32Le
+        ));
+        assert!(request.channel_bindings.channel(0).is_none());
+    }
+
     #[test]
     fn resolver_prefers_workspace_mapping() {
         let config = MultiConfig::from_toml_str(
@@ -1268,6 +1514,7 @@ handle = "alt"
             global_color_space: ColorSpaceMode::Auto,
             prewarm: Duration::from_millis(DEFAULT_PREWARM_MS),
             crossfade_curve: CrossfadeCurve::default(),
+            keyframe_config: None,
         };
         let mut engine = PlaylistEngine::new(config, cache, 99, options);
 
@@ -1295,6 +1542,76 @@ handle = "alt"
         assert_eq!(advances[0].handle, "alt");
     }
 
+    #[test]
+    fn keyframe_results_swap_waiting_targets() {
+        let temp = tempdir().unwrap();
+        let packs_root = temp.path().join("packs");
+        write_pack(&packs_root, "demo");
+        let repo = ShaderRepository::new(vec![packs_root], temp.path().join("cache"));
+        let cache = ShaderCache::new(&repo, None, false, PathResolver::with_cwd(temp.path()));
+        let config = MultiConfig::from_toml_str(
+            r#"
+version = 1
+
+[defaults]
+playlist = "solo"
+
+[playlists.solo]
+mode = "continuous"
+item_duration = 60
+
+[[playlists.solo.items]]
+handle = "demo"
+"#,
+        )
+        .unwrap();
+        let options = EngineOptions {
+            cache_only: false,
+            refresh_all: false,
+            global_fps: None,
+            global_antialias: Antialiasing::Auto,
+            global_color_space: ColorSpaceMode::Auto,
+            prewarm: Duration::from_millis(DEFAULT_PREWARM_MS),
+            crossfade_curve: CrossfadeCurve::default(),
+            keyframe_config: None,
+        };
+        let mut engine = PlaylistEngine::new(config, cache, 99, options);
+        let surface = SurfaceInfo {
+            surface_id: SurfaceId::from(7u64),
+            output_id: None,
+            output_name: None,
+            size: None,
+        };
+        let start = Instant::now();
+        let action = engine
+            .sync_targets(&[surface], None, start)
+            .expect("sync targets")
+            .remove(0);
+
+        let shader_path = PathBuf::from("demo/image.glsl");
+        let (sender, receiver) = mpsc::channel();
+        engine
+            .keyframes
+            .insert(shader_path.clone(), Keyframe::Searching(receiver));
+        for target in engine.targets.values_mut() {
+            target.keyframe_wait = Some(KeyframeWait {
+                shader_path: shader_path.clone(),
+                action: action.clone(),
+            });
+        }
+        assert!(engine.keyframe_actions().is_empty());
+
+        sender.send(Some(4.0)).unwrap();
+        let actions = engine.keyframe_actions();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].handle, "demo");
+        assert!(matches!(
+            actions[0].request.policy,
+            RenderPolicy::Still { time: Some(time) } if time == 4.0
+        ));
+        assert!(engine.keyframe_actions().is_empty());
+    }
+
     #[test]
     fn shader_handles_expand_environment_variables() {
         let temp = tempdir().unwrap();
@@ -1344,6 +1661,7 @@ handle = "${HYSHADERS_TEST_PACK}"
                 global_color_space: ColorSpaceMode::Auto,
                 prewarm: Duration::from_millis(DEFAULT_PREWARM_MS),
                 crossfade_curve: CrossfadeCurve::default(),
+                keyframe_config: None,
             },
         );
 
@@ -1411,6 +1729,7 @@ handle = "focus-pack"
             global_color_space: ColorSpaceMode::Auto,
             prewarm: Duration::from_millis(DEFAULT_PREWARM_MS),
             crossfade_curve: CrossfadeCurve::default(),
+            keyframe_config: None,
         };
         let mut engine = PlaylistEngine::new(config, cache, 7, options);
 
@@ -1496,6 +1815,7 @@ handle = "valid-pack"
             global_color_space: ColorSpaceMode::Auto,
             prewarm: Duration::from_millis(DEFAULT_PREWARM_MS),
             crossfade_curve: CrossfadeCurve::default(),
+            keyframe_config: None,
         };
         let mut engine = PlaylistEngine::new(config, cache, 55, options);
 
@@ -1539,9 +1859,23 @@ handle = "demo"
             window: true,
             still: false,
             still_time: None,
+            progressive: false,
+            progressive_start: None,
+            progressive_samples: None,
             still_export: None,
+            export_scale: None,
+            export_sequence: None,
+            every: None,
+            loop_fps: None,
+            loop_duration: None,
+            loop_crossfade: None,
             still_exit: None,
+            export_audio: None,
+            duration: std::time::Duration::from_secs(30),
             render_scale: None,
+            upscale_filter: None,
+            dynamic_scale: None,
+            frame_budget: None,
             fill_method: None,
             fps_adaptive: false,
             max_fps_occluded: None,
@@ -1562,7 +1896,8 @@ handle = "demo"
         };
 
         let resolver = PathResolver::with_cwd(temp.path());
-        let err = run_window_multi(&args, &repo, None, config, resolver).unwrap_err();
+        let err =
+            run_window_multi(&args, &repo, None, config, resolver, Vec::new(), None).unwrap_err();
         assert!(err.to_string().contains("defaults.playlist"));
     }
 }
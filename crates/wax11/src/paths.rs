@@ -106,10 +106,24 @@ impl AppPaths {
         roots
     }
 
+    /// Shared GLSL library directories (`lib/` under each shader root) searched
+    /// for `#include`s that the including pack does not provide itself.
+    pub fn shader_include_dirs(&self) -> Vec<PathBuf> {
+        self.shader_roots()
+            .into_iter()
+            .map(|root| root.join("lib"))
+            .collect()
+    }
+
     pub fn shadertoy_cache_dir(&self) -> PathBuf {
         self.cache_dir.join("shadertoy")
     }
 
+    /// Compiled SPIR-V and GPU pipeline cache data.
+    pub fn shader_cache_dir(&self) -> PathBuf {
+        self.cache_dir.join("shaders")
+    }
+
     pub fn playlist_user_dirs(&self) -> Vec<PathBuf> {
         // Resolution order: CONFIG_DIR > DATA_DIR
         // Users should copy playlists to CONFIG_DIR for customization.
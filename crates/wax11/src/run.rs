@@ -11,15 +11,20 @@
 //! - `run` drives the main execution path.
 //! - `initialise_tracing` configures logging.
 //! - `build_client` and `prepare_single_run` resolve Shadertoy data and renderer config.
+//! - `export_audio` renders the resolved shader's sound pass to WAV for `--export-audio`.
 //! - Utility routines log handle decisions and translate CLI overrides into `RendererConfig`.
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::{Context, Result};
 use renderer::{
-    GpuMemoryMode, GpuPowerPreference, RenderMode, RenderPolicy, Renderer, RendererConfig,
+    DynamicScale, ExportFormat, ExportSequence, GpuMemoryMode, GpuPowerPreference, KeyframeSearch,
+    ProgressiveRefine, RenderMode, RenderPolicy, Renderer, RendererConfig, SoundExportConfig,
 };
 use shadertoy::{
     load_entry_shader, ShaderHandle, ShaderRepository, ShaderSource, ShadertoyClient,
-    ShadertoyConfig,
+    ShadertoyConfig, StillAt,
 };
 use tracing_subscriber::EnvFilter;
 
@@ -29,7 +34,10 @@ use crate::bindings::{
 use crate::bootstrap::{
     bootstrap_filesystem, parse_surface_size, resolve_entry_handle, SingleRunConfig,
 };
-use crate::cli::{parse_export_format, RunArgs};
+use crate::cli::{
+    parse_export_format, RunArgs, DEFAULT_FRAME_BUDGET, DEFAULT_LOOP_DURATION, DEFAULT_LOOP_FPS,
+    DEFAULT_PROGRESSIVE_SAMPLES, DEFAULT_PROGRESSIVE_START, DEFAULT_SEQUENCE_STEP,
+};
 use crate::diagnostics;
 use crate::handles::{EntryHandle, LaunchHandle};
 use crate::multi;
@@ -67,6 +75,9 @@ pub fn run(args: RunArgs) -> Result<()> {
         });
 
     if let Some(handle) = playlist_handle {
+        if args.export_audio.is_some() {
+            anyhow::bail!("--export-audio needs a single shader, not a playlist");
+        }
         return multi::run_multi(
             &args,
             &repo,
@@ -82,10 +93,46 @@ pub fn run(args: RunArgs) -> Result<()> {
     let handle = entry_handle.clone().into_shader_handle();
     tracing::info!(?handle, "bootstrapping wax11 wallpaper daemon");
     log_handle_warnings(&args, &handle, client.as_ref());
-    let context = prepare_single_run(&args, &repo, client.as_ref(), handle.clone())?;
+    let context = prepare_single_run(
+        &args,
+        &repo,
+        client.as_ref(),
+        handle.clone(),
+        paths.shader_include_dirs(),
+        Some(paths.shader_cache_dir()),
+    )?;
+    if let Some(path) = args.export_audio.as_ref() {
+        return export_audio(&context.renderer_config, path, args.duration);
+    }
     run_single(context)
 }
 
+/// ShaderToy plays sound passes at 44.1 kHz regardless of the output device.
+const EXPORT_SAMPLE_RATE: u32 = 44_100;
+
+fn export_audio(
+    config: &RendererConfig,
+    path: &std::path::Path,
+    duration: std::time::Duration,
+) -> Result<()> {
+    let pass = config
+        .channel_bindings
+        .sound_pass()
+        .context("--export-audio requires a shader with a sound pass")?;
+    renderer::export_sound(&SoundExportConfig {
+        pass: pass.clone(),
+        path: path.to_path_buf(),
+        duration,
+        sample_rate: EXPORT_SAMPLE_RATE,
+        shader_compiler: config.shader_compiler,
+        shader_include_dirs: config.shader_include_dirs.clone(),
+        shader_cache_dir: config.shader_cache_dir.clone(),
+        color_space: config.color_space,
+        gpu_power: config.gpu_power,
+    })
+    .with_context(|| format!("failed to export audio to {}", path.display()))
+}
+
 fn log_entry_handle(handle: &EntryHandle, paths: &AppPaths) {
     match handle {
         EntryHandle::RawPath(path) => {
@@ -162,6 +209,8 @@ fn prepare_single_run(
     repo: &ShaderRepository,
     client: Option<&ShadertoyClient>,
     handle: ShaderHandle,
+    include_dirs: Vec<PathBuf>,
+    shader_cache_dir: Option<PathBuf>,
 ) -> Result<SingleRunConfig> {
     let source = repo.resolve(&handle, client, args.refresh)?;
     let channel_report = match &source {
@@ -176,14 +225,16 @@ fn prepare_single_run(
         channel_report.log_warnings();
     }
     let channel_bindings = channel_report.bindings;
-    let (surface_alpha, manifest_color) = match &source {
+    let (surface_alpha, manifest_color, manifest_still_at) = match &source {
         ShaderSource::Local(pack) => (
             map_manifest_alpha(pack.manifest().surface_alpha),
             map_manifest_color(pack.manifest().color_space),
+            pack.manifest().still_at,
         ),
         ShaderSource::CachedRemote(remote) => (
             map_manifest_alpha(remote.pack.manifest().surface_alpha),
             map_manifest_color(remote.pack.manifest().color_space),
+            remote.pack.manifest().still_at,
         ),
     };
     let shader_path = load_entry_shader(&source)?;
@@ -215,16 +266,28 @@ fn prepare_single_run(
 
     let color_space = resolve_color_space(args.color_space, manifest_color);
 
-    let still_time = parse_still_time_arg(args.still_time.as_deref())?;
+    let still_at = parse_still_time_arg(args.still_time.as_deref())?.or(manifest_still_at);
+    let still_time = match still_at {
+        Some(StillAt::Seconds(seconds)) => Some(seconds),
+        Some(StillAt::Auto) | None => None,
+    };
     let render_policy = if let Some(path) = args.still_export.as_ref() {
         let format = parse_export_format(path).map_err(|err| anyhow::anyhow!(err))?;
         RenderPolicy::Export {
             time: still_time,
             path: path.clone(),
             format,
+            scale: resolve_export_scale(args.export_scale)?,
+            sequence: resolve_export_sequence(args, format)?,
         }
     } else if args.still {
-        RenderPolicy::Still { time: still_time }
+        match resolve_progressive(args)? {
+            Some(refine) => RenderPolicy::Progressive {
+                time: still_time,
+                refine,
+            },
+            None => RenderPolicy::Still { time: still_time },
+        }
     } else {
         RenderPolicy::Animate {
             target_fps: match args.fps {
@@ -242,6 +305,25 @@ fn prepare_single_run(
     if args.still_time.is_some() && !args.still && args.still_export.is_none() {
         anyhow::bail!("--still-time requires --still or --still-export");
     }
+    if args.export_scale.is_some() && args.still_export.is_none() {
+        anyhow::bail!("--export-scale requires --still-export");
+    }
+    if args.export_sequence.is_some() && args.still_export.is_none() {
+        anyhow::bail!("--export-sequence requires --still-export");
+    }
+    if args.every.is_some() && args.export_sequence.is_none() {
+        anyhow::bail!("--every requires --export-sequence");
+    }
+    if (args.progressive || args.progressive_start.is_some() || args.progressive_samples.is_some())
+        && (!args.still || args.still_export.is_some())
+    {
+        anyhow::bail!(
+            "--progressive, --progressive-start and --progressive-samples require --still"
+        );
+    }
+    if has_loop_args(args) && args.still_export.is_none() {
+        anyhow::bail!("--loop-fps, --loop-duration and --loop-crossfade require --still-export");
+    }
     let render_mode = if args.window {
         RenderMode::Windowed
     } else {
@@ -253,7 +335,7 @@ fn prepare_single_run(
     let exit_on_export = args.still_exit.unwrap_or(true);
     let show_window = args.window;
 
-    let renderer_config = RendererConfig {
+    let mut renderer_config = RendererConfig {
         surface_size: fallback_surface,
         shader_source: shader_path,
         mode: render_mode,
@@ -266,8 +348,12 @@ fn prepare_single_run(
         antialiasing: args.antialias,
         surface_alpha,
         shader_compiler: args.shader_compiler,
+        shader_include_dirs: include_dirs,
+        shader_cache_dir,
         color_space,
         render_scale,
+        upscale_filter: args.upscale_filter.unwrap_or_default(),
+        dynamic_scale: resolve_dynamic_scale(args)?,
         fill_method: args.fill_method.unwrap_or_default(),
         max_fps_occluded: args.max_fps_occluded,
         show_window,
@@ -280,6 +366,16 @@ fn prepare_single_run(
         vsync_mode: args.vsync_mode,
     };
 
+    if still_at == Some(StillAt::Auto) && renderer_config.policy.is_still() {
+        match renderer::select_still_time(&renderer_config, &KeyframeSearch::default()) {
+            Ok(time) => renderer_config.policy.set_time(time),
+            Err(err) => tracing::warn!(
+                error = %format!("{err:#}"),
+                "keyframe search failed; rendering the still at 0s"
+            ),
+        }
+    }
+
     Ok(SingleRunConfig { renderer_config })
 }
 
@@ -288,7 +384,7 @@ fn run_single(config: SingleRunConfig) -> Result<()> {
     renderer.run()
 }
 
-fn parse_still_time_arg(value: Option<&str>) -> Result<Option<f32>> {
+fn parse_still_time_arg(value: Option<&str>) -> Result<Option<StillAt>> {
     let Some(raw) = value else {
         return Ok(None);
     };
@@ -299,8 +395,7 @@ fn parse_still_time_arg(value: Option<&str>) -> Result<Option<f32>> {
     }
 
     if trimmed.eq_ignore_ascii_case("auto") {
-        tracing::debug!("--still-time auto requested; using shader default (0s) for now");
-        return Ok(None);
+        return Ok(Some(StillAt::Auto));
     }
 
     let seconds: f32 = trimmed.parse().map_err(|_| {
@@ -309,7 +404,7 @@ fn parse_still_time_arg(value: Option<&str>) -> Result<Option<f32>> {
     if seconds < 0.0 {
         anyhow::bail!("--still-time must be non-negative");
     }
-    Ok(Some(seconds))
+    Ok(Some(StillAt::Seconds(seconds)))
 }
 
 pub(crate) fn convert_gpu_power(cli_value: crate::cli::GpuPowerPreference) -> GpuPowerPreference {
@@ -337,6 +432,111 @@ pub(crate) fn resolve_render_scale(value: Option<f32>) -> Result<f32> {
     }
 }
 
+/// Builds the dynamic resolution bounds and frame budget from `--dynamic-scale`.
+pub(crate) fn resolve_dynamic_scale(args: &RunArgs) -> Result<Option<DynamicScale>> {
+    let Some((min_scale, max_scale)) = args.dynamic_scale else {
+        if args.frame_budget.is_some() {
+            anyhow::bail!("--frame-budget requires --dynamic-scale");
+        }
+        return Ok(None);
+    };
+    if args.render_scale.is_some() {
+        anyhow::bail!("--render-scale and --dynamic-scale cannot be combined");
+    }
+    let budget = args.frame_budget.unwrap_or(match args.fps {
+        Some(fps) if fps > 0.0 => 1.0 / fps,
+        _ => DEFAULT_FRAME_BUDGET,
+    });
+    Ok(Some(DynamicScale {
+        min_scale,
+        max_scale,
+        frame_budget: Duration::from_secs_f32(budget),
+    }))
+}
+
+/// Builds the `--progressive` refinement schedule for a `--still` frame.
+pub(crate) fn resolve_progressive(args: &RunArgs) -> Result<Option<ProgressiveRefine>> {
+    if !args.progressive {
+        if args.progressive_start.is_some() || args.progressive_samples.is_some() {
+            anyhow::bail!("--progressive-start and --progressive-samples require --progressive");
+        }
+        return Ok(None);
+    }
+    if args.render_scale.is_some() || args.dynamic_scale.is_some() {
+        anyhow::bail!("--progressive cannot be combined with --render-scale or --dynamic-scale");
+    }
+    let start = args.progressive_start.unwrap_or(DEFAULT_PROGRESSIVE_START);
+    if !(0.1..=1.0).contains(&start) {
+        anyhow::bail!("--progressive-start must be between 0.1 and 1.0");
+    }
+    Ok(Some(ProgressiveRefine::new(
+        start,
+        args.progressive_samples
+            .unwrap_or(DEFAULT_PROGRESSIVE_SAMPLES),
+    )))
+}
+
+pub(crate) fn resolve_export_scale(value: Option<f32>) -> Result<f32> {
+    if let Some(scale) = value {
+        if !(0.25..=8.0).contains(&scale) {
+            anyhow::bail!("--export-scale must be between 0.25 and 8.0");
+        }
+        Ok(scale)
+    } else {
+        Ok(1.0)
+    }
+}
+
+fn has_loop_args(args: &RunArgs) -> bool {
+    args.loop_fps.is_some() || args.loop_duration.is_some() || args.loop_crossfade.is_some()
+}
+
+/// Builds the frame sequence for `--still-export`: numbered frames from
+/// `--export-sequence`, or the loop length and crossfade of a `.gif`/`.apng`.
+pub(crate) fn resolve_export_sequence(
+    args: &RunArgs,
+    format: ExportFormat,
+) -> Result<Option<ExportSequence>> {
+    if !format.is_animated() {
+        if has_loop_args(args) {
+            anyhow::bail!(
+                "--loop-fps, --loop-duration and --loop-crossfade require a .gif or .apng export"
+            );
+        }
+        return Ok(args.export_sequence.map(|frames| ExportSequence {
+            frames,
+            step: args.every.unwrap_or(DEFAULT_SEQUENCE_STEP),
+            crossfade: 0,
+        }));
+    }
+    if args.export_sequence.is_some() || args.every.is_some() {
+        anyhow::bail!(
+            "--export-sequence and --every do not apply to .gif/.apng loops; use --loop-fps and --loop-duration"
+        );
+    }
+    let fps = args.loop_fps.unwrap_or(DEFAULT_LOOP_FPS);
+    if !(1.0..=100.0).contains(&fps) {
+        anyhow::bail!("--loop-fps must be between 1 and 100");
+    }
+    let duration = args.loop_duration.unwrap_or(DEFAULT_LOOP_DURATION);
+    let frames = (duration.as_secs_f32() * fps).round() as u32;
+    if frames == 0 {
+        anyhow::bail!("--loop-duration must cover at least one frame at {fps} fps");
+    }
+    let crossfade = args
+        .loop_crossfade
+        .map(|fade| (fade.as_secs_f32() * fps).round() as u32)
+        .unwrap_or(0);
+    if crossfade >= frames {
+        anyhow::bail!("--loop-crossfade must be shorter than --loop-duration");
+    }
+    Ok(Some(ExportSequence {
+        frames,
+        step: 1.0 / fps,
+        crossfade,
+    }))
+}
+
 pub(crate) fn validate_occlusion_args(fps_adaptive: bool, max_fps: Option<f32>) -> Result<()> {
     if let Some(fps) = max_fps {
         if fps <= 0.0 {
@@ -351,6 +551,8 @@ pub(crate) fn validate_occlusion_args(fps_adaptive: bool, max_fps: Option<f32>)
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
+
     use super::*;
 
     #[test]
@@ -361,6 +563,116 @@ mod tests {
         assert!(resolve_render_scale(Some(1.5)).is_err());
     }
 
+    #[test]
+    fn export_scale_validation() {
+        assert_eq!(resolve_export_scale(None).unwrap(), 1.0);
+        assert_eq!(resolve_export_scale(Some(2.0)).unwrap(), 2.0);
+        assert!(resolve_export_scale(Some(0.0)).is_err());
+        assert!(resolve_export_scale(Some(16.0)).is_err());
+    }
+
+    #[test]
+    fn still_time_accepts_seconds_and_auto() {
+        assert_eq!(parse_still_time_arg(None).unwrap(), None);
+        assert_eq!(
+            parse_still_time_arg(Some("2.5")).unwrap(),
+            Some(StillAt::Seconds(2.5))
+        );
+        assert_eq!(
+            parse_still_time_arg(Some(" AUTO ")).unwrap(),
+            Some(StillAt::Auto)
+        );
+        assert!(parse_still_time_arg(Some("-1")).is_err());
+        assert!(parse_still_time_arg(Some("later")).is_err());
+    }
+
+    #[test]
+    fn dynamic_scale_budget_follows_fps() {
+        let dynamic = resolve_dynamic_scale(&export_args(&["--dynamic-scale", "0.4-1.0"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!((dynamic.min_scale, dynamic.max_scale), (0.4, 1.0));
+        assert_eq!(
+            dynamic.frame_budget,
+            Duration::from_secs_f32(DEFAULT_FRAME_BUDGET)
+        );
+
+        let capped = export_args(&["--dynamic-scale", "0.5-0.9", "--fps", "30"]);
+        let dynamic = resolve_dynamic_scale(&capped).unwrap().unwrap();
+        assert_eq!(dynamic.frame_budget, Duration::from_secs_f32(1.0 / 30.0));
+
+        assert!(resolve_dynamic_scale(&export_args(&["--frame-budget", "16ms"])).is_err());
+        let both = export_args(&["--dynamic-scale", "0.4-1.0", "--render-scale", "0.5"]);
+        assert!(resolve_dynamic_scale(&both).is_err());
+    }
+
+    #[test]
+    fn progressive_requires_plain_scale() {
+        let refine = resolve_progressive(&export_args(&["--still", "--progressive"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            refine,
+            ProgressiveRefine::new(DEFAULT_PROGRESSIVE_START, DEFAULT_PROGRESSIVE_SAMPLES)
+        );
+        let custom = export_args(&["--progressive", "--progressive-start", "0.5"]);
+        assert_eq!(
+            resolve_progressive(&custom).unwrap().unwrap().start_scale,
+            0.5
+        );
+
+        assert!(resolve_progressive(&export_args(&[])).unwrap().is_none());
+        assert!(resolve_progressive(&export_args(&["--progressive-samples", "4"])).is_err());
+        let scaled = export_args(&["--progressive", "--render-scale", "0.5"]);
+        assert!(resolve_progressive(&scaled).is_err());
+        let tiny = export_args(&["--progressive", "--progressive-start", "0.05"]);
+        assert!(resolve_progressive(&tiny).is_err());
+    }
+
+    fn export_args(extra: &[&str]) -> RunArgs {
+        RunArgs::try_parse_from(["wax11"].iter().chain(extra)).unwrap()
+    }
+
+    #[test]
+    fn loop_export_sequence_from_fps_and_duration() {
+        let args = export_args(&[
+            "--loop-fps",
+            "10",
+            "--loop-duration",
+            "2s",
+            "--loop-crossfade",
+            "0.5",
+        ]);
+        let sequence = resolve_export_sequence(&args, ExportFormat::Gif)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sequence.frames, 20);
+        assert_eq!(sequence.crossfade, 5);
+        assert!((sequence.step - 0.1).abs() < 1e-6);
+
+        let defaults = resolve_export_sequence(&export_args(&[]), ExportFormat::Apng)
+            .unwrap()
+            .unwrap();
+        assert_eq!(defaults.frames, 120);
+        assert_eq!(defaults.crossfade, 0);
+    }
+
+    #[test]
+    fn loop_export_flag_validation() {
+        let fade_too_long = export_args(&["--loop-duration", "1s", "--loop-crossfade", "1s"]);
+        assert!(resolve_export_sequence(&fade_too_long, ExportFormat::Gif).is_err());
+        let numbered = export_args(&["--export-sequence", "8"]);
+        assert!(resolve_export_sequence(&numbered, ExportFormat::Gif).is_err());
+        let loop_on_png = export_args(&["--loop-fps", "24"]);
+        assert!(resolve_export_sequence(&loop_on_png, ExportFormat::Png).is_err());
+        assert_eq!(
+            resolve_export_sequence(&numbered, ExportFormat::Png)
+                .unwrap()
+                .map(|sequence| sequence.frames),
+            Some(8)
+        );
+    }
+
     #[test]
     fn occlusion_validation_rules() {
         assert!(validate_occlusion_args(true, Some(10.0)).is_ok());